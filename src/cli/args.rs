@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
@@ -13,7 +13,8 @@ use std::path::PathBuf;
                   INPUT/OUTPUT FILES:\n  \
                     - Most commands take: <INPUT> <OUTPUT> as the last two arguments\n  \
                     - The 'info' command takes only: <INPUT> (no output file)\n  \
-                    - The 'composite' command takes: <BASE> <OVERLAY> <OUTPUT>\n\n\
+                    - The 'composite' command takes: <BASE> <OVERLAY> <OUTPUT>\n  \
+                    - With --in-place, omit <OUTPUT> to edit <INPUT> directly (--backup keeps <INPUT>.bak)\n\n\
                   EXAMPLES:\n  \
                     mdimgedit info photo.png\n  \
                     mdimgedit resize --width 800 input.png output.png\n  \
@@ -40,6 +41,34 @@ pub struct Cli {
     /// Overwrite output file without prompting
     #[arg(short = 'y', long, global = true)]
     pub overwrite: bool,
+
+    /// Ask before replacing an existing output when run at a terminal
+    #[arg(short, long, global = true)]
+    pub interactive: bool,
+
+    /// Edit the input file in place instead of writing a separate OUTPUT (implies --overwrite)
+    #[arg(long, global = true)]
+    pub in_place: bool,
+
+    /// With --in-place, keep a copy of the original as <INPUT>.bak
+    #[arg(long, global = true)]
+    pub backup: bool,
+
+    /// Exit with an error instead of writing output when any warning is raised
+    #[arg(long, global = true)]
+    pub fail_on_warning: bool,
+
+    /// Create missing parent directories of the output path
+    #[arg(long, global = true)]
+    pub create_dirs: bool,
+
+    /// Give the output the input's modification and access times
+    #[arg(long, global = true)]
+    pub preserve_timestamps: bool,
+
+    /// Downscale the result so neither dimension exceeds N pixels (keeps aspect ratio)
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_dimension: Option<u32>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -47,252 +76,356 @@ pub enum Command {
     /// Display image information (dimensions, format, color depth)
     #[command(long_about = "Extract metadata from an image file.\n\n\
                       Returns dimensions, format, color type, bit depth, and file size.\n\
-                      Use --json for machine-parseable output.\n\n\
+                      Use --assume-orientation to also report the dimensions a viewer would\n\
+                      show if the EXIF orientation were N (the file is not changed).\n\
+                      Use --channels to add min/max/mean per channel, in 8-bit RGBA.\n\
+                      Use --json for machine-parseable output, or --output-format csv|tsv for a\n\
+                      header row and a value row to paste into a spreadsheet.\n\n\
                       Examples:\n  \
                         mdimgedit info image.png\n  \
-                        mdimgedit info --json image.png")]
-    Info {
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-    },
+                        mdimgedit info --json image.png\n  \
+                        mdimgedit info --output-format csv image.png\n  \
+                        mdimgedit info --assume-orientation 6 photo.jpg\n  \
+                        mdimgedit info --json --channels image.png")]
+    Info(InfoArgs),
+
+    /// Read the value of a single pixel
+    #[command(long_about = "Report the color of the pixel at (X, Y).\n\n\
+                      Prints RGBA, hex and Rec.601 luma; values are converted to 8-bit.\n\
+                      Use --json for machine-parseable output.\n\n\
+                      Examples:\n  \
+                        mdimgedit pixel --x 10 --y 20 image.png\n  \
+                        mdimgedit pixel --json --x 0 --y 0 image.png")]
+    Pixel(PixelArgs),
+
+    /// Average the color over a rectangle
+    #[command(
+        name = "region-average",
+        long_about = "Report the mean color of a rectangle, for sampling swatches from noisy images.\n\n\
+                      The region is clamped to the image bounds; the clamped size is reported.\n\
+                      Values are converted to 8-bit RGBA before averaging.\n\n\
+                      Examples:\n  \
+                        mdimgedit region-average --x 10 --y 10 --width 20 --height 20 image.png\n  \
+                        mdimgedit region-average --json --x 0 --y 0 --width 5 --height 5 image.png"
+    )]
+    RegionAverage(RegionAverageArgs),
+
+    /// Check that an image file fully decodes
+    #[command(long_about = "Validate an image file by decoding it completely.\n\n\
+                      Reports whether the file is a readable, fully decodable image.\n\
+                      Truncated or corrupt files and unsupported formats are reported as invalid\n\
+                      and exit with a nonzero code (2 = unreadable, 4 = unsupported format).\n\n\
+                      Examples:\n  \
+                        mdimgedit validate image.png\n  \
+                        mdimgedit --json validate upload.jpg")]
+    Validate(ValidateArgs),
+
+    /// Summarize every image in a directory
+    #[command(
+        long_about = "Walk a directory (recursively) and report aggregate statistics without\n\
+                      editing anything: image count by format, total bytes, min/max/mean\n\
+                      dimensions, and how many images have alpha or EXIF metadata.\n\
+                      Files that are not readable images are counted as skipped.\n\n\
+                      Examples:\n  \
+                        mdimgedit audit assets/\n  \
+                        mdimgedit --json audit photos/"
+    )]
+    Audit(AuditArgs),
+
+    /// Print JSON Schemas for the --json response shapes
+    #[command(
+        long_about = "Print JSON Schema (draft 2020-12) documents describing the responses\n\
+                      written with --json: the success response on stdout and the error\n\
+                      response on stderr. The output is always JSON, keyed by type name.\n\n\
+                      Examples:\n  \
+                        mdimgedit schema > mdimgedit-schema.json"
+    )]
+    Schema,
 
     /// Display EXIF metadata from image
     #[command(long_about = "Read and display EXIF metadata from an image file.\n\n\
                       Shows camera information, shooting parameters, date/time, GPS coordinates,\n\
                       and other embedded metadata. Supports JPEG, TIFF, and some RAW formats.\n\n\
                       Use --verbose to show all EXIF fields.\n\
+                      Use --raw to also show each field's value type and the raw hex bytes of\n\
+                      maker notes and unknown tags (implies --verbose).\n\
                       Use --tag to retrieve a specific field.\n\
-                      Use --json for machine-parseable output.\n\n\
+                      Use --json for machine-parseable output, or --output-format csv|tsv for a\n\
+                      flat table with one column per field.\n\n\
                       Examples:\n  \
                         mdimgedit exif photo.jpg\n  \
                         mdimgedit exif --verbose photo.jpg\n  \
+                        mdimgedit exif --raw photo.jpg\n  \
                         mdimgedit exif --tag Make photo.jpg\n  \
                         mdimgedit exif --json photo.jpg")]
-    Exif {
-        /// Show all EXIF fields (verbose output)
-        #[arg(short, long)]
-        verbose: bool,
-        /// Retrieve only this specific tag
-        #[arg(long)]
-        tag: Option<String>,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-    },
+    Exif(ExifArgs),
+
+    /// Print a filename based on the EXIF capture date
+    #[command(
+        name = "suggest-name",
+        long_about = "Print a suggested filename built from the EXIF DateTimeOriginal tag.\n\n\
+                      Nothing is renamed; scripts can use the output to move files.\n\
+                      Pattern tokens: {year} {month} {day} {hour} {min} {sec}; {date:FORMAT} with\n\
+                      %Y %m %d %H %M %S %% specifiers (others are an error); {exif:Tag} for\n\
+                      any EXIF tag value, e.g. {exif:Model} (\"unknown\" when missing). Path\n\
+                      separators in dates and tag values become _. The input's extension is\n\
+                      appended. Files without a capture date use their modification time in\n\
+                      UTC instead, unless --require-exif is given; capture dates are the\n\
+                      camera's local time.\n\n\
+                      Examples:\n  \
+                        mdimgedit suggest-name photo.jpg\n  \
+                        mdimgedit suggest-name --pattern \"{year}/{month}/IMG_{day}{hour}{min}\" photo.jpg\n  \
+                        mdimgedit suggest-name --require-exif --pattern \"{date:%Y%m%d}_{exif:Model}\" photo.jpg"
+    )]
+    SuggestName(SuggestNameArgs),
+
+    /// Set the EXIF orientation tag without touching pixels
+    #[command(
+        long_about = "Rewrite the EXIF Orientation tag of a JPEG without re-encoding it.\n\n\
+                      Viewers that honor EXIF rotate or mirror the image on display; the\n\
+                      compressed pixel data is copied unchanged, so this is lossless.\n\
+                      Values: 1 normal, 2 mirrored, 3 rotated 180, 4 flipped vertically,\n\
+                      5 mirrored + 270 CW, 6 rotated 90 CW, 7 mirrored + 90 CW, 8 rotated 270 CW.\n\n\
+                      Examples:\n  \
+                        mdimgedit set-orientation --orientation 6 photo.jpg rotated.jpg\n  \
+                        mdimgedit set-orientation --orientation 1 -y photo.jpg photo.jpg"
+    )]
+    SetOrientation(SetOrientationArgs),
 
     /// Crop image to specified region
     #[command(long_about = "Extract a rectangular region from the image.\n\n\
                       Specify the region using --x, --y for the starting position and \
                       --width, --height for the size. Use --anchor to position the crop \
-                      region relative to a named point.\n\n\
+                      region relative to a named point. With --pad-if-needed a region reaching \
+                      past the edges is filled with --background instead of failing. With\n\
+                      --keep-palette an indexed PNG stays indexed, with its palette, as PNG output.\n\n\
                       Examples:\n  \
                         mdimgedit crop --width 100 --height 100 input.png output.png\n  \
                         mdimgedit crop --x 50 --y 50 --width 200 --height 200 input.png output.png\n  \
-                        mdimgedit crop --width 500 --height 500 --anchor center input.png output.png")]
-    Crop {
-        /// Left edge X coordinate
-        #[arg(long, default_value = "0")]
-        x: u32,
-        /// Top edge Y coordinate
-        #[arg(long, default_value = "0")]
-        y: u32,
-        /// Width of crop region
-        #[arg(long)]
-        width: u32,
-        /// Height of crop region
-        #[arg(long)]
-        height: u32,
-        /// Anchor point for positioning
-        #[arg(long, value_enum, default_value = "top-left")]
-        anchor: Anchor,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit crop --width 500 --height 500 --anchor center input.png output.png\n  \
+                        mdimgedit crop --width 200 --height 200 --position 50%,25% input.png output.png\n  \
+                        mdimgedit crop --x 960 --y 960 --width 256 --height 256 --pad-if-needed input.png tile.png")]
+    Crop(CropArgs),
+
+    /// Crop a region and resize it to a fixed size in one step
+    #[command(
+        long_about = "Extract a rectangular region and scale it to an exact output size.\n\n\
+                      The source region is given by --x, --y, --width and --height; the result is\n\
+                      resized to --out-width x --out-height without an intermediate file.\n\n\
+                      Examples:\n  \
+                        mdimgedit region --x 25 --y 25 --width 50 --height 50 --out-width 200 --out-height 200 input.png roi.png\n  \
+                        mdimgedit region --width 640 --height 360 --out-width 1280 --out-height 720 --filter cubic input.png roi.png"
+    )]
+    Region(RegionArgs),
 
     /// Rotate image by degrees
     #[command(
         long_about = "Rotate image by specified degrees counter-clockwise.\n\n\
                       For 90, 180, 270 degree rotations, uses lossless pixel remapping.\n\
-                      For arbitrary angles, uses bilinear interpolation.\n\n\
+                      For arbitrary angles, uses bilinear interpolation.\n\
+                      With --auto, estimates the skew of the image's dominant lines\n\
+                      and rotates to straighten them. --keep-palette keeps an indexed PNG\n\
+                      indexed when the rotated pixels still fit its palette.\n\n\
                       Examples:\n  \
                         mdimgedit rotate --degrees 90 input.png output.png\n  \
-                        mdimgedit rotate --degrees 45 --expand --background white input.png output.png"
-    )]
-    Rotate {
-        /// Rotation angle in degrees (counter-clockwise)
-        #[arg(long)]
-        degrees: f64,
-        /// Expand canvas to fit rotated image
-        #[arg(long)]
-        expand: bool,
-        /// Background color for expanded areas
-        #[arg(long, default_value = "transparent")]
-        background: String,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit rotate --degrees 45 --expand --background white input.png output.png\n  \
+                        mdimgedit rotate --auto --max-angle 10 --background white scan.png straight.png\n  \
+                        mdimgedit rotate --degrees 30 --expand --background-image texture.png input.png output.png"
+    )]
+    Rotate(RotateArgs),
+
+    /// Rotate 90 degrees counter-clockwise
+    #[command(
+        long_about = "Rotate the image a quarter turn counter-clockwise (lossless).\n\n\
+                      Shorthand for: rotate --degrees 90\n\n\
+                      Examples:\n  \
+                        mdimgedit rotate-left input.png output.png"
+    )]
+    RotateLeft(RotateLeftArgs),
+
+    /// Rotate 90 degrees clockwise
+    #[command(
+        long_about = "Rotate the image a quarter turn clockwise (lossless).\n\n\
+                      Shorthand for: rotate --degrees -90\n\n\
+                      Examples:\n  \
+                        mdimgedit rotate-right input.png output.png"
+    )]
+    RotateRight(RotateRightArgs),
+
+    /// Rotate 180 degrees
+    #[command(
+        name = "rotate-180",
+        long_about = "Turn the image upside down (lossless).\n\n\
+                      Shorthand for: rotate --degrees 180\n\n\
+                      Examples:\n  \
+                        mdimgedit rotate-180 input.png output.png"
+    )]
+    Rotate180(Rotate180Args),
 
     /// Flip image horizontally or vertically
     #[command(
         long_about = "Mirror image horizontally (left-right) or vertically (top-bottom).\n\n\
                       At least one of --horizontal or --vertical must be specified.\n\
-                      Both can be specified to flip in both directions.\n\n\
+                      Both can be specified to flip in both directions. --keep-palette\n\
+                      writes an indexed PNG input back out with its palette.\n\n\
                       Examples:\n  \
                         mdimgedit flip --horizontal input.png output.png\n  \
+                        mdimgedit flip --horizontal --keep-palette sprite.png mirrored.png\n  \
                         mdimgedit flip --vertical input.png output.png\n  \
                         mdimgedit flip --horizontal --vertical input.png output.png"
     )]
-    Flip {
-        /// Flip horizontally (mirror left-right)
-        #[arg(short = 'H', long)]
-        horizontal: bool,
-        /// Flip vertically (mirror top-bottom)
-        #[arg(short = 'V', long)]
-        vertical: bool,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+    Flip(FlipArgs),
 
     /// Resize image to exact dimensions or scale factor
     #[command(
         long_about = "Resize image to specified dimensions or by a scale factor.\n\n\
                       Specify either dimensions (--width and/or --height) OR --scale, not both.\n\
-                      When only one dimension is given, the other is calculated to preserve aspect ratio.\n\n\
+                      When only one dimension is given, the other is calculated to preserve aspect ratio;\n\
+                      with --keep-other it stays at its original size, stretching the image instead.\n\
+                      Use --pad with both dimensions to letterbox to an exact size without distortion.\n\
+                      --downscale-only / --upscale-only leave the image unchanged if the resize\n\
+                      would go the other way (reported as \"skipped\" in JSON output).\n\
+                      Filters: nearest, linear, cubic, lanczos (Lanczos3), lanczos2, mitchell, area,\n\
+                      auto (nearest for whole-number upscales such as pixel art at 2x, else lanczos).\n\
+                      Give two filters (--filter lanczos,nearest) to resample horizontally with\n\
+                      the first and vertically with the second.\n\
+                      --linear resamples in linear light so fine high-contrast detail does not\n\
+                      darken when downscaled.\n\n\
                       Examples:\n  \
                         mdimgedit resize --width 800 --height 600 input.png output.png\n  \
                         mdimgedit resize --width 800 input.png output.png\n  \
+                        mdimgedit resize --width 1920 --keep-other frame.png anamorphic.png\n  \
                         mdimgedit resize --scale 0.5 input.png output.png\n  \
-                        mdimgedit resize --scale 4 --filter nearest input.png output.png"
-    )]
-    Resize {
-        /// Target width in pixels
-        #[arg(long)]
-        width: Option<u32>,
-        /// Target height in pixels
-        #[arg(long)]
-        height: Option<u32>,
-        /// Scale factor (e.g., 0.5 for half, 2.0 for double)
-        #[arg(long)]
-        scale: Option<f64>,
-        /// Resampling filter
-        #[arg(long, value_enum, default_value = "lanczos")]
-        filter: ResizeFilter,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit resize --scale 4 --filter nearest input.png output.png\n  \
+                        mdimgedit resize --width 1920 --height 540 --filter lanczos,nearest input.png output.png\n  \
+                        mdimgedit resize --width 224 --height 224 --pad --background black input.png output.png\n  \
+                        mdimgedit resize --width 1200 --downscale-only input.png output.png"
+    )]
+    Resize(ResizeArgs),
 
     /// Resize to fit within bounds preserving aspect ratio
     #[command(
         long_about = "Resize image to fit within maximum dimensions while preserving aspect ratio.\n\n\
                       The image is scaled down to fit within the specified bounds.\n\
-                      Use --upscale to allow enlarging smaller images.\n\n\
+                      Use --mode to choose the scaling direction: down (default) never enlarges,\n\
+                      up only enlarges smaller images, both does either. --upscale is\n\
+                      shorthand for --mode both. --linear resamples in linear light.\n\n\
                       Examples:\n  \
                         mdimgedit fit --max-width 800 --max-height 600 input.png output.png\n  \
                         mdimgedit fit --max-width 1024 input.png output.png\n  \
-                        mdimgedit fit --max-width 800 --max-height 600 --upscale input.png output.png"
-    )]
-    Fit {
-        /// Maximum width constraint
-        #[arg(long)]
-        max_width: Option<u32>,
-        /// Maximum height constraint
-        #[arg(long)]
-        max_height: Option<u32>,
-        /// Allow upscaling if image is smaller than bounds
-        #[arg(long)]
-        upscale: bool,
-        /// Resampling filter
-        #[arg(long, value_enum, default_value = "lanczos")]
-        filter: ResizeFilter,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit fit --max-width 800 --max-height 600 --upscale input.png output.png\n  \
+                        mdimgedit fit --max-width 800 --max-height 600 --mode up input.png output.png"
+    )]
+    Fit(FitArgs),
+
+    /// Shrink so the longest side is at most N pixels
+    #[command(
+        long_about = "Scale the image down so neither side exceeds --max pixels, preserving\n\
+                      aspect ratio. Images that already fit are left at their size; this\n\
+                      never enlarges. Equivalent to fit with equal --max-width and --max-height.\n\n\
+                      Examples:\n  \
+                        mdimgedit limit --max 1600 photo.jpg web.jpg\n  \
+                        mdimgedit limit --max 256 --filter area photo.jpg thumb.png"
+    )]
+    Limit(LimitArgs),
+
+    /// Scale to a target box: stretch, fit, cover or pad
+    #[command(
+        long_about = "Scale the image to a --width x --height box, choosing how the aspect ratio\n\
+                      is handled with --mode: exact stretches to the box, fit scales to fit inside\n\
+                      it (the result may be smaller on one side), cover scales to fill it and crops\n\
+                      the overflow around the center, and pad fits inside it and fills the rest\n\
+                      with --background. Equivalent to resize, fit (with --mode both) and\n\
+                      resize --pad, plus cover, under one command.\n\n\
+                      Examples:\n  \
+                        mdimgedit scale --width 256 --height 256 --mode cover photo.jpg thumb.png\n  \
+                        mdimgedit scale --width 1280 --height 720 --mode pad --background black photo.jpg frame.png"
+    )]
+    Scale(ScaleArgs),
+
+    /// Crop around the most detailed region
+    #[command(
+        name = "smart-crop",
+        long_about = "Crop to the given size, positioning the window over the most detailed\n\
+                      region (highest edge density) instead of the geometric center.\n\
+                      Useful for automatic thumbnails; flat images fall back to a center crop.\n\n\
+                      Examples:\n  \
+                        mdimgedit smart-crop --width 300 --height 300 photo.jpg thumb.jpg"
+    )]
+    SmartCrop(SmartCropArgs),
+
+    /// Content-aware resize by removing or inserting seams
+    #[command(
+        long_about = "Resize an image by carving out (or inserting) low-energy seams.\n\n\
+                      Unlike resize, which scales everything uniformly, seam carving removes\n\
+                      paths of pixels through flat regions so prominent content keeps its\n\
+                      shape. Omitted dimensions keep the original size.\n\n\
+                      Examples:\n  \
+                        mdimgedit seam-carve --width 800 input.png output.png\n  \
+                        mdimgedit seam-carve --width 800 --height 400 input.png output.png"
+    )]
+    SeamCarve(SeamCarveArgs),
 
     /// Convert image format
     #[command(long_about = "Convert image between formats.\n\n\
                       Format is auto-detected from output extension if not specified.\n\
                       Use --quality for lossy formats (JPEG, WebP).\n\n\
-                      Supported formats: PNG, JPEG, GIF, BMP, TIFF, WebP, ICO\n\n\
+                      Supported formats: PNG, JPEG, GIF, BMP, TIFF, WebP, ICO\n\
+                      Animated inputs (GIF, APNG, WebP) require --frame to pick a frame.\n\
+                      --optimize shrinks output: JPEG via mozjpeg, PNG losslessly via oxipng\n\
+                      (build features `mozjpeg` and `oxipng`).\n\
+                      --assign-profile tags PNG, JPEG or WebP output with a standard ICC profile\n\
+                      (srgb, display-p3, adobe-rgb); pixel values are not converted.\n\
+                      --dpi records the print resolution (PNG pHYs, JPEG JFIF density, TIFF tags).\n\
+                      --lossless guarantees pixel-exact output: WebP, PNG and the other lossless\n\
+                      formats are accepted, JPEG and GIF are rejected.\n\
+                      --only-if-smaller writes the output only when it is smaller than the input\n\
+                      file (reported as \"skipped_no_gain\" in JSON output).\n\
+                      --low-memory lowers peak memory for very large images: the decoded image is\n\
+                      dropped as soon as it has been converted for the encoder, and output streams\n\
+                      to disk instead of being assembled in memory. The output is identical, but\n\
+                      --optimize and --only-if-smaller (which need the whole file in memory) are\n\
+                      unavailable, and the image itself must still fit in memory once.\n\
+                      --png-color-type forces the PNG color type (auto, rgb, rgba, gray, graya,\n\
+                      palette); rgb and gray drop alpha, palette needs at most 256 distinct colors.\n\n\
                       Examples:\n  \
                         mdimgedit convert input.png output.jpg\n  \
+                        mdimgedit convert --low-memory huge-scan.tiff huge-scan.jpg\n  \
+                        mdimgedit convert --png-color-type rgb opaque.png smaller.png\n  \
+                        mdimgedit convert --optimize --quality 80 input.png output.jpg\n  \
+                        mdimgedit convert --only-if-smaller --optimize --in-place -y photo.png\n  \
                         mdimgedit convert --format webp input.png output.webp\n  \
-                        mdimgedit convert --quality 85 input.png output.jpg")]
-    Convert {
-        /// Target format (auto-detected from extension if not specified)
-        #[arg(long, value_enum)]
-        format: Option<ImageFormat>,
-        /// Quality for lossy formats (1-100)
-        #[arg(long, default_value = "90", value_parser = clap::value_parser!(u8).range(1..=100))]
-        quality: u8,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit convert --quality 85 input.png output.jpg\n  \
+                        mdimgedit convert --frame 3 animation.gif frame3.png\n  \
+                        mdimgedit convert --assign-profile display-p3 render.png tagged.png\n  \
+                        mdimgedit convert --dpi 300 scan.png print.tiff")]
+    Convert(ConvertArgs),
 
     /// Convert to grayscale
     #[command(long_about = "Convert image to grayscale.\n\n\
-                      By default, preserves the alpha channel if present.\n\n\
+                      By default, preserves the alpha channel if present.\n\
+                      --method luma-alpha keeps alpha in a true gray+alpha image instead of gray RGBA.\n\
+                      Luminance uses Rec.601 weights unless --weights R,G,B is given (must sum to 1.0).\n\n\
                       Examples:\n  \
                         mdimgedit grayscale input.png output.png\n  \
-                        mdimgedit grayscale --no-preserve-alpha input.png output.png")]
-    Grayscale {
-        /// Don't preserve alpha channel
-        #[arg(long)]
-        no_preserve_alpha: bool,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit grayscale --no-preserve-alpha input.png output.png\n  \
+                        mdimgedit grayscale --method luma-alpha input.png output.png\n  \
+                        mdimgedit grayscale --weights 0.2126,0.7152,0.0722 input.png output.png")]
+    Grayscale(GrayscaleArgs),
 
     /// Change color bit depth
     #[command(long_about = "Change color bit depth of the image.\n\n\
                       Supported depths: 1 (black/white), 8 (standard), 16 (high precision).\n\
-                      Use --dither when reducing depth to minimize banding.\n\n\
+                      Use --dither when reducing depth to minimize banding; --dither-algo picks\n\
+                      the error diffusion (floyd, atkinson or sierra; floyd by default), and\n\
+                      --dither-strength scales the diffused error for a less noisy result.\n\n\
                       Examples:\n  \
                         mdimgedit depth --bits 1 input.png output.png\n  \
                         mdimgedit depth --bits 1 --dither input.png output.png\n  \
+                        mdimgedit depth --bits 1 --dither --dither-strength 0.5 input.png output.png\n  \
+                        mdimgedit depth --bits 1 --dither --dither-algo atkinson input.png output.png\n  \
                         mdimgedit depth --bits 16 input.png output.png")]
-    Depth {
-        /// Target bit depth per channel (1, 8, or 16)
-        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=16))]
-        bits: u8,
-        /// Apply dithering when reducing depth
-        #[arg(long)]
-        dither: bool,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+    Depth(DepthArgs),
 
     /// Invert image colors
     #[command(long_about = "Invert all color values in the image.\n\n\
@@ -300,17 +433,39 @@ pub enum Command {
                       Examples:\n  \
                         mdimgedit invert input.png output.png\n  \
                         mdimgedit invert --invert-alpha input.png output.png")]
-    Invert {
-        /// Also invert the alpha channel
-        #[arg(long)]
-        invert_alpha: bool,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+    Invert(InvertArgs),
+
+    /// Binarize alpha to fully opaque or fully transparent
+    #[command(
+        name = "alpha-threshold",
+        long_about = "Harden soft alpha, such as the anti-aliased edges of a mask: pixels whose\n\
+                      alpha is at least --value become fully opaque, the rest fully\n\
+                      transparent. Color values are left untouched.\n\n\
+                      Examples:\n  \
+                        mdimgedit alpha-threshold mask.png hard-mask.png\n  \
+                        mdimgedit alpha-threshold --value 200 cutout.png cutout-hard.png"
+    )]
+    AlphaThreshold(AlphaThresholdArgs),
+
+    /// Multiply color by alpha (premultiplied alpha)
+    #[command(
+        long_about = "Convert straight alpha to premultiplied alpha by multiplying each color\n\
+                      channel by alpha, as many renderers and game engines expect. Fully\n\
+                      transparent pixels become transparent black.\n\n\
+                      Examples:\n  \
+                        mdimgedit premultiply sprite.png sprite-pma.png"
+    )]
+    Premultiply(PremultiplyArgs),
+
+    /// Divide color by alpha (straight alpha)
+    #[command(
+        long_about = "Convert premultiplied alpha back to straight alpha by dividing each color\n\
+                      channel by alpha. Fully transparent pixels are left unchanged; precision\n\
+                      lost to premultiplying at low alpha cannot be recovered.\n\n\
+                      Examples:\n  \
+                        mdimgedit unpremultiply render-pma.png render.png"
+    )]
+    Unpremultiply(UnpremultiplyArgs),
 
     /// Adjust brightness
     #[command(long_about = "Adjust image brightness.\n\n\
@@ -319,17 +474,7 @@ pub enum Command {
                       Examples:\n  \
                         mdimgedit brightness --value 50 input.png output.png\n  \
                         mdimgedit brightness --value -30 input.png output.png")]
-    Brightness {
-        /// Brightness adjustment (-255 to 255)
-        #[arg(long, allow_hyphen_values = true)]
-        value: i32,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+    Brightness(BrightnessArgs),
 
     /// Adjust contrast
     #[command(long_about = "Adjust image contrast.\n\n\
@@ -338,17 +483,7 @@ pub enum Command {
                       Examples:\n  \
                         mdimgedit contrast --value 1.5 input.png output.png\n  \
                         mdimgedit contrast --value 0.8 input.png output.png")]
-    Contrast {
-        /// Contrast multiplier (0.0 to 10.0)
-        #[arg(long)]
-        value: f64,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+    Contrast(ContrastArgs),
 
     /// Apply gamma correction
     #[command(long_about = "Apply gamma correction to the image.\n\n\
@@ -357,17 +492,48 @@ pub enum Command {
                       Examples:\n  \
                         mdimgedit gamma --value 0.7 input.png output.png\n  \
                         mdimgedit gamma --value 1.5 input.png output.png")]
-    Gamma {
-        /// Gamma value (0.1 to 10.0)
-        #[arg(long)]
-        value: f64,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+    Gamma(GammaArgs),
+
+    /// Apply several tone and color adjustments in one pass
+    #[command(
+        long_about = "Adjust brightness, contrast, gamma and saturation in a single pass.\n\n\
+                      Adjustments are applied in that order, with the same ranges as the\n\
+                      individual commands. Saturation: 0.0 (grayscale) to 10.0 (1.0 = no change).\n\
+                      Give at least one adjustment.\n\n\
+                      Examples:\n  \
+                        mdimgedit adjust --brightness 20 --contrast 1.2 input.png output.png\n  \
+                        mdimgedit adjust --gamma 0.9 --saturation 1.3 input.png output.png"
+    )]
+    Adjust(AdjustArgs),
+
+    /// Apply tone curves, per channel or to all channels
+    #[command(
+        long_about = "Remap tones through curves given as x,y control points.\n\n\
+                      Each curve lists points as x,y pairs (0-255) separated by ';', with x\n\
+                      increasing; values between points are interpolated linearly. --red, --green\n\
+                      and --blue each adjust only their channel, channels without a curve pass\n\
+                      through, and --all is applied to every channel afterwards. Alpha is kept.\n\n\
+                      Examples:\n  \
+                        mdimgedit curves --all \"0,0;128,160;255,255\" input.png output.png\n  \
+                        mdimgedit curves --blue \"0,0;128,170;255,255\" input.png output.png\n  \
+                        mdimgedit curves --red \"0,20;255,255\" --green \"0,0;255,235\" input.png output.png"
+    )]
+    Curves(CurvesArgs),
+
+    /// Correct a color cast
+    #[command(
+        long_about = "Remove a color cast by scaling the red, green and blue channels.\n\n\
+                      With --gray-x/--gray-y, the pixel at that point is taken to be neutral:\n\
+                      each channel is scaled so it becomes gray at its current mean level.\n\
+                      With --temperature K the photo is corrected from light of that color\n\
+                      temperature (2000-40000; tungsten about 3000, shade about 7500) to daylight\n\
+                      (6500 K). --tint (-100 to 100) reduces green (positive) or magenta (negative).\n\n\
+                      Examples:\n  \
+                        mdimgedit white-balance --gray-x 120 --gray-y 40 photo.jpg fixed.jpg\n  \
+                        mdimgedit white-balance --temperature 3200 indoor.jpg fixed.jpg\n  \
+                        mdimgedit white-balance --temperature 5500 --tint 10 photo.jpg fixed.jpg"
+    )]
+    WhiteBalance(WhiteBalanceArgs),
 
     /// Apply Gaussian blur
     #[command(long_about = "Apply Gaussian blur filter to the image.\n\n\
@@ -376,17 +542,7 @@ pub enum Command {
                       Examples:\n  \
                         mdimgedit blur --radius 2.0 input.png output.png\n  \
                         mdimgedit blur --radius 10.0 input.png output.png")]
-    Blur {
-        /// Blur radius in pixels (0.1 to 100.0)
-        #[arg(long)]
-        radius: f32,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+    Blur(BlurArgs),
 
     /// Apply sharpening filter
     #[command(long_about = "Apply sharpening filter to the image.\n\n\
@@ -394,128 +550,1309 @@ pub enum Command {
                       Examples:\n  \
                         mdimgedit sharpen input.png output.png\n  \
                         mdimgedit sharpen --amount 2.0 input.png output.png")]
-    Sharpen {
-        /// Sharpening strength (0.0 to 10.0)
-        #[arg(long, default_value = "1.0")]
-        amount: f32,
-        /// Effect radius in pixels (0.1 to 10.0)
-        #[arg(long, default_value = "1.0")]
-        radius: f32,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+    Sharpen(SharpenArgs),
+
+    /// Smooth noise while keeping edges sharp
+    #[command(
+        long_about = "Apply an edge-preserving bilateral filter to the image.\n\n\
+                      Each pixel becomes a weighted mean of its neighbourhood, where neighbours\n\
+                      count less the farther away (--spatial-sigma, in pixels) and the more\n\
+                      different in color (--range-sigma, in 0-255 units) they are. Flat regions\n\
+                      and skin are smoothed while edges between distinct colors survive.\n\
+                      Larger spatial sigmas are slow on big images.\n\n\
+                      Examples:\n  \
+                        mdimgedit bilateral portrait.jpg smooth.jpg\n  \
+                        mdimgedit bilateral --spatial-sigma 5 --range-sigma 20 noisy.png clean.png"
+    )]
+    Bilateral(BilateralArgs),
 
     /// Add padding/border around image
     #[command(long_about = "Add padding or border around the image.\n\n\
                       Specify padding with --all (all sides), --horizontal/--vertical, \
-                      or individual --top/--bottom/--left/--right.\n\n\
+                      or individual --top/--bottom/--left/--right.\n\
+                      --top-color/--bottom-color/--left-color/--right-color override --color per edge;\n\
+                      top and bottom colors fill the corners.\n\n\
                       Color formats: named (red, blue), hex (#RGB, #RRGGBB), rgb(R,G,B), rgba(R,G,B,A)\n\n\
                       Examples:\n  \
                         mdimgedit pad --all 10 input.png output.png\n  \
                         mdimgedit pad --horizontal 20 --vertical 10 input.png output.png\n  \
                         mdimgedit pad --all 5 --color red input.png output.png\n  \
-                        mdimgedit pad --all 10 --color \"#FF5500\" input.png output.png")]
-    Pad {
-        /// Padding on all sides
-        #[arg(long)]
-        all: Option<u32>,
-        /// Top padding
-        #[arg(long)]
-        top: Option<u32>,
-        /// Bottom padding
-        #[arg(long)]
-        bottom: Option<u32>,
-        /// Left padding
-        #[arg(long)]
-        left: Option<u32>,
-        /// Right padding
-        #[arg(long)]
-        right: Option<u32>,
-        /// Horizontal (left and right) padding
-        #[arg(long)]
-        horizontal: Option<u32>,
-        /// Vertical (top and bottom) padding
-        #[arg(long)]
-        vertical: Option<u32>,
-        /// Padding color
-        #[arg(long, default_value = "transparent")]
-        color: String,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit pad --all 10 --color \"#FF5500\" input.png output.png\n  \
+                        mdimgedit pad --all 8 --color white --bottom-color black input.png output.png")]
+    Pad(PadArgs),
+
+    /// Add a decorative two-tone border
+    #[command(
+        long_about = "Frame the image with an outer border and an optional inner stripe.\n\n\
+                      The canvas grows by the combined width on every side: --width pixels of\n\
+                      --color on the outside, then --inner-width pixels of --inner-color next to\n\
+                      the image. Use --inner-width 0 for a single-tone border.\n\n\
+                      Examples:\n  \
+                        mdimgedit frame --width 20 input.png output.png\n  \
+                        mdimgedit frame --width 20 --color \"#333\" --inner-width 4 --inner-color white input.png output.png"
+    )]
+    Frame(FrameArgs),
+
+    /// Add a drop shadow behind the image
+    #[command(long_about = "Render a blurred drop shadow behind the image.\n\n\
+                      The canvas grows to fit the shadow at the given offset plus --blur pixels\n\
+                      of falloff; the surround is transparent, so the output is RGBA.\n\
+                      Negative offsets place the shadow up or to the left.\n\n\
+                      Examples:\n  \
+                        mdimgedit shadow input.png output.png\n  \
+                        mdimgedit shadow --offset-x 0 --offset-y 12 --blur 20 --color \"#00000080\" input.png output.png")]
+    Shadow(ShadowArgs),
+
+    /// Preview transparency over a checkerboard
+    #[command(
+        long_about = "Flatten the image over a white and light gray checkerboard.\n\n\
+                      Produces an opaque preview of transparent areas, as image editors show them.\n\
+                      --size sets the width of each checker cell in pixels.\n\n\
+                      Examples:\n  \
+                        mdimgedit checkerboard logo.png preview.png\n  \
+                        mdimgedit checkerboard --size 16 logo.png preview.jpg"
+    )]
+    Checkerboard(CheckerboardArgs),
+
+    /// Overlay alignment guides
+    #[command(
+        long_about = "Draw a grid of 1-pixel guide lines over the image, for checking alignment.\n\n\
+                      Lines run every --spacing pixels from the top-left edge. --color may be\n\
+                      translucent (#RRGGBBAA) so the content stays visible beneath the lines.\n\n\
+                      Examples:\n  \
+                        mdimgedit grid layout.png guides.png\n  \
+                        mdimgedit grid --spacing 16 --color \"#00FFFF60\" sprite.png guides.png"
+    )]
+    Grid(GridArgs),
+
+    /// Make a contiguous region of similar color transparent
+    #[command(
+        name = "flood-remove",
+        long_about = "Flood-fill from a seed pixel and make the filled region transparent.\n\n\
+                      Pixels within --tolerance of the seed color (largest per-channel difference)\n\
+                      that are 4-connected to the seed are cleared, like a magic wand. Similar\n\
+                      colors elsewhere in the image are kept. Output is RGBA.\n\n\
+                      Examples:\n  \
+                        mdimgedit flood-remove input.png output.png\n  \
+                        mdimgedit flood-remove --x 10 --y 10 --tolerance 30 input.png output.png"
+    )]
+    FloodRemove(FloodRemoveArgs),
 
     /// Resize canvas without scaling content
     #[command(long_about = "Resize the canvas without scaling image content.\n\n\
                       If new canvas is larger, original image is positioned according to --anchor.\n\
-                      If smaller, image is cropped from the anchor point.\n\n\
+                      If smaller, image is cropped from the anchor point; --no-crop makes that an\n\
+                      error instead.\n\
+                      With --scale-content the image is first scaled (up or down, keeping its\n\
+                      aspect ratio) to fit the canvas, letterboxing instead of cropping.\n\n\
                       Examples:\n  \
                         mdimgedit canvas --width 1000 --height 1000 input.png output.png\n  \
                         mdimgedit canvas --width 1000 --height 1000 --anchor top-left input.png output.png\n  \
-                        mdimgedit canvas --width 500 --height 500 --anchor center input.png output.png")]
-    Canvas {
-        /// New canvas width
-        #[arg(long)]
-        width: u32,
-        /// New canvas height
-        #[arg(long)]
-        height: u32,
-        /// Position of original image on new canvas
-        #[arg(long, value_enum, default_value = "center")]
-        anchor: Anchor,
-        /// Background color for new canvas areas
-        #[arg(long, default_value = "transparent")]
-        color: String,
-        /// Input image file
-        #[arg(value_name = "INPUT")]
-        input: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit canvas --width 500 --height 500 --anchor center input.png output.png\n  \
+                        mdimgedit canvas --width 1000 --height 1000 --background-image texture.png input.png output.png\n  \
+                        mdimgedit canvas --width 1080 --height 1920 --scale-content --color black input.png output.png")]
+    Canvas(CanvasArgs),
+
+    /// Draw a text label or timestamp onto an image
+    #[command(
+        long_about = "Draw text onto an image using a built-in 8x8 bitmap font.\n\n\
+                      Use --text for a fixed label, or --annotate-timestamp to stamp the EXIF\n\
+                      capture time (DateTime/DateTimeOriginal), falling back to the current\n\
+                      UTC time when the input has none. --time-format accepts %Y %m %d %H %M %S.\n\n\
+                      Examples:\n  \
+                        mdimgedit annotate --text \"Draft\" --anchor top-left input.png output.png\n  \
+                        mdimgedit annotate --annotate-timestamp photo.jpg stamped.jpg\n  \
+                        mdimgedit annotate --annotate-timestamp --time-format \"%d/%m/%Y\" --color yellow photo.jpg stamped.jpg"
+    )]
+    Annotate(AnnotateArgs),
 
     /// Overlay one image onto another
     #[command(long_about = "Composite (overlay) one image onto a base image.\n\n\
                       Position the overlay using --x/--y or --anchor.\n\
-                      Control transparency with --opacity and blend mode with --blend.\n\n\
+                      Control transparency with --opacity and blend mode with --blend.\n\
+                      Use --chroma-key to make overlay pixels near a color transparent first.\n\
+                      --guides draws a grid over the result (see the grid command) to check placement.\n\n\
                       Examples:\n  \
                         mdimgedit composite base.png overlay.png output.png\n  \
+                        mdimgedit composite --guides --grid-spacing 25 --x 50 --y 50 base.png overlay.png output.png\n  \
                         mdimgedit composite --x 100 --y 50 base.png overlay.png output.png\n  \
                         mdimgedit composite --anchor center base.png overlay.png output.png\n  \
-                        mdimgedit composite --opacity 0.5 base.png overlay.png output.png")]
-    Composite {
-        /// X position of overlay
-        #[arg(long)]
-        x: Option<i32>,
-        /// Y position of overlay
-        #[arg(long)]
-        y: Option<i32>,
-        /// Anchor point for positioning
-        #[arg(long, value_enum)]
-        anchor: Option<Anchor>,
-        /// Overlay opacity (0.0 to 1.0)
-        #[arg(long, default_value = "1.0")]
-        opacity: f32,
-        /// Blend mode
-        #[arg(long, value_enum, default_value = "normal")]
-        blend: BlendMode,
-        /// Base image file
-        #[arg(value_name = "BASE")]
-        base: PathBuf,
-        /// Overlay image file
-        #[arg(value_name = "OVERLAY")]
-        overlay: PathBuf,
-        /// Output image file
-        #[arg(value_name = "OUTPUT")]
-        output: PathBuf,
-    },
+                        mdimgedit composite --position bottom-right-10-10 base.png overlay.png output.png\n  \
+                        mdimgedit composite --opacity 0.5 base.png overlay.png output.png\n  \
+                        mdimgedit composite --chroma-key #00FF00 --chroma-tolerance 60 base.png greenscreen.png output.png")]
+    Composite(CompositeArgs),
+
+    /// Wash a linear gradient over the image
+    #[command(
+        long_about = "Composite a linear gradient over the whole image, for tints and fades.\n\n\
+                      The gradient runs from --start-color to --end-color along --angle degrees\n\
+                      (0 = left to right, 90 = top to bottom). Either color may be transparent,\n\
+                      fading the wash out. --opacity and --blend work as in composite.\n\n\
+                      Examples:\n  \
+                        mdimgedit gradient-overlay --start-color black --end-color transparent --angle 90 photo.png faded.png\n  \
+                        mdimgedit gradient-overlay --start-color \"#FF8800\" --end-color \"#0044FF\" --opacity 0.3 --blend overlay photo.png tinted.png"
+    )]
+    GradientOverlay(GradientOverlayArgs),
+
+    /// Create a new image filled with a color or gradient
+    #[command(
+        long_about = "Create a new image of the given size, filled with a solid --color or with a\n\
+                      two-stop linear --gradient given as start,end,angle (angle in degrees:\n\
+                      0 = left to right, 90 = top to bottom). No input file is read.\n\n\
+                      Examples:\n  \
+                        mdimgedit generate --width 100 --height 50 --color red -o red.png\n  \
+                        mdimgedit generate --width 800 --height 200 --gradient \"#FF8800,#0044FF,0\" -o banner.png"
+    )]
+    Generate(GenerateArgs),
+
+    /// Cut a circular avatar out of the image
+    #[command(
+        long_about = "Keep only the largest circle centered in the image, making everything\n\
+                      outside it transparent with anti-aliased edges. --crop cuts the result to\n\
+                      the circle's square bounding box; otherwise the size is unchanged.\n\
+                      --border-width paints a ring of --border-color just inside the edge.\n\n\
+                      Examples:\n  \
+                        mdimgedit circle --crop photo.jpg avatar.png\n  \
+                        mdimgedit circle --crop --border-width 4 --border-color white photo.jpg avatar.png"
+    )]
+    Circle(CircleArgs),
+
+    /// Lay out labelled thumbnails of many images on one sheet
+    #[command(
+        name = "contact-sheet",
+        long_about = "Arrange thumbnails of several images in a grid, each labelled beneath with\n\
+                      its file name (and optionally its dimensions), for reviewing assets at a\n\
+                      glance. Images are shrunk to fit a --cell-size square, never enlarged.\n\
+                      Give images as arguments and/or every image in --input-dir.\n\n\
+                      Examples:\n  \
+                        mdimgedit contact-sheet -o sheet.png a.png b.png c.png\n  \
+                        mdimgedit contact-sheet --input-dir assets --columns 6 --label name-size -o sheet.png"
+    )]
+    ContactSheet(ContactSheetArgs),
+
+    /// Run a list of commands from a JSON manifest
+    #[command(
+        long_about = "Run each job in a JSON manifest as if it were its own mdimgedit command line,\n\
+                      then report how each one went. The manifest is an array of objects with a\n\
+                      \"command\" name, its \"args\" (options, as on the command line), and the\n\
+                      \"input\" and \"output\" paths, which are appended in that order. Paths are\n\
+                      relative to the current directory. Only commands that write a file can be\n\
+                      jobs. Global options such as --overwrite apply to every job. A failed job\n\
+                      does not stop the ones after it; the exit code is that of the first failure.\n\n\
+                      Example manifest:\n  \
+                        [{\"command\": \"resize\", \"args\": [\"--width\", \"800\"],\n   \
+                          \"input\": \"a.png\", \"output\": \"a-small.png\"},\n   \
+                         {\"command\": \"grayscale\", \"input\": \"b.png\", \"output\": \"b-gray.png\"}]\n\n\
+                      Examples:\n  \
+                        mdimgedit batch --manifest jobs.json\n  \
+                        mdimgedit --json --overwrite batch --manifest jobs.json"
+    )]
+    Batch(BatchArgs),
+
+    /// Apply a recipe file of operations in one pass
+    #[command(
+        long_about = "Apply the operations listed in a JSON recipe file, in order, saving once.\n\n\
+                      The recipe is an object whose \"operations\" array holds one object per step,\n\
+                      named by \"op\" with the same parameters as the matching command:\n\
+                      grayscale, invert, resize, fit, crop, rotate, flip, pad, brightness,\n\
+                      contrast, gamma, saturation, blur, sharpen. Unknown operations or\n\
+                      parameters are rejected before the image is read.\n\n\
+                      Example recipe:\n  \
+                        {\"operations\": [{\"op\": \"grayscale\"},\n                  \
+                                         {\"op\": \"resize\", \"width\": 800},\n                  \
+                                         {\"op\": \"pad\", \"all\": 20, \"color\": \"white\"}]}\n\n\
+                      Examples:\n  \
+                        mdimgedit apply --recipe thumbnail.json input.png output.png"
+    )]
+    Apply(ApplyArgs),
+}
+
+// Each subcommand's arguments get their own struct so clap builds them in a
+// function of their own; with every field inline in `Command`, debug builds
+// assembled all of them in one stack frame of several megabytes.
+
+#[derive(Args, Debug)]
+pub struct InfoArgs {
+    /// Report display dimensions as if the EXIF orientation were N (1-8)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..=8))]
+    pub assume_orientation: Option<u16>,
+    /// Also report the min, max and mean of each RGBA channel (decodes every pixel)
+    #[arg(long)]
+    pub channels: bool,
+    /// Output as text, json, or a csv/tsv table for spreadsheets (default: text, or json with --json)
+    #[arg(long, value_enum)]
+    pub output_format: Option<ReportFormat>,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct PixelArgs {
+    /// X coordinate (0 = left edge)
+    #[arg(long)]
+    pub x: u32,
+    /// Y coordinate (0 = top edge)
+    #[arg(long)]
+    pub y: u32,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct RegionAverageArgs {
+    /// Left edge X coordinate
+    #[arg(long)]
+    pub x: u32,
+    /// Top edge Y coordinate
+    #[arg(long)]
+    pub y: u32,
+    /// Width of the region
+    #[arg(long)]
+    pub width: u32,
+    /// Height of the region
+    #[arg(long)]
+    pub height: u32,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct ValidateArgs {
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct AuditArgs {
+    /// Directory to scan
+    #[arg(value_name = "DIR")]
+    pub dir: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct ExifArgs {
+    /// Show all EXIF fields (verbose output)
+    #[arg(short, long)]
+    pub verbose: bool,
+    /// Include value types and raw bytes of maker notes and unknown tags
+    #[arg(long)]
+    pub raw: bool,
+    /// Retrieve only this specific tag
+    #[arg(long)]
+    pub tag: Option<String>,
+    /// Output as text, json, or a csv/tsv table for spreadsheets (default: text, or json with --json)
+    #[arg(long, value_enum)]
+    pub output_format: Option<ReportFormat>,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct SuggestNameArgs {
+    /// Filename pattern
+    #[arg(long, default_value = "{year}{month}{day}_{hour}{min}{sec}")]
+    pub pattern: String,
+    /// Fail instead of using the file modification time when there is no capture date
+    #[arg(long)]
+    pub require_exif: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct SetOrientationArgs {
+    /// EXIF orientation value (1-8)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..=8))]
+    pub orientation: u16,
+    /// Input JPEG file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output JPEG file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct CropArgs {
+    /// Left edge X coordinate
+    #[arg(long, default_value = "0")]
+    pub x: u32,
+    /// Top edge Y coordinate
+    #[arg(long, default_value = "0")]
+    pub y: u32,
+    /// Width of crop region
+    #[arg(long)]
+    pub width: u32,
+    /// Height of crop region
+    #[arg(long)]
+    pub height: u32,
+    /// Anchor point for positioning
+    #[arg(long, value_enum, default_value = "top-left")]
+    pub anchor: Anchor,
+    /// Position as anchor+offset (center+0-20), pixels (50,50) or percentages (50%,50%)
+    #[arg(long, conflicts_with_all = ["x", "y", "anchor"])]
+    pub position: Option<String>,
+    /// Fill the part of the region outside the image instead of failing
+    #[arg(long)]
+    pub pad_if_needed: bool,
+    /// Fill color for --pad-if-needed
+    #[arg(long, default_value = "transparent", requires = "pad_if_needed")]
+    pub background: String,
+    /// Write an indexed PNG input's palette back out instead of expanding to RGBA
+    #[arg(long)]
+    pub keep_palette: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct RegionArgs {
+    /// Left edge X coordinate of the source region
+    #[arg(long, default_value = "0")]
+    pub x: u32,
+    /// Top edge Y coordinate of the source region
+    #[arg(long, default_value = "0")]
+    pub y: u32,
+    /// Width of the source region
+    #[arg(long)]
+    pub width: u32,
+    /// Height of the source region
+    #[arg(long)]
+    pub height: u32,
+    /// Output width in pixels
+    #[arg(long)]
+    pub out_width: u32,
+    /// Output height in pixels
+    #[arg(long)]
+    pub out_height: u32,
+    /// Resampling filter
+    #[arg(long, value_enum, default_value = "lanczos")]
+    pub filter: ResizeFilter,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct RotateArgs {
+    /// Rotation angle in degrees (counter-clockwise)
+    #[arg(long, required_unless_present = "auto", conflicts_with = "auto")]
+    pub degrees: Option<f64>,
+    /// Automatically detect and correct skew
+    #[arg(long)]
+    pub auto: bool,
+    /// Largest skew correction considered by --auto, in degrees
+    #[arg(long, default_value = "15.0")]
+    pub max_angle: f64,
+    /// Expand canvas to fit rotated image
+    #[arg(long)]
+    pub expand: bool,
+    /// Background color for expanded areas
+    #[arg(long, default_value = "transparent")]
+    pub background: String,
+    /// Tile this image into expanded areas instead of a solid color
+    #[arg(long, value_name = "PATH", conflicts_with = "background")]
+    pub background_image: Option<PathBuf>,
+    /// Write an indexed PNG input's palette back out instead of expanding to RGBA
+    #[arg(long)]
+    pub keep_palette: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct RotateLeftArgs {
+    /// Write an indexed PNG input's palette back out instead of expanding to RGBA
+    #[arg(long)]
+    pub keep_palette: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct RotateRightArgs {
+    /// Write an indexed PNG input's palette back out instead of expanding to RGBA
+    #[arg(long)]
+    pub keep_palette: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct Rotate180Args {
+    /// Write an indexed PNG input's palette back out instead of expanding to RGBA
+    #[arg(long)]
+    pub keep_palette: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct FlipArgs {
+    /// Flip horizontally (mirror left-right)
+    #[arg(short = 'H', long)]
+    pub horizontal: bool,
+    /// Flip vertically (mirror top-bottom)
+    #[arg(short = 'V', long)]
+    pub vertical: bool,
+    /// Write an indexed PNG input's palette back out instead of expanding to RGBA
+    #[arg(long)]
+    pub keep_palette: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ResizeArgs {
+    /// Target width in pixels
+    #[arg(long)]
+    pub width: Option<u32>,
+    /// Target height in pixels
+    #[arg(long)]
+    pub height: Option<u32>,
+    /// Scale factor (e.g., 0.5 for half, 2.0 for double)
+    #[arg(long)]
+    pub scale: Option<f64>,
+    /// Keep the dimension that is not given at its original size (stretch)
+    #[arg(long, conflicts_with = "scale")]
+    pub keep_other: bool,
+    /// Resampling filter, or HORIZONTAL,VERTICAL filters for each axis
+    #[arg(long, value_enum, default_value = "lanczos", value_delimiter = ',')]
+    pub filter: Vec<ResizeFilter>,
+    /// Custom Lanczos window size (2 = sharper, 3 = default Lanczos3)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(2..=3), conflicts_with = "pad")]
+    pub lanczos_a: Option<u8>,
+    /// Fit within --width x --height and pad to exactly that size instead of stretching
+    #[arg(long, requires_all = ["width", "height"], conflicts_with = "scale")]
+    pub pad: bool,
+    /// Background color for padded areas (used with --pad)
+    #[arg(long, default_value = "transparent")]
+    pub background: String,
+    /// Leave the image unchanged if the resize would enlarge it
+    #[arg(long, conflicts_with = "upscale_only")]
+    pub downscale_only: bool,
+    /// Leave the image unchanged if the resize would shrink it
+    #[arg(long)]
+    pub upscale_only: bool,
+    /// Resample in linear light instead of on sRGB-encoded values
+    #[arg(long, conflicts_with = "pad")]
+    pub linear: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct FitArgs {
+    /// Maximum width constraint
+    #[arg(long)]
+    pub max_width: Option<u32>,
+    /// Maximum height constraint
+    #[arg(long)]
+    pub max_height: Option<u32>,
+    /// Allow upscaling if image is smaller than bounds (same as --mode both)
+    #[arg(long, conflicts_with = "mode")]
+    pub upscale: bool,
+    /// Scaling direction: down (never enlarge), up (only enlarge), or both
+    #[arg(long, value_enum)]
+    pub mode: Option<FitMode>,
+    /// Resampling filter
+    #[arg(long, value_enum, default_value = "lanczos")]
+    pub filter: ResizeFilter,
+    /// Resample in linear light instead of on sRGB-encoded values
+    #[arg(long)]
+    pub linear: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct LimitArgs {
+    /// Longest allowed side in pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub max: u32,
+    /// Resampling filter
+    #[arg(long, value_enum, default_value = "lanczos")]
+    pub filter: ResizeFilter,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ScaleArgs {
+    /// Target width in pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: u32,
+    /// Target height in pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: u32,
+    /// How to handle an aspect ratio that differs from the target's
+    #[arg(long, value_enum, default_value = "fit")]
+    pub mode: ScaleMode,
+    /// Resampling filter
+    #[arg(long, value_enum, default_value = "lanczos")]
+    pub filter: ResizeFilter,
+    /// Background color for padded areas (used with --mode pad)
+    #[arg(long, default_value = "transparent")]
+    pub background: String,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct SmartCropArgs {
+    /// Width of crop region
+    #[arg(long)]
+    pub width: u32,
+    /// Height of crop region
+    #[arg(long)]
+    pub height: u32,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct SeamCarveArgs {
+    /// Target width in pixels
+    #[arg(long, required_unless_present = "height")]
+    pub width: Option<u32>,
+    /// Target height in pixels
+    #[arg(long)]
+    pub height: Option<u32>,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConvertArgs {
+    /// Target format (auto-detected from extension if not specified)
+    #[arg(long, value_enum)]
+    pub format: Option<ImageFormat>,
+    /// Quality for lossy formats (1-100)
+    #[arg(long, default_value = "90", value_parser = clap::value_parser!(u8).range(1..=100))]
+    pub quality: u8,
+    /// Frame to extract from an animated input (0-based)
+    #[arg(long)]
+    pub frame: Option<usize>,
+    /// Produce smaller JPEG (mozjpeg) or PNG (oxipng) files; requires the matching build feature
+    #[arg(long)]
+    pub optimize: bool,
+    /// Embed an ICC profile for this color space without changing pixel values
+    #[arg(long, value_enum, value_name = "PROFILE")]
+    pub assign_profile: Option<ColorProfile>,
+    /// Record this print resolution in the output metadata (PNG, JPEG, TIFF)
+    #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+    pub dpi: Option<u16>,
+    /// Require a pixel-exact encoding; fails for JPEG and GIF output
+    #[arg(long, conflicts_with = "quality")]
+    pub lossless: bool,
+    /// Only write the output if it is smaller than the input file
+    #[arg(long)]
+    pub only_if_smaller: bool,
+    /// Stream the output to disk and free the decoded image early
+    #[arg(long, conflicts_with_all = ["optimize", "only_if_smaller"])]
+    pub low_memory: bool,
+    /// Color type of PNG output
+    #[arg(long, value_enum, default_value = "auto", value_name = "TYPE")]
+    pub png_color_type: PngColorType,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct GrayscaleArgs {
+    /// Don't preserve alpha channel
+    #[arg(long)]
+    pub no_preserve_alpha: bool,
+    /// Pixel layout of the result when alpha is preserved
+    #[arg(
+        long,
+        value_enum,
+        default_value = "rgba",
+        conflicts_with = "no_preserve_alpha"
+    )]
+    pub method: GrayscaleMethod,
+    /// Custom luminance weights as R,G,B (default: Rec.601 0.299,0.587,0.114)
+    #[arg(long, value_name = "R,G,B")]
+    pub weights: Option<String>,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct DepthArgs {
+    /// Target bit depth per channel (1, 8, or 16)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=16))]
+    pub bits: u8,
+    /// Apply dithering when reducing depth
+    #[arg(long)]
+    pub dither: bool,
+    /// Error-diffusion algorithm for --dither
+    #[arg(long, value_enum, requires = "dither")]
+    pub dither_algo: Option<DitherAlgorithm>,
+    /// Fraction of the error to diffuse: 0.0 thresholds, 1.0 dithers fully
+    #[arg(long, default_value = "1.0", requires = "dither")]
+    pub dither_strength: f64,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct InvertArgs {
+    /// Also invert the alpha channel
+    #[arg(long)]
+    pub invert_alpha: bool,
+    /// Process 8-bit PNG to PNG in strips of rows; other files are processed whole
+    #[arg(long)]
+    pub low_memory: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct AlphaThresholdArgs {
+    /// Alpha at or above which a pixel becomes opaque (0 to 255)
+    #[arg(long, default_value = "128")]
+    pub value: u8,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct PremultiplyArgs {
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct UnpremultiplyArgs {
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct BrightnessArgs {
+    /// Brightness adjustment (-255 to 255)
+    #[arg(long, allow_hyphen_values = true)]
+    pub value: i32,
+    /// Report how many channel values were clipped to 0 or 255
+    #[arg(long)]
+    pub clip_warn: bool,
+    /// Process 8-bit PNG to PNG in strips of rows; other files are processed whole
+    #[arg(long, conflicts_with = "clip_warn")]
+    pub low_memory: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ContrastArgs {
+    /// Contrast multiplier (0.0 to 10.0)
+    #[arg(long)]
+    pub value: f64,
+    /// Report how many channel values were clipped to 0 or 255
+    #[arg(long)]
+    pub clip_warn: bool,
+    /// Process 8-bit PNG to PNG in strips of rows; other files are processed whole
+    #[arg(long, conflicts_with = "clip_warn")]
+    pub low_memory: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct GammaArgs {
+    /// Gamma value (0.1 to 10.0)
+    #[arg(long)]
+    pub value: f64,
+    /// Process 8-bit PNG to PNG in strips of rows; other files are processed whole
+    #[arg(long)]
+    pub low_memory: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct AdjustArgs {
+    /// Brightness adjustment (-255 to 255)
+    #[arg(
+        long,
+        allow_hyphen_values = true,
+        required_unless_present_any = ["contrast", "gamma", "saturation"]
+    )]
+    pub brightness: Option<i32>,
+    /// Contrast multiplier (0.0 to 10.0)
+    #[arg(long)]
+    pub contrast: Option<f64>,
+    /// Gamma value (0.1 to 10.0)
+    #[arg(long)]
+    pub gamma: Option<f64>,
+    /// Saturation multiplier (0.0 to 10.0)
+    #[arg(long)]
+    pub saturation: Option<f64>,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct CurvesArgs {
+    /// Curve applied to all color channels
+    #[arg(long, value_name = "POINTS", required_unless_present_any = ["red", "green", "blue"])]
+    pub all: Option<String>,
+    /// Curve for the red channel
+    #[arg(long, value_name = "POINTS")]
+    pub red: Option<String>,
+    /// Curve for the green channel
+    #[arg(long, value_name = "POINTS")]
+    pub green: Option<String>,
+    /// Curve for the blue channel
+    #[arg(long, value_name = "POINTS")]
+    pub blue: Option<String>,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct WhiteBalanceArgs {
+    /// X coordinate of a pixel that should be neutral gray
+    #[arg(
+        long,
+        requires = "gray_y",
+        required_unless_present_any = ["temperature", "tint"],
+        conflicts_with_all = ["temperature", "tint"]
+    )]
+    pub gray_x: Option<u32>,
+    /// Y coordinate of a pixel that should be neutral gray
+    #[arg(long, requires = "gray_x")]
+    pub gray_y: Option<u32>,
+    /// Color temperature of the light the image was taken under, in kelvin
+    #[arg(long)]
+    pub temperature: Option<f64>,
+    /// Green-magenta correction (-100 to 100)
+    #[arg(long, allow_hyphen_values = true)]
+    pub tint: Option<f64>,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct BlurArgs {
+    /// Blur radius in pixels (0.1 to 100.0)
+    #[arg(long)]
+    pub radius: f32,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct SharpenArgs {
+    /// Sharpening strength (0.0 to 10.0)
+    #[arg(long, default_value = "1.0")]
+    pub amount: f32,
+    /// Effect radius in pixels (0.1 to 10.0)
+    #[arg(long, default_value = "1.0")]
+    pub radius: f32,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct BilateralArgs {
+    /// Neighbourhood spread in pixels (0.1 to 20.0)
+    #[arg(long, default_value = "3.0")]
+    pub spatial_sigma: f32,
+    /// Color difference tolerance in 0-255 units (0.1 to 255.0)
+    #[arg(long, default_value = "30.0")]
+    pub range_sigma: f32,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct PadArgs {
+    /// Padding on all sides
+    #[arg(long)]
+    pub all: Option<u32>,
+    /// Top padding
+    #[arg(long)]
+    pub top: Option<u32>,
+    /// Bottom padding
+    #[arg(long)]
+    pub bottom: Option<u32>,
+    /// Left padding
+    #[arg(long)]
+    pub left: Option<u32>,
+    /// Right padding
+    #[arg(long)]
+    pub right: Option<u32>,
+    /// Horizontal (left and right) padding
+    #[arg(long)]
+    pub horizontal: Option<u32>,
+    /// Vertical (top and bottom) padding
+    #[arg(long)]
+    pub vertical: Option<u32>,
+    /// Padding color
+    #[arg(long, default_value = "transparent")]
+    pub color: String,
+    /// Top border color (default: --color)
+    #[arg(long)]
+    pub top_color: Option<String>,
+    /// Bottom border color (default: --color)
+    #[arg(long)]
+    pub bottom_color: Option<String>,
+    /// Left border color (default: --color)
+    #[arg(long)]
+    pub left_color: Option<String>,
+    /// Right border color (default: --color)
+    #[arg(long)]
+    pub right_color: Option<String>,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct FrameArgs {
+    /// Outer border width in pixels
+    #[arg(long)]
+    pub width: u32,
+    /// Outer border color
+    #[arg(long, default_value = "black")]
+    pub color: String,
+    /// Inner stripe width in pixels (0 for none)
+    #[arg(long, default_value = "0")]
+    pub inner_width: u32,
+    /// Inner stripe color
+    #[arg(long, default_value = "white")]
+    pub inner_color: String,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ShadowArgs {
+    /// Horizontal shadow offset in pixels
+    #[arg(long, default_value = "8", allow_hyphen_values = true)]
+    pub offset_x: i32,
+    /// Vertical shadow offset in pixels
+    #[arg(long, default_value = "8", allow_hyphen_values = true)]
+    pub offset_y: i32,
+    /// Shadow blur radius in pixels (0.0 to 100.0)
+    #[arg(long, default_value = "8.0")]
+    pub blur: f32,
+    /// Shadow color; its alpha sets the shadow strength
+    #[arg(long, default_value = "#00000099")]
+    pub color: String,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct CheckerboardArgs {
+    /// Checker cell size in pixels
+    #[arg(long, default_value = "8", value_parser = clap::value_parser!(u32).range(1..))]
+    pub size: u32,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct GridArgs {
+    /// Distance between grid lines in pixels
+    #[arg(long, default_value = "50", value_parser = clap::value_parser!(u32).range(1..))]
+    pub spacing: u32,
+    /// Line color
+    #[arg(long, default_value = "#FF00FF80")]
+    pub color: String,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct FloodRemoveArgs {
+    /// Seed X coordinate
+    #[arg(long, default_value = "0")]
+    pub x: u32,
+    /// Seed Y coordinate
+    #[arg(long, default_value = "0")]
+    pub y: u32,
+    /// Largest per-channel difference from the seed color that is removed (0-255)
+    #[arg(long, default_value = "16")]
+    pub tolerance: u8,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct CanvasArgs {
+    /// New canvas width
+    #[arg(long)]
+    pub width: u32,
+    /// New canvas height
+    #[arg(long)]
+    pub height: u32,
+    /// Position of original image on new canvas
+    #[arg(long, value_enum, default_value = "center")]
+    pub anchor: Anchor,
+    /// Background color for new canvas areas
+    #[arg(long, default_value = "transparent")]
+    pub color: String,
+    /// Tile this image into new canvas areas instead of a solid color
+    #[arg(long, value_name = "PATH", conflicts_with = "color")]
+    pub background_image: Option<PathBuf>,
+    /// Scale the image to fit within the canvas (keeping aspect ratio) before placing it
+    #[arg(long)]
+    pub scale_content: bool,
+    /// Fail instead of cropping when the canvas is smaller than the image
+    #[arg(long)]
+    pub no_crop: bool,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct AnnotateArgs {
+    /// Text to draw
+    #[arg(
+        long,
+        required_unless_present = "timestamp",
+        conflicts_with = "timestamp"
+    )]
+    pub text: Option<String>,
+    /// Draw the capture time from EXIF (or the current time if absent)
+    #[arg(long = "annotate-timestamp", alias = "timestamp")]
+    pub timestamp: bool,
+    /// Timestamp format (strftime-style: %Y %m %d %H %M %S)
+    #[arg(long, default_value = "%Y-%m-%d %H:%M:%S")]
+    pub time_format: String,
+    /// Position of the text
+    #[arg(long, value_enum, default_value = "bottom-right")]
+    pub anchor: Anchor,
+    /// Distance from the anchored edges in pixels
+    #[arg(long, default_value = "10")]
+    pub margin: u32,
+    /// Font scale (each font pixel becomes scale x scale pixels, 1-32)
+    #[arg(long, default_value = "2")]
+    pub scale: u32,
+    /// Text color
+    #[arg(long, default_value = "white")]
+    pub color: String,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct CompositeArgs {
+    /// X position of overlay
+    #[arg(long)]
+    pub x: Option<i32>,
+    /// Y position of overlay
+    #[arg(long)]
+    pub y: Option<i32>,
+    /// Anchor point for positioning
+    #[arg(long, value_enum)]
+    pub anchor: Option<Anchor>,
+    /// Position as anchor+offset (bottom-right-10-10), pixels (100,50) or percentages (50%,50%)
+    #[arg(long, conflicts_with_all = ["x", "y", "anchor"])]
+    pub position: Option<String>,
+    /// Overlay opacity (0.0 to 1.0)
+    #[arg(long, default_value = "1.0")]
+    pub opacity: f32,
+    /// Blend mode
+    #[arg(long, value_enum, default_value = "normal")]
+    pub blend: BlendMode,
+    /// Key color to make transparent in the overlay before blending
+    #[arg(long)]
+    pub chroma_key: Option<String>,
+    /// Largest per-channel difference from the key color that is keyed out (0-255)
+    #[arg(long, default_value = "40")]
+    pub chroma_tolerance: u8,
+    /// Also write the chroma key mask (white = keyed out) as a grayscale image
+    #[arg(long, value_name = "PATH", requires = "chroma_key")]
+    pub mask_output: Option<PathBuf>,
+    /// Soften the keyed-out edges by blurring the overlay alpha (pixels)
+    #[arg(long, requires = "chroma_key")]
+    pub feather: Option<f32>,
+    /// Draw a guide grid over the composited result
+    #[arg(long)]
+    pub guides: bool,
+    /// Distance between guide lines in pixels
+    #[arg(long, default_value = "50", requires = "guides", value_parser = clap::value_parser!(u32).range(1..))]
+    pub grid_spacing: u32,
+    /// Guide line color
+    #[arg(long, default_value = "#FF00FF80", requires = "guides")]
+    pub grid_color: String,
+    /// Base image file
+    #[arg(value_name = "BASE")]
+    pub base: PathBuf,
+    /// Overlay image file
+    #[arg(value_name = "OVERLAY")]
+    pub overlay: PathBuf,
+    /// Output image file
+    #[arg(value_name = "OUTPUT")]
+    pub output: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct GradientOverlayArgs {
+    /// Color at the start of the gradient
+    #[arg(long, default_value = "black")]
+    pub start_color: String,
+    /// Color at the end of the gradient
+    #[arg(long, default_value = "transparent")]
+    pub end_color: String,
+    /// Direction of the gradient in degrees (0 = left to right, 90 = top to bottom)
+    #[arg(long, default_value = "90", allow_hyphen_values = true)]
+    pub angle: f64,
+    /// Gradient opacity (0.0 to 1.0)
+    #[arg(long, default_value = "1.0")]
+    pub opacity: f32,
+    /// Blend mode
+    #[arg(long, value_enum, default_value = "normal")]
+    pub blend: BlendMode,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct GenerateArgs {
+    /// Image width in pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub width: u32,
+    /// Image height in pixels
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub height: u32,
+    /// Fill color
+    #[arg(long, default_value = "black", conflicts_with = "gradient")]
+    pub color: String,
+    /// Fill with a linear gradient instead: start,end,angle
+    #[arg(long, value_name = "START,END,ANGLE", allow_hyphen_values = true)]
+    pub gradient: Option<String>,
+    /// Output image file
+    #[arg(short, long, value_name = "OUTPUT")]
+    pub output: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct CircleArgs {
+    /// Crop to the square around the circle
+    #[arg(long)]
+    pub crop: bool,
+    /// Width of a ring drawn inside the edge, in pixels (0 = none)
+    #[arg(long, default_value = "0")]
+    pub border_width: u32,
+    /// Ring color
+    #[arg(long, default_value = "white")]
+    pub border_color: String,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct ContactSheetArgs {
+    /// Images to include
+    #[arg(value_name = "INPUT", required_unless_present = "input_dir")]
+    pub inputs: Vec<PathBuf>,
+    /// Also include every image file in this directory (in name order)
+    #[arg(long, value_name = "DIR")]
+    pub input_dir: Option<PathBuf>,
+    /// Thumbnails per row
+    #[arg(long, default_value = "4", value_parser = clap::value_parser!(u32).range(1..))]
+    pub columns: u32,
+    /// Size of the square each thumbnail is fitted into, in pixels
+    #[arg(long, default_value = "160", value_parser = clap::value_parser!(u32).range(8..))]
+    pub cell_size: u32,
+    /// Text drawn under each thumbnail
+    #[arg(long, value_enum, default_value = "name")]
+    pub label: SheetLabel,
+    /// Sheet background color
+    #[arg(long, default_value = "white")]
+    pub background: String,
+    /// Label text color
+    #[arg(long, default_value = "black")]
+    pub label_color: String,
+    /// Output image file
+    #[arg(short, long, value_name = "OUTPUT")]
+    pub output: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct BatchArgs {
+    /// Manifest file (JSON)
+    #[arg(long, value_name = "PATH")]
+    pub manifest: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct ApplyArgs {
+    /// Recipe file (JSON)
+    #[arg(long, value_name = "PATH")]
+    pub recipe: PathBuf,
+    /// Input image file
+    #[arg(value_name = "INPUT")]
+    pub input: PathBuf,
+    /// Output image file (omit with --in-place)
+    #[arg(value_name = "OUTPUT")]
+    pub output: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
@@ -546,19 +1883,123 @@ pub enum ResizeFilter {
     Linear,
     Cubic,
     Lanczos,
+    /// Two-lobe Lanczos: sharper than Lanczos3 with less ringing
+    Lanczos2,
+    /// Mitchell-Netravali cubic (B = C = 1/3): soft, with little ringing
+    Mitchell,
+    /// Area averaging: each output pixel is the mean of the source pixels it
+    /// covers; clean and fast for large downscales
+    Area,
+    /// Nearest for whole-number upscales (keeps pixel art crisp), Lanczos3 otherwise
+    Auto,
 }
 
 impl ResizeFilter {
+    /// The `image` crate filter for this choice. Lanczos2, Mitchell and Area
+    /// have no built-in equivalent and map to the closest one here; the resize
+    /// operations resample them with their own kernels instead. Auto maps to
+    /// Lanczos3; the resize operations pick its filter from the dimensions.
     pub fn to_image_filter(self) -> image::imageops::FilterType {
         match self {
             ResizeFilter::Nearest => image::imageops::FilterType::Nearest,
-            ResizeFilter::Linear => image::imageops::FilterType::Triangle,
-            ResizeFilter::Cubic => image::imageops::FilterType::CatmullRom,
-            ResizeFilter::Lanczos => image::imageops::FilterType::Lanczos3,
+            ResizeFilter::Linear | ResizeFilter::Area => image::imageops::FilterType::Triangle,
+            ResizeFilter::Cubic | ResizeFilter::Mitchell => image::imageops::FilterType::CatmullRom,
+            ResizeFilter::Lanczos | ResizeFilter::Lanczos2 | ResizeFilter::Auto => {
+                image::imageops::FilterType::Lanczos3
+            }
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum FitMode {
+    /// Scale up or down to fill the bounds
+    Both,
+    /// Only shrink images larger than the bounds
+    Down,
+    /// Only enlarge images smaller than the bounds
+    Up,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Human-readable text
+    Text,
+    /// The same JSON as --json
+    Json,
+    /// A header row and a value row, comma-separated
+    Csv,
+    /// A header row and a value row, tab-separated
+    Tsv,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum ScaleMode {
+    /// Stretch to exactly the target size, distorting the aspect ratio
+    Exact,
+    /// Scale to fit within the target, keeping the aspect ratio
+    Fit,
+    /// Scale to fill the target, cropping the overflow around the center
+    Cover,
+    /// Fit within the target, then pad to exactly its size
+    Pad,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum GrayscaleMethod {
+    /// Gray values in all three color channels, plus alpha
+    Rgba,
+    /// A single luma channel plus alpha
+    LumaAlpha,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum DitherAlgorithm {
+    /// Floyd-Steinberg: diffuses all of the error to four neighbors
+    Floyd,
+    /// Atkinson: diffuses 3/4 of the error, for higher contrast
+    Atkinson,
+    /// Sierra: diffuses all of the error over three rows, for smoother tones
+    Sierra,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum SheetLabel {
+    /// No labels
+    None,
+    /// File name
+    Name,
+    /// File name and dimensions
+    NameSize,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq, Default)]
+pub enum PngColorType {
+    /// Keep the image's own layout
+    #[default]
+    Auto,
+    /// RGB, dropping any alpha
+    Rgb,
+    /// RGB with alpha
+    Rgba,
+    /// Single gray channel, dropping any alpha
+    Gray,
+    /// Gray with alpha
+    Graya,
+    /// Indexed color with up to 256 palette entries (with transparency)
+    Palette,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum ColorProfile {
+    /// sRGB (IEC 61966-2-1)
+    Srgb,
+    /// Display P3 (DCI-P3 primaries, D65 white, sRGB curve)
+    DisplayP3,
+    /// Adobe RGB (1998)
+    AdobeRgb,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
 pub enum ImageFormat {
     Png,
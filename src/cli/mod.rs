@@ -1,5 +1,7 @@
 pub mod args;
 pub mod output;
+pub mod position;
 
 pub use args::{Cli, Command};
 pub use output::OutputFormat;
+pub use position::parse_position;
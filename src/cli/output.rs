@@ -1,4 +1,5 @@
-use crate::error::ImgEditError;
+use crate::cli::args::ReportFormat;
+use crate::error::{ImgEditError, Warning};
 use serde::Serialize;
 use std::collections::HashMap;
 
@@ -18,6 +19,12 @@ pub struct SuccessResponse {
     pub output: Option<String>,
     #[serde(skip_serializing_if = "HashMap::is_empty")]
     pub details: HashMap<String, serde_json::Value>,
+    /// The operation's resolved parameters (after anchors, defaults and color
+    /// parsing are applied), so callers can confirm exactly what ran
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub parameters: HashMap<String, serde_json::Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<Warning>,
 }
 
 impl SuccessResponse {
@@ -28,6 +35,8 @@ impl SuccessResponse {
             input: None,
             output: None,
             details: HashMap::new(),
+            parameters: HashMap::new(),
+            warnings: Vec::new(),
         }
     }
 
@@ -46,9 +55,90 @@ impl SuccessResponse {
         self
     }
 
+    pub fn with_parameter<V: Into<serde_json::Value>>(mut self, key: &str, value: V) -> Self {
+        self.parameters.insert(key.to_string(), value.into());
+        self
+    }
+
+    pub fn with_warning(mut self, warning: Warning) -> Self {
+        self.warnings.push(warning);
+        self
+    }
+
     pub fn to_json(&self) -> String {
         serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
     }
+
+    /// A header row and a value row separated by `delimiter`: the input, then
+    /// the details in key order. Nested objects and arrays are flattened into
+    /// dotted columns such as `fields.0.tag`.
+    pub fn to_delimited(&self, delimiter: char) -> String {
+        let mut columns = Vec::new();
+        if let Some(input) = &self.input {
+            columns.push(("input".to_string(), input.clone()));
+        }
+        let mut details: Vec<_> = self.details.iter().collect();
+        details.sort_by_key(|(key, _)| *key);
+        for (key, value) in details {
+            flatten_value(key, value, &mut columns);
+        }
+
+        let row = |cells: Vec<&str>| {
+            cells
+                .into_iter()
+                .map(|cell| delimited_cell(cell, delimiter))
+                .collect::<Vec<_>>()
+                .join(&delimiter.to_string())
+        };
+        format!(
+            "{}\n{}",
+            row(columns.iter().map(|(k, _)| k.as_str()).collect()),
+            row(columns.iter().map(|(_, v)| v.as_str()).collect())
+        )
+    }
+}
+
+fn flatten_value(key: &str, value: &serde_json::Value, columns: &mut Vec<(String, String)>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(k, _)| *k);
+            for (k, v) in entries {
+                flatten_value(&format!("{}.{}", key, k), v, columns);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                flatten_value(&format!("{}.{}", key, i), v, columns);
+            }
+        }
+        serde_json::Value::Null => columns.push((key.to_string(), String::new())),
+        serde_json::Value::String(text) => columns.push((key.to_string(), text.clone())),
+        other => columns.push((key.to_string(), other.to_string())),
+    }
+}
+
+/// Quote a CSV cell that needs it; TSV cannot quote, so tabs and line breaks
+/// become spaces
+fn delimited_cell(cell: &str, delimiter: char) -> String {
+    if delimiter == '\t' {
+        cell.replace(['\t', '\n', '\r'], " ")
+    } else if cell.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Print a reporting command's response as JSON or a delimited table; text
+/// output is left to the caller
+pub fn print_report(response: &SuccessResponse, format: ReportFormat) {
+    match format {
+        ReportFormat::Json => println!("{}", response.to_json()),
+        ReportFormat::Csv => println!("{}", response.to_delimited(',')),
+        ReportFormat::Tsv => println!("{}", response.to_delimited('\t')),
+        ReportFormat::Text => {}
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -57,6 +147,9 @@ pub struct ErrorResponse {
     pub command: String,
     pub error: String,
     pub code: String,
+    /// The argument whose value caused the error, when known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
 }
 
 impl ErrorResponse {
@@ -66,6 +159,7 @@ impl ErrorResponse {
             command: command.to_string(),
             error: err.to_string(),
             code: err.code().to_string(),
+            field: err.field().map(str::to_string),
         }
     }
 
@@ -74,6 +168,76 @@ impl ErrorResponse {
     }
 }
 
+const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// JSON Schema for [`Warning`] entries
+fn warning_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "code": { "type": "string" },
+            "message": { "type": "string" }
+        },
+        "required": ["code", "message"],
+        "additionalProperties": false
+    })
+}
+
+/// JSON Schema for the [`SuccessResponse`] printed to stdout with `--json`.
+/// Keys absent from a response are simply unset; `details` and `parameters`
+/// vary by command.
+pub fn success_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": "SuccessResponse",
+        "type": "object",
+        "properties": {
+            "success": {
+                "type": "boolean",
+                "description": "False only when validate finds an invalid file or batch jobs fail"
+            },
+            "command": { "type": "string" },
+            "input": { "type": "string" },
+            "output": { "type": "string" },
+            "details": {
+                "type": "object",
+                "description": "Command-specific results, such as result_width"
+            },
+            "parameters": {
+                "type": "object",
+                "description": "The resolved parameters the command ran with"
+            },
+            "warnings": { "type": "array", "items": warning_schema() }
+        },
+        "required": ["success", "command"],
+        "additionalProperties": false
+    })
+}
+
+/// JSON Schema for the [`ErrorResponse`] printed to stderr with `--json`
+pub fn error_schema() -> serde_json::Value {
+    serde_json::json!({
+        "$schema": SCHEMA_DIALECT,
+        "title": "ErrorResponse",
+        "type": "object",
+        "properties": {
+            "success": { "const": false },
+            "command": { "type": "string" },
+            "error": { "type": "string", "description": "Human-readable message" },
+            "code": {
+                "type": "string",
+                "description": "Stable error code, e.g. INPUT_NOT_FOUND"
+            },
+            "field": {
+                "type": "string",
+                "description": "The argument whose value caused the error"
+            }
+        },
+        "required": ["success", "command", "error", "code"],
+        "additionalProperties": false
+    })
+}
+
 /// Print success output in the appropriate format
 pub fn print_success(format: OutputFormat, response: &SuccessResponse, quiet: bool) {
     match format {
@@ -88,6 +252,13 @@ pub fn print_success(format: OutputFormat, response: &SuccessResponse, quiet: bo
     }
 }
 
+/// Print warnings for text output (JSON output carries them in the response)
+pub fn print_warnings(warnings: &[Warning]) {
+    for warning in warnings {
+        eprintln!("Warning: {}", warning.message);
+    }
+}
+
 /// Print error output in the appropriate format
 pub fn print_error(format: OutputFormat, command: &str, err: &ImgEditError) {
     match format {
@@ -155,6 +326,17 @@ mod tests {
         let json = response.to_json();
         assert!(json.contains("\"success\": false"));
         assert!(json.contains("\"code\": \"INVALID_COLOR\""));
+        assert!(!json.contains("\"field\""));
+    }
+
+    #[test]
+    fn test_error_response_field() {
+        let err = ImgEditError::InvalidColor("bad".to_string()).for_field("background");
+        let response = ErrorResponse::new("rotate", &err);
+
+        assert_eq!(response.field.as_deref(), Some("background"));
+        assert_eq!(response.code, "INVALID_COLOR");
+        assert!(response.to_json().contains("\"field\": \"background\""));
     }
 
     #[test]
@@ -202,6 +384,35 @@ mod tests {
         assert!(!json.contains("details"));
     }
 
+    #[test]
+    fn test_success_response_warnings() {
+        let response = SuccessResponse::new("resize")
+            .with_warning(Warning::new("LARGE_UPSCALE", "upscaled 10x".to_string()));
+        let json: serde_json::Value = serde_json::from_str(&response.to_json()).unwrap();
+        assert_eq!(json["warnings"][0]["code"], "LARGE_UPSCALE");
+        assert_eq!(json["warnings"][0]["message"], "upscaled 10x");
+
+        // No warnings should not be serialized
+        assert!(!SuccessResponse::new("resize")
+            .to_json()
+            .contains("warnings"));
+    }
+
+    #[test]
+    fn test_success_response_parameters() {
+        let response = SuccessResponse::new("crop")
+            .with_parameter("x", 25)
+            .with_parameter("anchor", "center");
+        let json: serde_json::Value = serde_json::from_str(&response.to_json()).unwrap();
+        assert_eq!(json["parameters"]["x"], 25);
+        assert_eq!(json["parameters"]["anchor"], "center");
+
+        // No parameters should not be serialized
+        assert!(!SuccessResponse::new("crop")
+            .to_json()
+            .contains("parameters"));
+    }
+
     #[test]
     fn test_success_response_no_input_output() {
         let response = SuccessResponse::new("test");
@@ -210,4 +421,53 @@ mod tests {
         assert!(!json.contains("input"));
         assert!(!json.contains("output"));
     }
+
+    #[test]
+    fn test_to_delimited_flattens_details() {
+        let response = SuccessResponse::new("exif")
+            .with_input("a,b.jpg")
+            .with_detail("width", 32)
+            .with_detail("make", None::<String>)
+            .with_detail(
+                "fields",
+                serde_json::json!([{"tag": "Make", "value": "Acme\tCo"}]),
+            );
+
+        assert_eq!(
+            response.to_delimited(','),
+            "input,fields.0.tag,fields.0.value,make,width\n\"a,b.jpg\",Make,Acme\tCo,,32"
+        );
+        assert_eq!(
+            response.to_delimited('\t'),
+            "input\tfields.0.tag\tfields.0.value\tmake\twidth\na,b.jpg\tMake\tAcme Co\t\t32"
+        );
+    }
+
+    #[test]
+    fn test_schemas_cover_serialized_fields() {
+        let success = serde_json::to_value(
+            SuccessResponse::new("resize")
+                .with_input("in.png")
+                .with_output("out.png")
+                .with_detail("result_width", 10)
+                .with_parameter("filter", "lanczos")
+                .with_warning(Warning::new("UPSCALE", "big".to_string())),
+        )
+        .unwrap();
+        let error = serde_json::to_value(ErrorResponse::new(
+            "pad",
+            &ImgEditError::InvalidColor("bad".to_string()).for_field("color"),
+        ))
+        .unwrap();
+
+        for (value, schema) in [(success, success_schema()), (error, error_schema())] {
+            let properties = schema["properties"].as_object().unwrap();
+            for key in value.as_object().unwrap().keys() {
+                assert!(properties.contains_key(key), "{} missing from schema", key);
+            }
+            for key in schema["required"].as_array().unwrap() {
+                assert!(value.get(key.as_str().unwrap()).is_some(), "{}", key);
+            }
+        }
+    }
 }
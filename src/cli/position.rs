@@ -0,0 +1,164 @@
+use crate::cli::args::Anchor;
+use crate::error::{ImgEditError, Result};
+use crate::ops::canvas::calculate_anchor_offset;
+use clap::ValueEnum;
+
+/// Parse a position string into the top-left coordinates of an `item`-sized
+/// region placed inside a `container`-sized one.
+///
+/// Supported formats:
+/// - Named anchor: `center`, `top-right`, ...
+/// - Named anchor with offset: `top-right+10-10` (x offset, then y offset)
+/// - Absolute pixels: `100,200`
+/// - Percentages: `50%,50%`, where 0% aligns the item with the left/top edge
+///   and 100% with the right/bottom edge
+/// - Mixed: `25%,40`
+pub fn parse_position(spec: &str, container: (u32, u32), item: (u32, u32)) -> Result<(i32, i32)> {
+    let s = spec.trim().to_lowercase();
+
+    if let Some((x, y)) = s.split_once(',') {
+        let x = parse_coordinate(x, container.0, item.0, spec)?;
+        let y = parse_coordinate(y, container.1, item.1, spec)?;
+        return Ok((x, y));
+    }
+
+    // Longest match first so `center-left` is not read as `center` + offset
+    let mut anchors: Vec<(String, Anchor)> = Anchor::value_variants()
+        .iter()
+        .filter_map(|a| {
+            a.to_possible_value()
+                .map(|v| (v.get_name().to_string(), *a))
+        })
+        .collect();
+    anchors.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+    for (name, anchor) in anchors {
+        if let Some(rest) = s.strip_prefix(&name) {
+            let (dx, dy) = parse_offset(rest, spec)?;
+            let (ax, ay) =
+                calculate_anchor_offset(container.0, container.1, item.0, item.1, anchor);
+            return Ok((ax as i32 + dx, ay as i32 + dy));
+        }
+    }
+
+    Err(invalid_position(spec))
+}
+
+fn parse_coordinate(value: &str, container: u32, item: u32, spec: &str) -> Result<i32> {
+    let value = value.trim();
+
+    if let Some(percent) = value.strip_suffix('%') {
+        let percent: f64 = percent.trim().parse().map_err(|_| invalid_position(spec))?;
+        let free = container as f64 - item as f64;
+        return Ok((free * percent / 100.0).round() as i32);
+    }
+
+    value.parse().map_err(|_| invalid_position(spec))
+}
+
+/// Parse an optional `+X-Y` style offset suffix
+fn parse_offset(rest: &str, spec: &str) -> Result<(i32, i32)> {
+    if rest.is_empty() {
+        return Ok((0, 0));
+    }
+
+    // Split before the second sign: "+10-10" -> "+10", "-10"
+    let split = rest
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == '+' || *c == '-')
+        .map(|(i, _)| i)
+        .ok_or_else(|| invalid_position(spec))?;
+    let (dx, dy) = rest.split_at(split);
+
+    if !dx.starts_with(['+', '-']) {
+        return Err(invalid_position(spec));
+    }
+
+    let dx = dx.parse().map_err(|_| invalid_position(spec))?;
+    let dy = dy.parse().map_err(|_| invalid_position(spec))?;
+    Ok((dx, dy))
+}
+
+fn invalid_position(spec: &str) -> ImgEditError {
+    ImgEditError::InvalidParameter(format!(
+        "Invalid position '{}': expected an anchor (e.g. top-right+10-10), \
+         X,Y pixels, or X%,Y% percentages",
+        spec
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_anchor() {
+        assert_eq!(
+            parse_position("center", (100, 100), (20, 20)).unwrap(),
+            (40, 40)
+        );
+        assert_eq!(
+            parse_position("top-left", (100, 100), (20, 20)).unwrap(),
+            (0, 0)
+        );
+        assert_eq!(
+            parse_position("Bottom-Right", (100, 80), (20, 20)).unwrap(),
+            (80, 60)
+        );
+    }
+
+    #[test]
+    fn test_named_anchor_with_offset() {
+        assert_eq!(
+            parse_position("top-right+10-10", (100, 100), (20, 20)).unwrap(),
+            (90, -10)
+        );
+        assert_eq!(
+            parse_position("bottom-right-10-10", (100, 100), (20, 20)).unwrap(),
+            (70, 70)
+        );
+        assert_eq!(
+            parse_position("center-left+5+0", (100, 100), (20, 20)).unwrap(),
+            (5, 40)
+        );
+    }
+
+    #[test]
+    fn test_percentage_coordinates() {
+        assert_eq!(
+            parse_position("50%,50%", (100, 100), (20, 20)).unwrap(),
+            (40, 40)
+        );
+        assert_eq!(
+            parse_position("0%,100%", (100, 100), (20, 20)).unwrap(),
+            (0, 80)
+        );
+        assert_eq!(
+            parse_position("25%, 40", (100, 100), (20, 20)).unwrap(),
+            (20, 40)
+        );
+    }
+
+    #[test]
+    fn test_absolute_pixels() {
+        assert_eq!(
+            parse_position("100,200", (500, 500), (20, 20)).unwrap(),
+            (100, 200)
+        );
+        assert_eq!(
+            parse_position("-5,7", (500, 500), (20, 20)).unwrap(),
+            (-5, 7)
+        );
+    }
+
+    #[test]
+    fn test_invalid_positions() {
+        assert!(parse_position("middle", (100, 100), (20, 20)).is_err());
+        assert!(parse_position("center+10", (100, 100), (20, 20)).is_err());
+        assert!(parse_position("centerx", (100, 100), (20, 20)).is_err());
+        assert!(parse_position("centeré+1+1", (100, 100), (20, 20)).is_err());
+        assert!(parse_position("10,abc", (100, 100), (20, 20)).is_err());
+        assert!(parse_position("a%,5", (100, 100), (20, 20)).is_err());
+    }
+}
@@ -1,6 +1,16 @@
 use crate::error::{ImgEditError, Result};
 use image::Rgba;
 
+/// Format a color as `#RRGGBB`, or `#RRGGBBAA` when it is not fully opaque
+pub fn color_to_hex(color: Rgba<u8>) -> String {
+    let [r, g, b, a] = color.0;
+    let mut hex = format!("#{:02X}{:02X}{:02X}", r, g, b);
+    if a != 255 {
+        hex.push_str(&format!("{:02X}", a));
+    }
+    hex
+}
+
 /// Parse a color string into an RGBA value.
 ///
 /// Supported formats:
@@ -148,6 +158,13 @@ fn parse_color_component(s: &str) -> Result<u8> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_color_to_hex() {
+        assert_eq!(color_to_hex(Rgba([255, 0, 16, 255])), "#FF0010");
+        assert_eq!(color_to_hex(Rgba([0, 0, 0, 0])), "#00000000");
+        assert_eq!(color_to_hex(parse_color("#12345678").unwrap()), "#12345678");
+    }
+
     #[test]
     fn test_named_colors() {
         assert_eq!(parse_color("black").unwrap(), Rgba([0, 0, 0, 255]));
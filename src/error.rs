@@ -1,3 +1,4 @@
+use serde::Serialize;
 use thiserror::Error;
 
 /// Exit codes for the application
@@ -44,6 +45,17 @@ pub enum ImgEditError {
 
     #[error("Image processing error: {0}")]
     ImageError(#[from] image::ImageError),
+
+    /// A warning raised while `--fail-on-warning` is set
+    #[error("Warning treated as error: {}", .0.message)]
+    WarningAsError(Warning),
+
+    /// Another error caused by the value of a specific command-line argument
+    #[error("{source}")]
+    Argument {
+        field: String,
+        source: Box<ImgEditError>,
+    },
 }
 
 impl ImgEditError {
@@ -61,6 +73,8 @@ impl ImgEditError {
             ImgEditError::MissingOption(_) => "MISSING_OPTION",
             ImgEditError::IoError(_) => "IO_ERROR",
             ImgEditError::ImageError(_) => "IMAGE_ERROR",
+            ImgEditError::WarningAsError(_) => "WARNING_AS_ERROR",
+            ImgEditError::Argument { source, .. } => source.code(),
         }
     }
 
@@ -76,13 +90,51 @@ impl ImgEditError {
             | ImgEditError::InvalidColor(_)
             | ImgEditError::InvalidParameter(_)
             | ImgEditError::MissingOption(_) => exit_codes::INVALID_PARAMETERS,
-            ImgEditError::IoError(_) | ImgEditError::ImageError(_) => exit_codes::GENERAL_ERROR,
+            ImgEditError::IoError(_)
+            | ImgEditError::ImageError(_)
+            | ImgEditError::WarningAsError(_) => exit_codes::GENERAL_ERROR,
+            ImgEditError::Argument { source, .. } => source.exit_code(),
+        }
+    }
+
+    /// Attribute this error to the argument `field` (e.g. `background`)
+    pub fn for_field(self, field: &str) -> Self {
+        match self {
+            ImgEditError::Argument { .. } => self,
+            other => ImgEditError::Argument {
+                field: field.to_string(),
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// The argument this error was attributed to, if any
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            ImgEditError::Argument { field, .. } => Some(field),
+            _ => None,
         }
     }
 }
 
 pub type Result<T> = std::result::Result<T, ImgEditError>;
 
+/// An advisory, non-fatal diagnostic attached to a successful operation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    pub code: String,
+    pub message: String,
+}
+
+impl Warning {
+    pub fn new(code: &str, message: String) -> Self {
+        Self {
+            code: code.to_string(),
+            message,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -155,4 +207,32 @@ mod tests {
             "Operation requires at least one option: foo"
         );
     }
+
+    #[test]
+    fn test_warning_as_error() {
+        let err = ImgEditError::WarningAsError(Warning::new(
+            "ALPHA_DISCARDED",
+            "transparency lost".to_string(),
+        ));
+        assert_eq!(err.code(), "WARNING_AS_ERROR");
+        assert_eq!(err.exit_code(), exit_codes::GENERAL_ERROR);
+        assert_eq!(
+            err.to_string(),
+            "Warning treated as error: transparency lost"
+        );
+    }
+
+    #[test]
+    fn test_for_field_keeps_inner_error() {
+        let err = ImgEditError::InvalidColor("bad".to_string()).for_field("background");
+        assert_eq!(err.field(), Some("background"));
+        assert_eq!(err.code(), "INVALID_COLOR");
+        assert_eq!(err.exit_code(), exit_codes::INVALID_PARAMETERS);
+        assert_eq!(err.to_string(), "Invalid color specification: bad");
+
+        // The innermost attribution wins
+        let err = err.for_field("color");
+        assert_eq!(err.field(), Some("background"));
+        assert_eq!(ImgEditError::MissingOption("x".into()).field(), None);
+    }
 }
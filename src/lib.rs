@@ -4,5 +4,5 @@ pub mod error;
 pub mod ops;
 
 pub use cli::{Cli, Command, OutputFormat};
-pub use color::parse_color;
-pub use error::{ImgEditError, Result};
+pub use color::{color_to_hex, parse_color};
+pub use error::{ImgEditError, Result, Warning};
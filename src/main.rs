@@ -1,9 +1,22 @@
-use clap::Parser;
-use mdimgedit::cli::output::{print_error, OutputFormat, SuccessResponse};
-use mdimgedit::cli::{Cli, Command};
-use mdimgedit::error::{exit_codes, ImgEditError};
+use clap::{Parser, ValueEnum};
+use mdimgedit::cli::args::{
+    AdjustArgs, AlphaThresholdArgs, Anchor, AnnotateArgs, ApplyArgs, AuditArgs, BatchArgs,
+    BilateralArgs, BlurArgs, BrightnessArgs, CanvasArgs, CheckerboardArgs, CircleArgs,
+    CompositeArgs, ContactSheetArgs, ContrastArgs, ConvertArgs, CropArgs, CurvesArgs, DepthArgs,
+    DitherAlgorithm, ExifArgs, FitArgs, FitMode, FlipArgs, FloodRemoveArgs, FrameArgs, GammaArgs,
+    GenerateArgs, GradientOverlayArgs, GrayscaleArgs, GrayscaleMethod, GridArgs, InfoArgs,
+    InvertArgs, LimitArgs, PadArgs, PixelArgs, PngColorType, PremultiplyArgs, RegionArgs,
+    RegionAverageArgs, ReportFormat, ResizeArgs, ResizeFilter, Rotate180Args, RotateArgs,
+    RotateLeftArgs, RotateRightArgs, ScaleArgs, ScaleMode, SeamCarveArgs, SetOrientationArgs,
+    ShadowArgs, SharpenArgs, SheetLabel, SmartCropArgs, SuggestNameArgs, UnpremultiplyArgs,
+    ValidateArgs, WhiteBalanceArgs,
+};
+use mdimgedit::cli::output::{self, print_error, print_warnings, OutputFormat, SuccessResponse};
+use mdimgedit::cli::{parse_position, Cli, Command};
+use mdimgedit::error::{exit_codes, ImgEditError, Warning};
 use mdimgedit::ops;
-use mdimgedit::parse_color;
+use mdimgedit::{color_to_hex, parse_color};
+use std::io::{BufRead, IsTerminal, Write};
 use std::path::Path;
 use std::process::ExitCode;
 
@@ -29,31 +42,109 @@ fn main() -> ExitCode {
 
 fn command_name(cmd: &Command) -> &'static str {
     match cmd {
-        Command::Info { .. } => "info",
-        Command::Exif { .. } => "exif",
-        Command::Crop { .. } => "crop",
-        Command::Rotate { .. } => "rotate",
-        Command::Flip { .. } => "flip",
-        Command::Resize { .. } => "resize",
-        Command::Fit { .. } => "fit",
-        Command::Convert { .. } => "convert",
-        Command::Grayscale { .. } => "grayscale",
-        Command::Depth { .. } => "depth",
-        Command::Invert { .. } => "invert",
-        Command::Brightness { .. } => "brightness",
-        Command::Contrast { .. } => "contrast",
-        Command::Gamma { .. } => "gamma",
-        Command::Blur { .. } => "blur",
-        Command::Sharpen { .. } => "sharpen",
-        Command::Pad { .. } => "pad",
-        Command::Canvas { .. } => "canvas",
-        Command::Composite { .. } => "composite",
+        Command::Info(..) => "info",
+        Command::Pixel(..) => "pixel",
+        Command::RegionAverage(..) => "region-average",
+        Command::Validate(..) => "validate",
+        Command::Audit(..) => "audit",
+        Command::Schema => "schema",
+        Command::Exif(..) => "exif",
+        Command::SuggestName(..) => "suggest-name",
+        Command::SetOrientation(..) => "set-orientation",
+        Command::Crop(..) => "crop",
+        Command::Region(..) => "region",
+        Command::Rotate(..) => "rotate",
+        Command::RotateLeft(..) => "rotate-left",
+        Command::RotateRight(..) => "rotate-right",
+        Command::Rotate180(..) => "rotate-180",
+        Command::Flip(..) => "flip",
+        Command::Resize(..) => "resize",
+        Command::Fit(..) => "fit",
+        Command::Limit(..) => "limit",
+        Command::Scale(..) => "scale",
+        Command::SmartCrop(..) => "smart-crop",
+        Command::SeamCarve(..) => "seam-carve",
+        Command::Convert(..) => "convert",
+        Command::Grayscale(..) => "grayscale",
+        Command::Depth(..) => "depth",
+        Command::Invert(..) => "invert",
+        Command::AlphaThreshold(..) => "alpha-threshold",
+        Command::Premultiply(..) => "premultiply",
+        Command::Unpremultiply(..) => "unpremultiply",
+        Command::Brightness(..) => "brightness",
+        Command::Contrast(..) => "contrast",
+        Command::Gamma(..) => "gamma",
+        Command::Adjust(..) => "adjust",
+        Command::Curves(..) => "curves",
+        Command::WhiteBalance(..) => "white-balance",
+        Command::Blur(..) => "blur",
+        Command::Sharpen(..) => "sharpen",
+        Command::Bilateral(..) => "bilateral",
+        Command::Pad(..) => "pad",
+        Command::Frame(..) => "frame",
+        Command::Shadow(..) => "shadow",
+        Command::Checkerboard(..) => "checkerboard",
+        Command::Grid(..) => "grid",
+        Command::FloodRemove(..) => "flood-remove",
+        Command::Canvas(..) => "canvas",
+        Command::Annotate(..) => "annotate",
+        Command::Composite(..) => "composite",
+        Command::GradientOverlay(..) => "gradient-overlay",
+        Command::Generate(..) => "generate",
+        Command::Circle(..) => "circle",
+        Command::ContactSheet(..) => "contact-sheet",
+        Command::Batch(..) => "batch",
+        Command::Apply(..) => "apply",
     }
 }
 
-/// Check if output file exists and handle overwrite logic
-fn check_output_overwrite(path: &Path, overwrite: bool) -> mdimgedit::Result<()> {
+/// Parse the color given for the argument `field`, attributing any error to it
+fn color_arg(value: &str, field: &str) -> mdimgedit::Result<image::Rgba<u8>> {
+    parse_color(value).map_err(|e| e.for_field(field))
+}
+
+/// The format a reporting command prints in: `requested` with
+/// --output-format, else whatever --json selected
+fn report_format(requested: Option<ReportFormat>, format: OutputFormat) -> ReportFormat {
+    requested.unwrap_or(match format {
+        OutputFormat::Json => ReportFormat::Json,
+        OutputFormat::Text => ReportFormat::Text,
+    })
+}
+
+/// The command-line spelling of a value enum, e.g. `top-left`
+fn value_name(value: impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|v| v.get_name().to_string())
+        .unwrap_or_default()
+}
+
+/// Ask on `prompt` whether to replace `path`, reading the answer from
+/// `answer`. Only `y` or `yes` agree; anything else, or no input, declines.
+fn confirm_overwrite(path: &Path, answer: &mut impl BufRead, prompt: &mut impl Write) -> bool {
+    let _ = write!(prompt, "Overwrite {}? [y/N] ", path.display());
+    let _ = prompt.flush();
+    let mut line = String::new();
+    answer.read_line(&mut line).is_ok()
+        && matches!(line.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Check if output file exists and handle overwrite logic. With
+/// `interactive` and a terminal on stdin, the user is asked instead of
+/// refused outright.
+fn check_output_overwrite(
+    path: &Path,
+    overwrite: bool,
+    interactive: bool,
+) -> mdimgedit::Result<()> {
     if path.exists() && !overwrite {
+        if interactive
+            && std::io::stdin().is_terminal()
+            && confirm_overwrite(path, &mut std::io::stdin().lock(), &mut std::io::stderr())
+        {
+            return Ok(());
+        }
         return Err(ImgEditError::WriteError {
             path: path.display().to_string(),
             reason: "File exists. Use --overwrite (-y) to replace.".to_string(),
@@ -62,72 +153,592 @@ fn check_output_overwrite(path: &Path, overwrite: bool) -> mdimgedit::Result<()>
     Ok(())
 }
 
+/// Check that an output path may be written: it must not clobber an existing
+/// file without --overwrite, and its directory must exist (or be created with
+/// --create-dirs).
+fn check_output(path: &Path, cli: &Cli) -> mdimgedit::Result<()> {
+    check_output_overwrite(path, cli.overwrite, cli.interactive)?;
+    ops::ensure_parent_dir(path, cli.create_dirs)
+}
+
+/// Resolve where a single-input command writes its result and check that it
+/// may be written.
+///
+/// With `--in-place` the input path is returned; overwriting it is implied.
+/// With `--backup` as well, [`write_output`] copies the original to
+/// `<INPUT>.bak` just before replacing it; an existing backup is refused
+/// here unless `--overwrite` is given.
+fn prepare_output<'a>(
+    output: Option<&'a Path>,
+    input: &'a Path,
+    cli: &Cli,
+) -> mdimgedit::Result<&'a Path> {
+    if !cli.in_place {
+        // Checked here rather than by clap, which misses a global --in-place
+        // given on the other side of the subcommand
+        if cli.backup {
+            return Err(ImgEditError::InvalidParameter(
+                "--backup requires --in-place".to_string(),
+            ));
+        }
+        let output = output.ok_or_else(|| {
+            ImgEditError::InvalidParameter("An OUTPUT path or --in-place is required".to_string())
+        })?;
+        // Replacing the source by passing it twice is almost always a slip;
+        // require the same explicit opt-in as any other existing file
+        if !cli.overwrite && same_file(input, output) {
+            return Err(ImgEditError::WriteError {
+                path: output.display().to_string(),
+                reason: "OUTPUT is the same file as INPUT. Use --in-place or --overwrite (-y) to replace it."
+                    .to_string(),
+            });
+        }
+        check_output(output, cli)?;
+        return Ok(output);
+    }
+
+    if output.is_some() {
+        return Err(ImgEditError::InvalidParameter(
+            "--in-place writes back to the input; do not also give an OUTPUT path".to_string(),
+        ));
+    }
+    if cli.backup {
+        check_output_overwrite(&backup_path(input), cli.overwrite, cli.interactive)?;
+    }
+    Ok(input)
+}
+
+/// Where `--backup` keeps the original of an in-place edit
+fn backup_path(input: &Path) -> std::path::PathBuf {
+    let mut backup = input.as_os_str().to_owned();
+    backup.push(".bak");
+    backup.into()
+}
+
+/// Whether two paths name the same existing file
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// Create a hidden, not yet existing sibling of `output` that keeps its file
+/// name (and so its extension), unique to this process and call
+fn create_temp_sibling(output: &Path) -> mdimgedit::Result<std::path::PathBuf> {
+    static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+    let file_name = output
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    loop {
+        let n = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let temp = output.with_file_name(format!(
+            ".mdimgedit-tmp-{}-{}-{}",
+            std::process::id(),
+            n,
+            file_name
+        ));
+        match std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temp)
+        {
+            Ok(_) => return Ok(temp),
+            // Left behind by an earlier process with the same id
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                return Err(ImgEditError::WriteError {
+                    path: temp.display().to_string(),
+                    reason: e.to_string(),
+                })
+            }
+        }
+    }
+}
+
+/// Write `output` through `save`.
+///
+/// An existing file (including the input, for in-place edits) is never
+/// written directly: the result goes to a hidden sibling that keeps the
+/// original extension, which is then renamed over it, so a failed encode
+/// leaves the old file intact. For an in-place edit with `--backup`, the
+/// original is copied to `<INPUT>.bak` once the result has been written.
+fn write_output(
+    output: &Path,
+    cli: &Cli,
+    save: impl FnOnce(&Path) -> mdimgedit::Result<()>,
+) -> mdimgedit::Result<()> {
+    if !output.exists() {
+        return save(output);
+    }
+
+    let temp = create_temp_sibling(output)?;
+    let result = save(&temp).and_then(|()| {
+        if cli.in_place && cli.backup {
+            let backup = backup_path(output);
+            std::fs::copy(output, &backup).map_err(|e| ImgEditError::WriteError {
+                path: backup.display().to_string(),
+                reason: e.to_string(),
+            })?;
+        }
+        std::fs::rename(&temp, output).map_err(|e| ImgEditError::WriteError {
+            path: output.display().to_string(),
+            reason: e.to_string(),
+        })
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result
+}
+
+/// The modification and access times of `input` when `--preserve-timestamps`
+/// is set. Read before writing, since an in-place edit replaces the input.
+fn source_times(input: Option<&Path>, cli: &Cli) -> mdimgedit::Result<Option<std::fs::FileTimes>> {
+    let Some(input) = input.filter(|_| cli.preserve_timestamps) else {
+        return Ok(None);
+    };
+    let metadata = std::fs::metadata(input)?;
+    Ok(Some(
+        std::fs::FileTimes::new()
+            .set_modified(metadata.modified()?)
+            .set_accessed(metadata.accessed()?),
+    ))
+}
+
+/// Apply times captured by [`source_times`] to the written `output`
+fn restore_times(output: &Path, times: Option<std::fs::FileTimes>) -> mdimgedit::Result<()> {
+    let Some(times) = times else {
+        return Ok(());
+    };
+    std::fs::File::options()
+        .write(true)
+        .open(output)
+        .and_then(|file| file.set_times(times))
+        .map_err(|e| ImgEditError::WriteError {
+            path: output.display().to_string(),
+            reason: format!("Could not set file times: {}", e),
+        })
+}
+
+/// Downscale `img` to honour the global `--max-dimension` cap.
+/// Returns `None` when no cap is set or the image already fits.
+fn apply_max_dimension(
+    img: &image::DynamicImage,
+    cli: &Cli,
+) -> mdimgedit::Result<Option<image::DynamicImage>> {
+    match cli.max_dimension {
+        Some(max) if img.width() > max || img.height() > max => {
+            ops::limit(img, max, ResizeFilter::Lanczos).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Under `--fail-on-warning`, turn the first warning into an error. Checked
+/// before anything is written, so a strict run leaves no output behind.
+fn check_warnings(warnings: &[Warning], cli: &Cli) -> mdimgedit::Result<()> {
+    match warnings.first() {
+        Some(warning) if cli.fail_on_warning => Err(ImgEditError::WarningAsError(warning.clone())),
+        _ => Ok(()),
+    }
+}
+
 /// Save an image and print success response
+///
+/// `response` carries the command name, input and any command-specific details
+/// or warnings; the output path and dimensions are added here.
 fn save_and_respond(
     img: &image::DynamicImage,
     output: &Path,
+    cli: &Cli,
+    format: OutputFormat,
+    response: SuccessResponse,
+    orig_dim: (u32, u32),
+) -> mdimgedit::Result<i32> {
+    save_and_respond_keeping_palette(img, None, output, cli, format, response, orig_dim)
+}
+
+/// Like [`save_and_respond`], but with `palette_source` (for --keep-palette)
+/// an indexed PNG source's palette is reused for PNG output, as long as the
+/// result's colors still fit in 256 entries. Reports `palette_kept`.
+fn save_and_respond_keeping_palette(
+    img: &image::DynamicImage,
+    palette_source: Option<&Path>,
+    output: &Path,
+    cli: &Cli,
     format: OutputFormat,
-    quiet: bool,
-    cmd_name: &str,
-    input_path: &str,
+    response: SuccessResponse,
     orig_dim: (u32, u32),
 ) -> mdimgedit::Result<i32> {
-    img.save(output).map_err(|e| ImgEditError::WriteError {
-        path: output.display().to_string(),
-        reason: e.to_string(),
+    let (response, result_dim) = save_result(img, palette_source, output, cli, response)?;
+    respond(output, cli, format, response, orig_dim, result_dim);
+    Ok(exit_codes::SUCCESS)
+}
+
+/// The saving half of [`save_and_respond_keeping_palette`], for commands that
+/// write more before responding. Returns the response to print and the
+/// dimensions written.
+fn save_result(
+    img: &image::DynamicImage,
+    palette_source: Option<&Path>,
+    output: &Path,
+    cli: &Cli,
+    mut response: SuccessResponse,
+) -> mdimgedit::Result<(SuccessResponse, (u32, u32))> {
+    let capped = apply_max_dimension(img, cli)?;
+    let img = capped.as_ref().unwrap_or(img);
+
+    let output_format = image::ImageFormat::from_path(output).ok();
+    if let Some(warning) = output_format.and_then(|format| ops::alpha_warning(img, format)) {
+        response = response.with_warning(warning);
+    }
+    check_warnings(&response.warnings, cli)?;
+    // Resampling by --max-dimension mixes new colors in
+    let palette = palette_source
+        .filter(|_| capped.is_none() && output_format == Some(image::ImageFormat::Png))
+        .and_then(ops::png_palette);
+    // Gray results are stored as one channel where the format allows it
+    let narrowed = output_format
+        .filter(|_| palette.is_none())
+        .and_then(|format| ops::single_channel(img, format));
+    let img = narrowed.as_ref().unwrap_or(img);
+    let times = source_times(response.input.as_deref().map(Path::new), cli)?;
+    let mut palette_kept = false;
+    write_output(output, cli, |path| {
+        if let Some(palette) = &palette {
+            palette_kept = ops::save_with_palette(img, path, palette)?;
+            if palette_kept {
+                return Ok(());
+            }
+        }
+        img.save(path).map_err(|e| ImgEditError::WriteError {
+            path: output.display().to_string(),
+            reason: e.to_string(),
+        })
     })?;
+    restore_times(output, times)?;
+    if palette_source.is_some() {
+        response = response.with_detail("palette_kept", palette_kept);
+    }
+    Ok((response, (img.width(), img.height())))
+}
 
+/// Print the success response for a written `output`
+fn respond(
+    output: &Path,
+    cli: &Cli,
+    format: OutputFormat,
+    response: SuccessResponse,
+    orig_dim: (u32, u32),
+    result_dim: (u32, u32),
+) {
     if format == OutputFormat::Json {
-        let response = SuccessResponse::new(cmd_name)
-            .with_input(input_path)
+        let response = response
             .with_output(&output.display().to_string())
             .with_detail("original_width", orig_dim.0)
             .with_detail("original_height", orig_dim.1)
-            .with_detail("result_width", img.width())
-            .with_detail("result_height", img.height());
-        println!("{}", response.to_json());
-    } else if !quiet {
+            .with_detail("result_width", result_dim.0)
+            .with_detail("result_height", result_dim.1);
+        print_response(response);
+    } else if !cli.quiet {
         println!(
             "Saved {} ({}x{} -> {}x{})",
             output.display(),
             orig_dim.0,
             orig_dim.1,
-            img.width(),
-            img.height()
+            result_dim.0,
+            result_dim.1
         );
+        print_warnings(&response.warnings);
+    }
+}
+
+thread_local! {
+    /// `Some` while a batch job runs on this thread: the job's response is kept
+    /// here for the batch's results instead of being printed
+    static JOB_RESPONSE: std::cell::RefCell<Option<Option<SuccessResponse>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Print a command's JSON response, or keep it when it comes from a batch job
+fn print_response(response: SuccessResponse) {
+    let response = JOB_RESPONSE.with_borrow_mut(|job| match job {
+        Some(kept) => {
+            *kept = Some(response);
+            None
+        }
+        None => Some(response),
+    });
+    if let Some(response) = response {
+        println!("{}", response.to_json());
     }
+}
 
-    Ok(exit_codes::SUCCESS)
+/// Run one manifest job through the same parsing and dispatch as a command
+/// line. The batch's global options carry over; the job runs in JSON mode
+/// with its response returned rather than printed, so the batch can report
+/// its details and warnings.
+fn run_batch_job(
+    job: &ops::BatchJob,
+    batch: &Cli,
+) -> mdimgedit::Result<(i32, Option<SuccessResponse>)> {
+    let argv = std::iter::once("mdimgedit".to_string()).chain(job.argv());
+    let mut cli = Cli::try_parse_from(argv).map_err(|e| {
+        let message = e.to_string();
+        ImgEditError::InvalidParameter(
+            message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+                .to_string(),
+        )
+    })?;
+    cli.json = true;
+    cli.quiet = true;
+    cli.overwrite |= batch.overwrite;
+    cli.interactive |= batch.interactive;
+    cli.fail_on_warning |= batch.fail_on_warning;
+    cli.create_dirs |= batch.create_dirs;
+    cli.preserve_timestamps |= batch.preserve_timestamps;
+    cli.max_dimension = cli.max_dimension.or(batch.max_dimension);
+
+    JOB_RESPONSE.set(Some(None));
+    let code = run_command(&cli, OutputFormat::Json);
+    let response = JOB_RESPONSE.take().flatten();
+    Ok((code?, response))
+}
+
+/// Apply a pointwise `op` for `--low-memory`, streaming it strip by strip
+/// when the strip path can handle `op`, `input` and `output` (8-bit PNG to
+/// PNG, no --max-dimension). Returns `None` when the whole image must be loaded.
+fn stream_and_respond(
+    op: ops::PointOp,
+    input: &Path,
+    output: &Path,
+    cli: &Cli,
+    format: OutputFormat,
+    response: SuccessResponse,
+) -> mdimgedit::Result<Option<i32>> {
+    let png_output = image::ImageFormat::from_path(output).ok() == Some(image::ImageFormat::Png);
+    let Some(dims) =
+        ops::streamable_png(input, op).filter(|_| png_output && cli.max_dimension.is_none())
+    else {
+        return Ok(None);
+    };
+
+    check_warnings(&response.warnings, cli)?;
+    let times = source_times(Some(input), cli)?;
+    write_output(output, cli, |path| ops::stream_point_op(input, path, op))?;
+    restore_times(output, times)?;
+
+    let response = response.with_detail("low_memory", true);
+    respond(output, cli, format, response, dims, dims);
+    Ok(Some(exit_codes::SUCCESS))
 }
 
 fn run_command(cli: &Cli, format: OutputFormat) -> mdimgedit::Result<i32> {
     match &cli.command {
-        Command::Info { input } => {
+        Command::Info(InfoArgs {
+            assume_orientation,
+            channels,
+            output_format,
+            input,
+        }) => {
             let info = ops::get_image_info(input)?;
-
-            if format == OutputFormat::Json {
-                let response = SuccessResponse::new("info")
+            let display = assume_orientation
+                .map(|o| (o, ops::display_dimensions(info.width, info.height, o)));
+            let channel_stats = if *channels {
+                Some(ops::channel_stats(&ops::load_image(input)?)?)
+            } else {
+                None
+            };
+
+            let report = report_format(*output_format, format);
+            if report != ReportFormat::Text {
+                let mut response = SuccessResponse::new("info")
                     .with_input(&info.file)
                     .with_detail("format", info.format.clone())
+                    .with_detail("format_mismatch", info.format_mismatch)
                     .with_detail("width", info.width)
                     .with_detail("height", info.height)
                     .with_detail("color_type", info.color_type.clone())
                     .with_detail("bit_depth", info.bit_depth)
-                    .with_detail("file_size_bytes", info.file_size_bytes);
-                println!("{}", response.to_json());
+                    .with_detail("file_size_bytes", info.file_size_bytes)
+                    .with_detail("dpi_x", info.dpi_x)
+                    .with_detail("dpi_y", info.dpi_y);
+                if let Some((orientation, (width, height))) = display {
+                    response = response
+                        .with_detail("assumed_orientation", orientation)
+                        .with_detail("display_width", width)
+                        .with_detail("display_height", height);
+                }
+                if let Some(stats) = channel_stats {
+                    response = response.with_detail("channels", serde_json::json!(stats));
+                }
+                output::print_report(&response, report);
             } else if !cli.quiet {
                 println!("{}", info.display());
+                if let Some((orientation, (width, height))) = display {
+                    println!(
+                        "Display Dimensions: {}x{} (assuming orientation {})",
+                        width, height, orientation
+                    );
+                }
+                if let Some(stats) = channel_stats {
+                    println!("{}", stats.display());
+                }
+            }
+
+            Ok(exit_codes::SUCCESS)
+        }
+
+        Command::Pixel(PixelArgs { x, y, input }) => {
+            let img = ops::load_image(input)?;
+            let pixel = ops::pixel_info(&img, *x, *y)?;
+
+            if format == OutputFormat::Json {
+                let response = SuccessResponse::new("pixel")
+                    .with_input(&input.display().to_string())
+                    .with_detail("x", pixel.x)
+                    .with_detail("y", pixel.y)
+                    .with_detail("r", pixel.r)
+                    .with_detail("g", pixel.g)
+                    .with_detail("b", pixel.b)
+                    .with_detail("a", pixel.a)
+                    .with_detail("hex", pixel.hex.clone())
+                    .with_detail("luma", pixel.luma);
+                print_response(response);
+            } else if !cli.quiet {
+                println!("{}", pixel.display());
+            }
+
+            Ok(exit_codes::SUCCESS)
+        }
+
+        Command::RegionAverage(RegionAverageArgs {
+            x,
+            y,
+            width,
+            height,
+            input,
+        }) => {
+            let img = ops::load_image(input)?;
+            let avg = ops::region_average(&img, *x, *y, *width, *height)?;
+
+            if format == OutputFormat::Json {
+                let response = SuccessResponse::new("region-average")
+                    .with_input(&input.display().to_string())
+                    .with_detail("x", avg.x)
+                    .with_detail("y", avg.y)
+                    .with_detail("width", avg.width)
+                    .with_detail("height", avg.height)
+                    .with_detail("r", avg.r)
+                    .with_detail("g", avg.g)
+                    .with_detail("b", avg.b)
+                    .with_detail("a", avg.a)
+                    .with_detail("hex", avg.hex.clone());
+                print_response(response);
+            } else if !cli.quiet {
+                println!("{}", avg.display());
+            }
+
+            Ok(exit_codes::SUCCESS)
+        }
+
+        Command::Validate(ValidateArgs { input }) => {
+            if !input.exists() {
+                return Err(ImgEditError::InputNotFound(input.display().to_string()));
+            }
+
+            let response =
+                SuccessResponse::new("validate").with_input(&input.display().to_string());
+            match ops::validate_image(input) {
+                Ok(img) => {
+                    if format == OutputFormat::Json {
+                        let response = response
+                            .with_detail("valid", true)
+                            .with_detail("width", img.width())
+                            .with_detail("height", img.height());
+                        print_response(response);
+                    } else if !cli.quiet {
+                        println!(
+                            "Valid: {} ({}x{})",
+                            input.display(),
+                            img.width(),
+                            img.height()
+                        );
+                    }
+                    Ok(exit_codes::SUCCESS)
+                }
+                Err(err) => {
+                    if format == OutputFormat::Json {
+                        let mut response = response
+                            .with_detail("valid", false)
+                            .with_detail("error", err.to_string())
+                            .with_detail("code", err.code());
+                        response.success = false;
+                        print_response(response);
+                    } else if !cli.quiet {
+                        eprintln!("Invalid: {}", err);
+                    }
+                    Ok(err.exit_code())
+                }
+            }
+        }
+
+        Command::Schema => {
+            let schemas = serde_json::json!({
+                "SuccessResponse": output::success_schema(),
+                "ErrorResponse": output::error_schema(),
+            });
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&schemas).unwrap_or_default()
+            );
+            Ok(exit_codes::SUCCESS)
+        }
+
+        Command::Audit(AuditArgs { dir }) => {
+            let summary = ops::audit(dir)?;
+
+            if format == OutputFormat::Json {
+                let mut response = SuccessResponse::new("audit")
+                    .with_input(&summary.directory)
+                    .with_detail("images", summary.images)
+                    .with_detail("skipped", summary.skipped)
+                    .with_detail("formats", serde_json::json!(summary.formats))
+                    .with_detail("total_bytes", summary.total_bytes)
+                    .with_detail("with_alpha", summary.with_alpha)
+                    .with_detail("with_exif", summary.with_exif);
+                if let (Some(width), Some(height)) = (summary.width, summary.height) {
+                    response = response
+                        .with_detail("width", width)
+                        .with_detail("height", height);
+                }
+                print_response(response);
+            } else if !cli.quiet {
+                println!("{}", summary.display());
             }
 
             Ok(exit_codes::SUCCESS)
         }
 
-        Command::Exif {
+        Command::Exif(ExifArgs {
             verbose,
+            raw,
             tag,
+            output_format,
             input,
-        } => {
-            let exif_data = ops::read_exif(input)?;
+        }) => {
+            let mut exif_data = ops::read_exif(input)?;
+            let report = report_format(*output_format, format);
+            if !*raw {
+                exif_data = exif_data.without_raw();
+            }
 
             if let Some(ref tag_name) = tag {
                 // Specific tag requested
@@ -136,7 +747,7 @@ fn run_command(cli: &Cli, format: OutputFormat) -> mdimgedit::Result<i32> {
                     .iter()
                     .find(|f| f.tag.to_lowercase() == tag_name.to_lowercase());
 
-                if format == OutputFormat::Json {
+                if report != ReportFormat::Text {
                     let mut response = SuccessResponse::new("exif")
                         .with_input(&input.display().to_string())
                         .with_detail("tag", tag_name.clone());
@@ -145,10 +756,15 @@ fn run_command(cli: &Cli, format: OutputFormat) -> mdimgedit::Result<i32> {
                         response = response
                             .with_detail("value", f.value.clone())
                             .with_detail("found", true);
+                        if *raw {
+                            response = response
+                                .with_detail("value_type", f.value_type.clone())
+                                .with_detail("raw_hex", f.raw_hex.clone());
+                        }
                     } else {
                         response = response.with_detail("found", false);
                     }
-                    println!("{}", response.to_json());
+                    output::print_report(&response, report);
                 } else if !cli.quiet {
                     if let Some(f) = field {
                         println!("{}: {}", f.tag, f.value);
@@ -156,7 +772,7 @@ fn run_command(cli: &Cli, format: OutputFormat) -> mdimgedit::Result<i32> {
                         println!("Tag '{}' not found", tag_name);
                     }
                 }
-            } else if format == OutputFormat::Json {
+            } else if report != ReportFormat::Text {
                 // Serialize ExifData directly for complete JSON output
                 let fields_json =
                     serde_json::to_value(&exif_data.fields).unwrap_or(serde_json::Value::Null);
@@ -178,9 +794,9 @@ fn run_command(cli: &Cli, format: OutputFormat) -> mdimgedit::Result<i32> {
                     .with_detail("artist", exif_data.artist.clone())
                     .with_detail("copyright", exif_data.copyright.clone())
                     .with_detail("fields", fields_json);
-                println!("{}", response.to_json());
+                output::print_report(&response, report);
             } else if !cli.quiet {
-                if *verbose {
+                if *verbose || *raw {
                     println!("{}", ops::exif::format_exif_verbose(&exif_data));
                 } else {
                     println!("{}", ops::exif::format_exif_text(&exif_data));
@@ -190,224 +806,833 @@ fn run_command(cli: &Cli, format: OutputFormat) -> mdimgedit::Result<i32> {
             Ok(exit_codes::SUCCESS)
         }
 
-        Command::Crop {
+        Command::SuggestName(SuggestNameArgs {
+            pattern,
+            require_exif,
+            input,
+        }) => {
+            let name = ops::suggest_name(input, pattern, *require_exif)?;
+
+            if format == OutputFormat::Json {
+                let response = SuccessResponse::new("suggest-name")
+                    .with_input(&input.display().to_string())
+                    .with_detail("name", name);
+                print_response(response);
+            } else if !cli.quiet {
+                println!("{}", name);
+            }
+
+            Ok(exit_codes::SUCCESS)
+        }
+
+        Command::SetOrientation(SetOrientationArgs {
+            orientation,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let times = source_times(Some(input), cli)?;
+            write_output(output, cli, |path| {
+                ops::set_orientation(input, path, *orientation)
+            })?;
+            restore_times(output, times)?;
+
+            if format == OutputFormat::Json {
+                let response = SuccessResponse::new("set-orientation")
+                    .with_input(&input.display().to_string())
+                    .with_output(&output.display().to_string())
+                    .with_detail("orientation", *orientation);
+                print_response(response);
+            } else if !cli.quiet {
+                println!(
+                    "Saved {} (EXIF orientation {})",
+                    output.display(),
+                    orientation
+                );
+            }
+
+            Ok(exit_codes::SUCCESS)
+        }
+
+        Command::Crop(CropArgs {
             x,
             y,
             width,
             height,
             anchor,
+            position,
+            pad_if_needed,
+            background,
+            keep_palette,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            // Resolve the crop origin up front so the response can report it
+            let (crop_x, crop_y, crop_anchor) = match position {
+                Some(spec) => {
+                    let (px, py) =
+                        parse_position(spec, (orig_width, orig_height), (*width, *height))?;
+                    if (px < 0 || py < 0) && !*pad_if_needed {
+                        return Err(ImgEditError::CropOutOfBounds(format!(
+                            "Crop position ({}, {}) is outside the image",
+                            px, py
+                        )));
+                    }
+                    (px as i64, py as i64, Anchor::TopLeft)
+                }
+                None if *pad_if_needed => {
+                    let (cx, cy) = ops::crop::calculate_padded_crop_position(
+                        orig_width,
+                        orig_height,
+                        *width,
+                        *height,
+                        *x,
+                        *y,
+                        *anchor,
+                    );
+                    (cx, cy, *anchor)
+                }
+                None => {
+                    let (cx, cy) = ops::crop::calculate_crop_position(
+                        orig_width,
+                        orig_height,
+                        *width,
+                        *height,
+                        *x,
+                        *y,
+                        *anchor,
+                    );
+                    (cx as i64, cy as i64, *anchor)
+                }
+            };
+            let result = if *pad_if_needed {
+                let bg_color = color_arg(background, "background")?;
+                ops::crop_padded(&img, crop_x, crop_y, *width, *height, bg_color)?
+            } else {
+                ops::crop(
+                    &img,
+                    crop_x as u32,
+                    crop_y as u32,
+                    *width,
+                    *height,
+                    Anchor::TopLeft,
+                )?
+            };
+
+            save_and_respond_keeping_palette(
+                &result,
+                keep_palette.then_some(input.as_path()),
+                output,
+                cli,
+                format,
+                SuccessResponse::new("crop")
+                    .with_input(&input.display().to_string())
+                    .with_parameter("anchor", value_name(crop_anchor))
+                    .with_parameter("x", crop_x)
+                    .with_parameter("y", crop_y)
+                    .with_parameter("width", *width)
+                    .with_parameter("height", *height),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Region(RegionArgs {
+            x,
+            y,
+            width,
+            height,
+            out_width,
+            out_height,
+            filter,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
-            let result = ops::crop(&img, *x, *y, *width, *height, *anchor)?;
+            let cropped = ops::crop(&img, *x, *y, *width, *height, Anchor::TopLeft)?;
+            let result = ops::resize(&cropped, Some(*out_width), Some(*out_height), None, *filter)?;
+
+            let mut response = SuccessResponse::new("region")
+                .with_input(&input.display().to_string())
+                .with_parameter("x", *x)
+                .with_parameter("y", *y)
+                .with_parameter("width", *width)
+                .with_parameter("height", *height)
+                .with_parameter("out_width", *out_width)
+                .with_parameter("out_height", *out_height)
+                .with_parameter("filter", value_name(*filter));
+            // Measured on what is written, after any --max-dimension cap
+            let result = apply_max_dimension(&result, cli)?.unwrap_or(result);
+            if let Some(warning) =
+                ops::upscale_warning((*width, *height), (result.width(), result.height()))
+            {
+                response = response.with_warning(warning);
+            }
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "crop",
-                &input.display().to_string(),
+                response,
                 (orig_width, orig_height),
             )
         }
 
-        Command::Rotate {
+        Command::Rotate(RotateArgs {
             degrees,
+            auto,
+            max_angle,
             expand,
             background,
+            background_image,
+            keep_palette,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
-            let bg_color = parse_color(background)?;
-            let result = ops::rotate(&img, *degrees, *expand, bg_color)?;
+            let bg_color = color_arg(background, "background")?;
+            let mut response =
+                SuccessResponse::new("rotate").with_input(&input.display().to_string());
+            let angle = match degrees {
+                Some(d) if !*auto => *d,
+                _ => {
+                    let skew = ops::estimate_skew(&img, *max_angle)?;
+                    response = response.with_detail("skew_degrees", skew);
+                    -skew
+                }
+            };
+            let result = match background_image {
+                Some(path) => ops::rotate_textured(&img, angle, *expand, &ops::load_image(path)?)?,
+                None => ops::rotate(&img, angle, *expand, bg_color)?,
+            };
+            response = response
+                .with_parameter("degrees", angle)
+                .with_parameter("expand", *expand);
+            if background_image.is_none() {
+                response = response.with_parameter("background", color_to_hex(bg_color));
+            }
 
-            save_and_respond(
+            save_and_respond_keeping_palette(
+                &result,
+                keep_palette.then_some(input.as_path()),
+                output,
+                cli,
+                format,
+                response,
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::RotateLeft(RotateLeftArgs {
+            keep_palette,
+            input,
+            output,
+        })
+        | Command::RotateRight(RotateRightArgs {
+            keep_palette,
+            input,
+            output,
+        })
+        | Command::Rotate180(Rotate180Args {
+            keep_palette,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let degrees = match &cli.command {
+                Command::RotateLeft(..) => 90.0,
+                Command::RotateRight(..) => -90.0,
+                _ => 180.0,
+            };
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            // Quarter turns in the direction the command names, whatever
+            // direction `rotate --degrees` takes
+            let result = match &cli.command {
+                Command::RotateLeft(..) => img.rotate270(),
+                Command::RotateRight(..) => img.rotate90(),
+                _ => img.rotate180(),
+            };
+
+            save_and_respond_keeping_palette(
                 &result,
+                keep_palette.then_some(input.as_path()),
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "rotate",
-                &input.display().to_string(),
+                SuccessResponse::new(command_name(&cli.command))
+                    .with_input(&input.display().to_string())
+                    .with_detail("degrees", degrees),
                 (orig_width, orig_height),
             )
         }
 
-        Command::Flip {
+        Command::Flip(FlipArgs {
             horizontal,
             vertical,
+            keep_palette,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
             let result = ops::flip(&img, *horizontal, *vertical)?;
 
-            save_and_respond(
+            save_and_respond_keeping_palette(
                 &result,
+                keep_palette.then_some(input.as_path()),
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "flip",
-                &input.display().to_string(),
+                SuccessResponse::new("flip").with_input(&input.display().to_string()),
                 (orig_width, orig_height),
             )
         }
 
-        Command::Resize {
+        Command::Resize(ResizeArgs {
             width,
             height,
             scale,
+            keep_other,
             filter,
+            lanczos_a,
+            pad,
+            background,
+            downscale_only,
+            upscale_only,
+            linear,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
+            let (width, height) = if *keep_other {
+                ops::keep_other_dimension(&img, *width, *height)
+            } else {
+                (*width, *height)
+            };
+
+            let guard = match (*downscale_only, *upscale_only) {
+                (true, _) => FitMode::Down,
+                (_, true) => FitMode::Up,
+                _ => FitMode::Both,
+            };
+            let skipped = !ops::resize_allowed(&img, width, height, *scale, guard)?;
+
+            let (horizontal, vertical) = match filter.as_slice() {
+                [single] => (*single, *single),
+                [horizontal, vertical] => (*horizontal, *vertical),
+                _ => {
+                    return Err(ImgEditError::InvalidParameter(
+                        "--filter takes one filter or two separated by a comma".to_string(),
+                    )
+                    .for_field("filter"));
+                }
+            };
+            let per_axis = horizontal != vertical;
+            if per_axis && (*pad || lanczos_a.is_some()) {
+                return Err(ImgEditError::InvalidParameter(
+                    "Per-axis filters cannot be combined with --pad or --lanczos-a".to_string(),
+                )
+                .for_field("filter"));
+            }
 
-            let result = ops::resize(&img, *width, *height, *scale, *filter)?;
+            let result = match (*pad, width, height) {
+                _ if skipped => img.clone(),
+                (true, Some(w), Some(h)) => {
+                    let bg_color = color_arg(background, "background")?;
+                    ops::resize_pad(&img, w, h, horizontal, bg_color)?
+                }
+                (true, _, _) => {
+                    return Err(ImgEditError::InvalidParameter(
+                        "--pad requires both --width and --height".to_string(),
+                    ));
+                }
+                _ => {
+                    if lanczos_a.is_some() && horizontal != ResizeFilter::Lanczos {
+                        return Err(ImgEditError::InvalidParameter(
+                            "--lanczos-a requires --filter lanczos".to_string(),
+                        ));
+                    }
+                    let resample = |img: &image::DynamicImage| match lanczos_a {
+                        _ if per_axis => {
+                            ops::resize_per_axis(img, width, height, *scale, horizontal, vertical)
+                        }
+                        Some(a) => ops::resize_lanczos(img, width, height, *scale, *a as u32),
+                        None => ops::resize(img, width, height, *scale, horizontal),
+                    };
+                    if *linear {
+                        ops::in_linear_light(&img, resample)?
+                    } else {
+                        resample(&img)?
+                    }
+                }
+            };
+
+            let filter_name = if per_axis {
+                format!("{},{}", value_name(horizontal), value_name(vertical))
+            } else {
+                value_name(horizontal)
+            };
+            let mut response = SuccessResponse::new("resize")
+                .with_input(&input.display().to_string())
+                .with_parameter("width", result.width())
+                .with_parameter("height", result.height())
+                .with_parameter("filter", filter_name);
+            if *linear {
+                response = response.with_parameter("linear", true);
+            }
+            if horizontal == ResizeFilter::Auto && !per_axis {
+                let chosen =
+                    ops::auto_filter((orig_width, orig_height), (result.width(), result.height()));
+                response = response.with_detail("resolved_filter", value_name(chosen));
+            }
+            if guard != FitMode::Both {
+                response = response.with_detail("skipped", skipped);
+            }
+            // Measured on what is written, after any --max-dimension cap
+            let result = apply_max_dimension(&result, cli)?.unwrap_or(result);
+            if let Some(warning) =
+                ops::upscale_warning((orig_width, orig_height), (result.width(), result.height()))
+            {
+                response = response.with_warning(warning);
+            }
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "resize",
-                &input.display().to_string(),
+                response,
                 (orig_width, orig_height),
             )
         }
 
-        Command::Fit {
+        Command::Fit(FitArgs {
             max_width,
             max_height,
             upscale,
+            mode,
             filter,
+            linear,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
-            let result = ops::fit(&img, *max_width, *max_height, *upscale, *filter)?;
+            let mode = match mode {
+                Some(m) => *m,
+                None if *upscale => FitMode::Both,
+                None => FitMode::Down,
+            };
+            let resample =
+                |img: &image::DynamicImage| ops::fit(img, *max_width, *max_height, mode, *filter);
+            let result = if *linear {
+                ops::in_linear_light(&img, resample)?
+            } else {
+                resample(&img)?
+            };
+
+            let mut response = SuccessResponse::new("fit")
+                .with_input(&input.display().to_string())
+                .with_parameter("width", result.width())
+                .with_parameter("height", result.height())
+                .with_parameter("mode", value_name(mode))
+                .with_parameter("filter", value_name(*filter));
+            if *linear {
+                response = response.with_parameter("linear", true);
+            }
+            // Measured on what is written, after any --max-dimension cap
+            let result = apply_max_dimension(&result, cli)?.unwrap_or(result);
+            if let Some(warning) =
+                ops::upscale_warning((orig_width, orig_height), (result.width(), result.height()))
+            {
+                response = response.with_warning(warning);
+            }
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "fit",
-                &input.display().to_string(),
+                response,
                 (orig_width, orig_height),
             )
         }
 
-        Command::Convert {
-            format: img_format,
-            quality,
+        Command::Limit(LimitArgs {
+            max,
+            filter,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
-            let target_format = ops::determine_format(output, *img_format)?;
-            ops::save_with_format(&img, output, target_format, *quality)?;
+            let result = ops::limit(&img, *max, *filter)?;
 
-            if format == OutputFormat::Json {
-                let response = SuccessResponse::new("convert")
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("limit")
                     .with_input(&input.display().to_string())
-                    .with_output(&output.display().to_string())
-                    .with_detail("original_width", orig_width)
-                    .with_detail("original_height", orig_height)
-                    .with_detail("result_width", img.width())
-                    .with_detail("result_height", img.height())
-                    .with_detail("format", format!("{:?}", target_format));
-                println!("{}", response.to_json());
-            } else if !cli.quiet {
-                println!(
-                    "Converted {} -> {} ({:?})",
-                    input.display(),
-                    output.display(),
-                    target_format
-                );
-            }
-
+                    .with_parameter("max", *max)
+                    .with_parameter("filter", value_name(*filter)),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Scale(ScaleArgs {
+            width,
+            height,
+            mode,
+            filter,
+            background,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let bg_color = color_arg(background, "background")?;
+            let result = ops::scale(&img, *width, *height, *mode, *filter, bg_color)?;
+
+            let mut response = SuccessResponse::new("scale")
+                .with_input(&input.display().to_string())
+                .with_parameter("width", *width)
+                .with_parameter("height", *height)
+                .with_parameter("mode", value_name(*mode))
+                .with_parameter("filter", value_name(*filter));
+            if *mode == ScaleMode::Pad {
+                response = response.with_parameter("background", background.as_str());
+            }
+            // Measured on what is written, after any --max-dimension cap
+            let result = apply_max_dimension(&result, cli)?.unwrap_or(result);
+            if let Some(warning) =
+                ops::upscale_warning((orig_width, orig_height), (result.width(), result.height()))
+            {
+                response = response.with_warning(warning);
+            }
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                response,
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::SmartCrop(SmartCropArgs {
+            width,
+            height,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let (x, y) = ops::smart_crop_position(&img, *width, *height)?;
+            let result = ops::crop(&img, x, y, *width, *height, Anchor::TopLeft)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("smart-crop")
+                    .with_input(&input.display().to_string())
+                    .with_detail("x", x)
+                    .with_detail("y", y),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::SeamCarve(SeamCarveArgs {
+            width,
+            height,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::seam_carve(
+                &img,
+                width.unwrap_or(orig_width),
+                height.unwrap_or(orig_height),
+            )?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("seam-carve").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Convert(ConvertArgs {
+            format: img_format,
+            quality,
+            frame,
+            optimize,
+            assign_profile,
+            dpi,
+            lossless,
+            only_if_smaller,
+            low_memory,
+            png_color_type,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = match frame {
+                Some(index) => ops::load_frame(input, *index)?,
+                None => {
+                    if ops::is_animated(input)? {
+                        return Err(ImgEditError::UnsupportedFormat(
+                            "Input is animated; use --frame N to convert a single frame"
+                                .to_string(),
+                        ));
+                    }
+                    ops::load_image(input)?
+                }
+            };
+            let orig_width = img.width();
+            let orig_height = img.height();
+            let img = apply_max_dimension(&img, cli)?.unwrap_or(img);
+            let (result_width, result_height) = (img.width(), img.height());
+
+            let target_format = ops::determine_format(output, *img_format)?;
+            if *lossless {
+                ops::check_lossless(target_format)?;
+            }
+            let options = ops::SaveOptions {
+                quality: *quality,
+                optimize: *optimize,
+                assign_profile: *assign_profile,
+                dpi: *dpi,
+                png_color_type: *png_color_type,
+            };
+
+            let mut warnings = Vec::new();
+            warnings.extend(ops::alpha_warning(&img, target_format));
+            if *optimize {
+                warnings.extend(ops::optimize_warning(target_format));
+            }
+            if assign_profile.is_some() {
+                warnings.extend(ops::profile_warning(target_format));
+            }
+            if dpi.is_some() {
+                warnings.extend(ops::dpi_warning(target_format));
+            }
+            if *png_color_type != PngColorType::Auto {
+                warnings.extend(ops::png_color_type_warning(target_format));
+                warnings.extend(ops::png_alpha_warning(&img, target_format, *png_color_type));
+            }
+            check_warnings(&warnings, cli)?;
+
+            let times = source_times(Some(input), cli)?;
+            let mut skipped_no_gain = false;
+            if *only_if_smaller {
+                // Encode in memory first so nothing is written unless it helps
+                let encoded = ops::encode_with_options(&img, output, target_format, &options)?;
+                let input_size = std::fs::metadata(input)?.len();
+                skipped_no_gain = encoded.len() as u64 >= input_size;
+                if !skipped_no_gain {
+                    write_output(output, cli, |path| {
+                        std::fs::write(path, &encoded).map_err(|e| ImgEditError::WriteError {
+                            path: path.display().to_string(),
+                            reason: e.to_string(),
+                        })
+                    })?;
+                }
+            } else if *low_memory {
+                write_output(output, cli, |path| {
+                    ops::save_low_memory(img, path, target_format, &options)
+                })?;
+            } else {
+                write_output(output, cli, |path| {
+                    ops::save_with_options(&img, path, target_format, &options)
+                })?;
+            }
+            if !skipped_no_gain {
+                restore_times(output, times)?;
+            }
+
+            if format == OutputFormat::Json {
+                let mut response = SuccessResponse::new("convert")
+                    .with_input(&input.display().to_string())
+                    .with_output(&output.display().to_string())
+                    .with_detail("original_width", orig_width)
+                    .with_detail("original_height", orig_height)
+                    .with_detail("result_width", result_width)
+                    .with_detail("result_height", result_height)
+                    .with_detail("format", format!("{:?}", target_format));
+                if let Some(index) = frame {
+                    response = response.with_detail("frame", *index);
+                }
+                if let Some(profile) = assign_profile {
+                    response = response.with_detail("profile", ops::icc::profile_name(*profile));
+                }
+                if let Some(dpi) = dpi {
+                    response = response.with_detail("dpi", *dpi);
+                }
+                if *lossless {
+                    response = response.with_detail("lossless", true);
+                }
+                if *only_if_smaller {
+                    response = response.with_detail("skipped_no_gain", skipped_no_gain);
+                }
+                if *low_memory {
+                    response = response.with_detail("low_memory", true);
+                }
+                if *png_color_type != PngColorType::Auto {
+                    response = response.with_detail("png_color_type", value_name(*png_color_type));
+                }
+                for warning in warnings {
+                    response = response.with_warning(warning);
+                }
+                print_response(response);
+            } else if !cli.quiet && skipped_no_gain {
+                println!(
+                    "Skipped {}: {:?} output would not be smaller",
+                    input.display(),
+                    target_format
+                );
+                print_warnings(&warnings);
+            } else if !cli.quiet {
+                println!(
+                    "Converted {} -> {} ({:?})",
+                    input.display(),
+                    output.display(),
+                    target_format
+                );
+                print_warnings(&warnings);
+            }
+
             Ok(exit_codes::SUCCESS)
         }
 
-        Command::Grayscale {
+        Command::Grayscale(GrayscaleArgs {
             no_preserve_alpha,
+            method,
+            weights,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let weights = weights.as_deref().map(ops::parse_weights).transpose()?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
-            let result = ops::grayscale(&img, !no_preserve_alpha)?;
+            let result = match method {
+                GrayscaleMethod::LumaAlpha => ops::grayscale_luma_alpha(&img, weights)?,
+                GrayscaleMethod::Rgba => ops::grayscale(&img, !no_preserve_alpha, weights)?,
+            };
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "grayscale",
-                &input.display().to_string(),
+                SuccessResponse::new("grayscale").with_input(&input.display().to_string()),
                 (orig_width, orig_height),
             )
         }
 
-        Command::Depth {
+        Command::Depth(DepthArgs {
             bits,
             dither,
+            dither_algo,
+            dither_strength,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
-            let result = ops::change_depth(&img, *bits, *dither)?;
+            let algorithm = dither.then(|| dither_algo.unwrap_or(DitherAlgorithm::Floyd));
+            let result = ops::change_depth(&img, *bits, algorithm, *dither_strength)?;
+
+            let mut response =
+                SuccessResponse::new("depth").with_input(&input.display().to_string());
+            if *dither {
+                response = response.with_parameter("dither_strength", *dither_strength);
+            }
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "depth",
-                &input.display().to_string(),
+                response,
                 (orig_width, orig_height),
             )
         }
 
-        Command::Invert {
+        Command::Invert(InvertArgs {
             invert_alpha,
+            low_memory,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            if *low_memory {
+                let response =
+                    SuccessResponse::new("invert").with_input(&input.display().to_string());
+                if let Some(code) = stream_and_respond(
+                    ops::PointOp::Invert {
+                        invert_alpha: *invert_alpha,
+                    },
+                    input,
+                    output,
+                    cli,
+                    format,
+                    response,
+                )? {
+                    return Ok(code);
+                }
+            }
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
@@ -417,388 +1642,1504 @@ fn run_command(cli: &Cli, format: OutputFormat) -> mdimgedit::Result<i32> {
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "invert",
-                &input.display().to_string(),
+                SuccessResponse::new("invert").with_input(&input.display().to_string()),
                 (orig_width, orig_height),
             )
         }
 
-        Command::Brightness {
+        Command::AlphaThreshold(AlphaThresholdArgs {
             value,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::alpha_threshold(&img, *value)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("alpha-threshold")
+                    .with_input(&input.display().to_string())
+                    .with_parameter("value", *value),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Premultiply(PremultiplyArgs { input, output }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::premultiply(&img)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("premultiply").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Unpremultiply(UnpremultiplyArgs { input, output }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::unpremultiply(&img)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("unpremultiply").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Brightness(BrightnessArgs {
+            value,
+            clip_warn,
+            low_memory,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            if *low_memory {
+                let response =
+                    SuccessResponse::new("brightness").with_input(&input.display().to_string());
+                if let Some(code) = stream_and_respond(
+                    ops::PointOp::Brightness(*value),
+                    input,
+                    output,
+                    cli,
+                    format,
+                    response,
+                )? {
+                    return Ok(code);
+                }
+            }
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
             let result = ops::brightness(&img, *value)?;
 
+            let mut response =
+                SuccessResponse::new("brightness").with_input(&input.display().to_string());
+            if *clip_warn {
+                let counts = ops::clip_counts(&img, *value, 1.0);
+                if let Some(warning) = ops::clip_warning(&counts) {
+                    response = response.with_warning(warning);
+                }
+                response = response.with_detail("clipped", counts);
+            }
+
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "brightness",
-                &input.display().to_string(),
+                response,
                 (orig_width, orig_height),
             )
         }
 
-        Command::Contrast {
+        Command::Contrast(ContrastArgs {
             value,
+            clip_warn,
+            low_memory,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            if *low_memory {
+                let response =
+                    SuccessResponse::new("contrast").with_input(&input.display().to_string());
+                if let Some(code) = stream_and_respond(
+                    ops::PointOp::Contrast(*value),
+                    input,
+                    output,
+                    cli,
+                    format,
+                    response,
+                )? {
+                    return Ok(code);
+                }
+            }
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
             let result = ops::contrast(&img, *value)?;
 
+            let mut response =
+                SuccessResponse::new("contrast").with_input(&input.display().to_string());
+            if *clip_warn {
+                let counts = ops::clip_counts(&img, 0, *value);
+                if let Some(warning) = ops::clip_warning(&counts) {
+                    response = response.with_warning(warning);
+                }
+                response = response.with_detail("clipped", counts);
+            }
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                response,
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Gamma(GammaArgs {
+            value,
+            low_memory,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            if *low_memory {
+                let response =
+                    SuccessResponse::new("gamma").with_input(&input.display().to_string());
+                if let Some(code) = stream_and_respond(
+                    ops::PointOp::Gamma(*value),
+                    input,
+                    output,
+                    cli,
+                    format,
+                    response,
+                )? {
+                    return Ok(code);
+                }
+            }
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::gamma(&img, *value)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("gamma").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Adjust(AdjustArgs {
+            brightness,
+            contrast,
+            gamma,
+            saturation,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let defaults = ops::Adjustments::default();
+            let settings = ops::Adjustments {
+                brightness: brightness.unwrap_or(defaults.brightness),
+                contrast: contrast.unwrap_or(defaults.contrast),
+                gamma: gamma.unwrap_or(defaults.gamma),
+                saturation: saturation.unwrap_or(defaults.saturation),
+            };
+            let result = ops::adjust(&img, &settings)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("adjust").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Curves(CurvesArgs {
+            all,
+            red,
+            green,
+            blue,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let curve = |spec: &Option<String>, field: &str| {
+                spec.as_deref()
+                    .map(|spec| ops::parse_curve(spec).map_err(|e| e.for_field(field)))
+                    .transpose()
+            };
+            let master = curve(all, "all")?;
+            let channels = [
+                curve(red, "red")?,
+                curve(green, "green")?,
+                curve(blue, "blue")?,
+            ];
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::curves(
+                &img,
+                master.as_ref(),
+                channels.each_ref().map(Option::as_ref),
+            )?;
+
+            let mut response =
+                SuccessResponse::new("curves").with_input(&input.display().to_string());
+            for (name, spec) in [("all", all), ("red", red), ("green", green), ("blue", blue)] {
+                if let Some(spec) = spec {
+                    response = response.with_parameter(name, spec.as_str());
+                }
+            }
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                response,
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::WhiteBalance(WhiteBalanceArgs {
+            gray_x,
+            gray_y,
+            temperature,
+            tint,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let mut response =
+                SuccessResponse::new("white-balance").with_input(&input.display().to_string());
+            let gains = match (gray_x, gray_y) {
+                (Some(x), Some(y)) => {
+                    response = response
+                        .with_parameter("gray_x", *x)
+                        .with_parameter("gray_y", *y);
+                    ops::gray_point_gains(&img, *x, *y).map_err(|e| e.for_field("gray-x"))?
+                }
+                _ => {
+                    let kelvin = temperature.unwrap_or(6500.0);
+                    let tint = tint.unwrap_or(0.0);
+                    response = response
+                        .with_parameter("temperature", kelvin)
+                        .with_parameter("tint", tint);
+                    ops::temperature_gains(kelvin, tint)?
+                }
+            };
+            let result = ops::white_balance(&img, gains)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                response.with_detail("gains", gains.to_vec()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Blur(BlurArgs {
+            radius,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::blur(&img, *radius)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("blur").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Sharpen(SharpenArgs {
+            amount,
+            radius,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::sharpen(&img, *amount, *radius)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("sharpen").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Bilateral(BilateralArgs {
+            spatial_sigma,
+            range_sigma,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::bilateral(&img, *spatial_sigma, *range_sigma)?;
+
             save_and_respond(
                 &result,
                 output,
+                cli,
+                format,
+                SuccessResponse::new("bilateral")
+                    .with_input(&input.display().to_string())
+                    .with_parameter("spatial_sigma", *spatial_sigma)
+                    .with_parameter("range_sigma", *range_sigma),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Pad(PadArgs {
+            all,
+            top,
+            bottom,
+            left,
+            right,
+            horizontal,
+            vertical,
+            color,
+            top_color,
+            bottom_color,
+            left_color,
+            right_color,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            // Calculate padding values with priority: specific > axis > all
+            let pad_top = top.or(*vertical).or(*all).unwrap_or(0);
+            let pad_bottom = bottom.or(*vertical).or(*all).unwrap_or(0);
+            let pad_left = left.or(*horizontal).or(*all).unwrap_or(0);
+            let pad_right = right.or(*horizontal).or(*all).unwrap_or(0);
+
+            if pad_top == 0 && pad_bottom == 0 && pad_left == 0 && pad_right == 0 {
+                return Err(ImgEditError::InvalidParameter(
+                    "At least one padding value must be specified".to_string(),
+                ));
+            }
+
+            let pad_color = color_arg(color, "color")?;
+            let edge_color = |edge: &Option<String>, field| {
+                edge.as_deref()
+                    .map(|c| color_arg(c, field))
+                    .transpose()
+                    .map(|c| c.unwrap_or(pad_color))
+            };
+            let colors = ops::EdgeColors {
+                top: edge_color(top_color, "top-color")?,
+                bottom: edge_color(bottom_color, "bottom-color")?,
+                left: edge_color(left_color, "left-color")?,
+                right: edge_color(right_color, "right-color")?,
+            };
+            let result = ops::pad_edges(&img, pad_top, pad_bottom, pad_left, pad_right, colors)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("pad").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Frame(FrameArgs {
+            width,
+            color,
+            inner_width,
+            inner_color,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let outer = color_arg(color, "color")?;
+            let inner = color_arg(inner_color, "inner-color")?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::frame(&img, *width, outer, *inner_width, inner)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("frame").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Shadow(ShadowArgs {
+            offset_x,
+            offset_y,
+            blur,
+            color,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let shadow_color = color_arg(color, "color")?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::shadow(&img, *offset_x, *offset_y, *blur, shadow_color)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("shadow").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Checkerboard(CheckerboardArgs {
+            size,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::checkerboard(&img, *size)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("checkerboard")
+                    .with_input(&input.display().to_string())
+                    .with_parameter("size", *size),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Grid(GridArgs {
+            spacing,
+            color,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let line_color = color_arg(color, "color")?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::grid(&img, *spacing, line_color)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("grid")
+                    .with_input(&input.display().to_string())
+                    .with_parameter("spacing", *spacing)
+                    .with_parameter("color", color.as_str()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::FloodRemove(FloodRemoveArgs {
+            x,
+            y,
+            tolerance,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let result = ops::flood_remove(&img, *x, *y, *tolerance)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                SuccessResponse::new("flood-remove").with_input(&input.display().to_string()),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Canvas(CanvasArgs {
+            width,
+            height,
+            anchor,
+            color,
+            background_image,
+            scale_content,
+            no_crop,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let mut img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let mut response = SuccessResponse::new("canvas")
+                .with_input(&input.display().to_string())
+                .with_parameter("width", *width)
+                .with_parameter("height", *height)
+                .with_parameter("anchor", value_name(*anchor));
+            if *scale_content {
+                img = ops::fit(
+                    &img,
+                    Some(*width),
+                    Some(*height),
+                    FitMode::Both,
+                    ResizeFilter::Lanczos,
+                )?;
+                response = response
+                    .with_detail("content_width", img.width())
+                    .with_detail("content_height", img.height());
+            }
+            if *no_crop {
+                ops::check_canvas_fits(&img, *width, *height)?;
+            }
+
+            let result = match background_image {
+                Some(path) => {
+                    let texture = ops::load_image(path)?;
+                    ops::canvas_resize_textured(&img, *width, *height, *anchor, &texture)?
+                }
+                None => {
+                    let bg_color = color_arg(color, "color")?;
+                    response = response.with_parameter("color", color_to_hex(bg_color));
+                    ops::canvas_resize(&img, *width, *height, *anchor, bg_color)?
+                }
+            };
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                response,
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Annotate(AnnotateArgs {
+            text,
+            timestamp,
+            time_format,
+            anchor,
+            margin,
+            scale,
+            color,
+            input,
+            output,
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let img = ops::load_image(input)?;
+            let orig_width = img.width();
+            let orig_height = img.height();
+
+            let mut response =
+                SuccessResponse::new("annotate").with_input(&input.display().to_string());
+            let label = match text {
+                Some(t) if !*timestamp => t.clone(),
+                _ => {
+                    // Formats without EXIF support are treated like missing EXIF
+                    let captured = ops::read_exif(input)
+                        .ok()
+                        .and_then(|data| data.date_time)
+                        .and_then(|dt| ops::Timestamp::from_exif(&dt));
+                    response = response.with_detail(
+                        "time_source",
+                        if captured.is_some() { "exif" } else { "now" },
+                    );
+                    captured
+                        .unwrap_or_else(ops::Timestamp::now)
+                        .format(time_format)
+                }
+            };
+
+            let text_color = color_arg(color, "color")?;
+            let result =
+                ops::draw_text_anchored(&img, &label, *anchor, *margin, *scale, text_color)?;
+
+            save_and_respond(
+                &result,
+                output,
+                cli,
+                format,
+                response.with_detail("text", label),
+                (orig_width, orig_height),
+            )
+        }
+
+        Command::Composite(CompositeArgs {
+            x,
+            y,
+            anchor,
+            position,
+            opacity,
+            blend,
+            chroma_key,
+            chroma_tolerance,
+            mask_output,
+            feather,
+            guides,
+            grid_spacing,
+            grid_color,
+            base,
+            overlay,
+            output,
+        }) => {
+            if cli.in_place {
+                return Err(ImgEditError::InvalidParameter(
+                    "--in-place is not supported for composite; give an OUTPUT path".to_string(),
+                ));
+            }
+            check_output(output, cli)?;
+            if let Some(mask_path) = mask_output {
+                if mask_path == output
+                    || [output, base, overlay]
+                        .iter()
+                        .any(|path| same_file(mask_path, path))
+                {
+                    return Err(ImgEditError::InvalidParameter(
+                        "--mask-output must differ from BASE, OVERLAY and OUTPUT".to_string(),
+                    )
+                    .for_field("mask-output"));
+                }
+                check_output(mask_path, cli)?;
+            }
+            let guide_color = guides
+                .then(|| color_arg(grid_color, "grid-color"))
+                .transpose()?;
+            let base_img = ops::load_image(base)?;
+            let mut overlay_img = ops::load_image(overlay)?;
+            let mut response =
+                SuccessResponse::new("composite").with_input(&base.display().to_string());
+            let mut mask = None;
+            if let Some(key) = chroma_key {
+                let key_color = color_arg(key, "chroma-key")?;
+                if let Some(mask_path) = mask_output {
+                    mask = Some((
+                        mask_path,
+                        ops::chroma_key_mask(&overlay_img, key_color, *chroma_tolerance),
+                    ));
+                    response = response.with_detail("mask_output", mask_path.display().to_string());
+                }
+                overlay_img = ops::chroma_key(&overlay_img, key_color, *chroma_tolerance);
+                if let Some(radius) = feather {
+                    overlay_img = ops::feather_alpha(&overlay_img, *radius)?;
+                }
+            }
+            let orig_width = base_img.width();
+            let orig_height = base_img.height();
+
+            let (pos_x, pos_y) = match position {
+                Some(spec) => parse_position(
+                    spec,
+                    (base_img.width(), base_img.height()),
+                    (overlay_img.width(), overlay_img.height()),
+                )?,
+                None => (x.unwrap_or(0), y.unwrap_or(0)),
+            };
+            let result = ops::composite(
+                &base_img,
+                &overlay_img,
+                pos_x,
+                pos_y,
+                *anchor,
+                *opacity,
+                *blend,
+            )
+            .map_err(|e| e.for_field("opacity"))?;
+            response = response.with_detail(
+                "overlay_visible_fraction",
+                ops::overlay_visible_fraction(&base_img, &overlay_img, pos_x, pos_y, *anchor),
+            );
+            // Guides go on last so they mark the final positions
+            let result = match guide_color {
+                Some(color) => {
+                    response = response.with_parameter("grid_spacing", *grid_spacing);
+                    ops::grid(&result, *grid_spacing, color)?
+                }
+                None => result,
+            };
+
+            // The mask is written once the composite is, so a failure in
+            // between leaves no stray mask behind
+            let (response, result_dim) = save_result(&result, None, output, cli, response)?;
+            if let Some((mask_path, mask)) = mask {
+                write_output(mask_path, cli, |path| {
+                    mask.save(path).map_err(|e| ImgEditError::WriteError {
+                        path: mask_path.display().to_string(),
+                        reason: e.to_string(),
+                    })
+                })?;
+            }
+            respond(
+                output,
+                cli,
                 format,
-                cli.quiet,
-                "contrast",
-                &input.display().to_string(),
+                response,
                 (orig_width, orig_height),
-            )
+                result_dim,
+            );
+            Ok(exit_codes::SUCCESS)
         }
 
-        Command::Gamma {
-            value,
+        Command::GradientOverlay(GradientOverlayArgs {
+            start_color,
+            end_color,
+            angle,
+            opacity,
+            blend,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
+            let start = color_arg(start_color, "start-color")?;
+            let end = color_arg(end_color, "end-color")?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
-            let result = ops::gamma(&img, *value)?;
+            let result = ops::gradient_overlay(&img, start, end, *angle, *opacity, *blend)
+                .map_err(|e| e.for_field("opacity"))?;
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "gamma",
-                &input.display().to_string(),
+                SuccessResponse::new("gradient-overlay")
+                    .with_input(&input.display().to_string())
+                    .with_parameter("start_color", start_color.as_str())
+                    .with_parameter("end_color", end_color.as_str())
+                    .with_parameter("angle", *angle)
+                    .with_parameter("opacity", *opacity)
+                    .with_parameter("blend", value_name(*blend)),
                 (orig_width, orig_height),
             )
         }
 
-        Command::Blur {
-            radius,
+        Command::Generate(GenerateArgs {
+            width,
+            height,
+            color,
+            gradient,
+            output,
+        }) => {
+            if cli.in_place {
+                return Err(ImgEditError::InvalidParameter(
+                    "--in-place is not supported for generate; give an --output path".to_string(),
+                ));
+            }
+            check_output(output, cli)?;
+            let fill = color_arg(color, "color")?;
+            let stops = gradient
+                .as_deref()
+                .map(ops::parse_gradient_spec)
+                .transpose()
+                .map_err(|e| e.for_field("gradient"))?;
+
+            let result = ops::generate(*width, *height, fill, stops)?;
+            let mut response = SuccessResponse::new("generate")
+                .with_parameter("width", *width)
+                .with_parameter("height", *height);
+            response = match gradient {
+                Some(spec) => response.with_parameter("gradient", spec.as_str()),
+                None => response.with_parameter("color", color.as_str()),
+            };
+
+            save_and_respond(&result, output, cli, format, response, (*width, *height))
+        }
+
+        Command::Circle(CircleArgs {
+            crop,
+            border_width,
+            border_color,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
+            let ring_color = color_arg(border_color, "border-color")?;
 
-            let result = ops::blur(&img, *radius)?;
+            let result = ops::circle(&img, *crop, *border_width, ring_color)?;
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "blur",
-                &input.display().to_string(),
+                SuccessResponse::new("circle")
+                    .with_input(&input.display().to_string())
+                    .with_parameter("crop", *crop)
+                    .with_parameter("border_width", *border_width)
+                    .with_parameter("border_color", border_color.as_str()),
                 (orig_width, orig_height),
             )
         }
 
-        Command::Sharpen {
-            amount,
-            radius,
-            input,
+        Command::ContactSheet(ContactSheetArgs {
+            inputs,
+            input_dir,
+            columns,
+            cell_size,
+            label,
+            background,
+            label_color,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
-            let img = ops::load_image(input)?;
-            let orig_width = img.width();
-            let orig_height = img.height();
+        }) => {
+            if cli.in_place {
+                return Err(ImgEditError::InvalidParameter(
+                    "--in-place is not supported for contact-sheet; give an --output path"
+                        .to_string(),
+                ));
+            }
+            check_output(output, cli)?;
+            let background = color_arg(background, "background")?;
+            let label_color = color_arg(label_color, "label-color")?;
 
-            let result = ops::sharpen(&img, *amount, *radius)?;
+            let mut paths = inputs.clone();
+            if let Some(dir) = input_dir {
+                paths.extend(image_files_in(dir)?);
+            }
+            let mut items = Vec::with_capacity(paths.len());
+            for path in &paths {
+                let image = ops::load_image(path)?;
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let label = match label {
+                    SheetLabel::None => vec![],
+                    SheetLabel::Name => vec![name],
+                    SheetLabel::NameSize => {
+                        vec![name, format!("{}x{}", image.width(), image.height())]
+                    }
+                };
+                items.push(ops::SheetItem { image, label });
+            }
+
+            let result = ops::contact_sheet(&items, *columns, *cell_size, background, label_color)?;
+            let response = SuccessResponse::new("contact-sheet")
+                .with_detail("images", items.len())
+                .with_detail("columns", (*columns).min(items.len() as u32));
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "sharpen",
-                &input.display().to_string(),
-                (orig_width, orig_height),
+                response,
+                (result.width(), result.height()),
             )
         }
 
-        Command::Pad {
-            all,
-            top,
-            bottom,
-            left,
-            right,
-            horizontal,
-            vertical,
-            color,
-            input,
-            output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
-            let img = ops::load_image(input)?;
-            let orig_width = img.width();
-            let orig_height = img.height();
-
-            // Calculate padding values with priority: specific > axis > all
-            let pad_top = top.or(*vertical).or(*all).unwrap_or(0);
-            let pad_bottom = bottom.or(*vertical).or(*all).unwrap_or(0);
-            let pad_left = left.or(*horizontal).or(*all).unwrap_or(0);
-            let pad_right = right.or(*horizontal).or(*all).unwrap_or(0);
-
-            if pad_top == 0 && pad_bottom == 0 && pad_left == 0 && pad_right == 0 {
+        Command::Batch(BatchArgs { manifest }) => {
+            if cli.in_place {
                 return Err(ImgEditError::InvalidParameter(
-                    "At least one padding value must be specified".to_string(),
+                    "--in-place applies to single jobs; put it in a job's args".to_string(),
                 ));
             }
+            let jobs = ops::load_manifest(manifest).map_err(|e| e.for_field("manifest"))?;
+
+            let mut results = Vec::new();
+            let mut first_failure = None;
+            for (i, job) in jobs.iter().enumerate() {
+                let outcome = run_batch_job(job, cli);
+                let mut result = serde_json::json!({
+                    "job": i + 1,
+                    "command": job.command,
+                    "input": job.input,
+                    "output": job.output,
+                });
+                match outcome {
+                    Ok((code, ref response)) => {
+                        result["success"] = (code == exit_codes::SUCCESS).into();
+                        result["exit_code"] = code.into();
+                        if let Some(response) = response {
+                            if !response.details.is_empty() {
+                                result["details"] = serde_json::json!(response.details);
+                            }
+                            if !response.warnings.is_empty() {
+                                result["warnings"] = serde_json::json!(response.warnings);
+                            }
+                        }
+                    }
+                    Err(ref e) => {
+                        result["success"] = false.into();
+                        result["exit_code"] = e.exit_code().into();
+                        result["error"] = e.to_string().into();
+                        result["code"] = e.code().into();
+                    }
+                }
+                let code = result["exit_code"].as_i64().unwrap_or(1) as i32;
+                if code != exit_codes::SUCCESS {
+                    first_failure.get_or_insert(code);
+                }
 
-            let pad_color = parse_color(color)?;
-            let result = ops::pad(&img, pad_top, pad_bottom, pad_left, pad_right, pad_color)?;
+                if format == OutputFormat::Text && !cli.quiet {
+                    match &outcome {
+                        Ok((code, _)) if *code != exit_codes::SUCCESS => {
+                            println!("Job {} {}: exited with {}", i + 1, job.command, code)
+                        }
+                        Ok((_, response)) => {
+                            println!("Job {} {}: done", i + 1, job.command);
+                            if let Some(response) = response {
+                                print_warnings(&response.warnings);
+                            }
+                        }
+                        Err(e) => println!("Job {} {}: failed: {}", i + 1, job.command, e),
+                    }
+                }
+                results.push(result);
+            }
 
-            save_and_respond(
-                &result,
-                output,
-                format,
-                cli.quiet,
-                "pad",
-                &input.display().to_string(),
-                (orig_width, orig_height),
-            )
+            let failed = results.iter().filter(|r| r["success"] == false).count();
+            if format == OutputFormat::Json {
+                let mut response = SuccessResponse::new("batch")
+                    .with_input(&manifest.display().to_string())
+                    .with_detail("jobs", results.len())
+                    .with_detail("succeeded", results.len() - failed)
+                    .with_detail("failed", failed)
+                    .with_detail("results", results);
+                response.success = failed == 0;
+                println!("{}", response.to_json());
+            } else if !cli.quiet {
+                println!("{} of {} jobs succeeded", jobs.len() - failed, jobs.len());
+            }
+
+            Ok(first_failure.unwrap_or(exit_codes::SUCCESS))
         }
 
-        Command::Canvas {
-            width,
-            height,
-            anchor,
-            color,
+        Command::Apply(ApplyArgs {
+            recipe,
             input,
             output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
+        }) => {
+            let steps = ops::load_recipe(recipe).map_err(|e| e.for_field("recipe"))?;
+            let output = prepare_output(output.as_deref(), input, cli)?;
             let img = ops::load_image(input)?;
             let orig_width = img.width();
             let orig_height = img.height();
 
-            let bg_color = parse_color(color)?;
-            let result = ops::canvas_resize(&img, *width, *height, *anchor, bg_color)?;
+            let result = ops::apply_recipe(&img, &steps)?;
+            let operations: Vec<&str> = steps.iter().map(|step| step.name()).collect();
 
             save_and_respond(
                 &result,
                 output,
+                cli,
                 format,
-                cli.quiet,
-                "canvas",
-                &input.display().to_string(),
+                SuccessResponse::new("apply")
+                    .with_input(&input.display().to_string())
+                    .with_parameter("recipe", recipe.display().to_string())
+                    .with_detail("operations", operations),
                 (orig_width, orig_height),
             )
         }
+    }
+}
 
-        Command::Composite {
-            x,
-            y,
-            anchor,
-            opacity,
-            blend,
-            base,
-            overlay,
-            output,
-        } => {
-            check_output_overwrite(output, cli.overwrite)?;
-            let base_img = ops::load_image(base)?;
-            let overlay_img = ops::load_image(overlay)?;
-            let orig_width = base_img.width();
-            let orig_height = base_img.height();
-
-            let result = ops::composite(
-                &base_img,
-                &overlay_img,
-                x.unwrap_or(0),
-                y.unwrap_or(0),
-                *anchor,
-                *opacity,
-                *blend,
-            )?;
-
-            save_and_respond(
-                &result,
-                output,
-                format,
-                cli.quiet,
-                "composite",
-                &base.display().to_string(),
-                (orig_width, orig_height),
-            )
-        }
+/// Image files directly inside `dir`, judged by extension, in name order
+fn image_files_in(dir: &Path) -> mdimgedit::Result<Vec<std::path::PathBuf>> {
+    if !dir.is_dir() {
+        return Err(ImgEditError::InputNotFound(dir.display().to_string()));
     }
+    let mut files = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    files.retain(|path| path.is_file() && image::ImageFormat::from_path(path).is_ok());
+    files.sort();
+    Ok(files)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use mdimgedit::cli::args::{Anchor, BlendMode, ImageFormat, ResizeFilter};
+    use mdimgedit::cli::args::{BlendMode, ImageFormat};
     use std::path::PathBuf;
 
+    #[test]
+    fn test_confirm_overwrite() {
+        let path = Path::new("out.png");
+        let confirm = |input: &str| {
+            let mut prompt = Vec::new();
+            let agreed = confirm_overwrite(path, &mut input.as_bytes(), &mut prompt);
+            assert_eq!(
+                String::from_utf8(prompt).unwrap(),
+                "Overwrite out.png? [y/N] "
+            );
+            agreed
+        };
+
+        assert!(confirm("y\n"));
+        assert!(confirm("YES\n"));
+        assert!(!confirm("n\n"));
+        assert!(!confirm("\n"));
+        assert!(!confirm(""));
+    }
+
+    #[test]
+    fn test_create_temp_sibling() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let output = dir.path().join("out.png");
+
+        let first = create_temp_sibling(&output).unwrap();
+        let second = create_temp_sibling(&output).unwrap();
+        assert_ne!(first, second);
+        for temp in [&first, &second] {
+            assert!(temp.exists());
+            assert_eq!(temp.parent(), Some(dir.path()));
+            assert_eq!(temp.extension().unwrap(), "png");
+        }
+    }
+
     #[test]
     fn test_command_name() {
         let p = PathBuf::from("test.png");
 
-        assert_eq!(command_name(&Command::Info { input: p.clone() }), "info");
         assert_eq!(
-            command_name(&Command::Exif {
+            command_name(&Command::Info(InfoArgs {
+                assume_orientation: None,
+                channels: false,
+                output_format: None,
+                input: p.clone()
+            })),
+            "info"
+        );
+        assert_eq!(
+            command_name(&Command::Pixel(PixelArgs {
+                x: 0,
+                y: 0,
+                input: p.clone()
+            })),
+            "pixel"
+        );
+        assert_eq!(
+            command_name(&Command::RegionAverage(RegionAverageArgs {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1,
+                input: p.clone()
+            })),
+            "region-average"
+        );
+        assert_eq!(
+            command_name(&Command::Validate(ValidateArgs { input: p.clone() })),
+            "validate"
+        );
+        assert_eq!(
+            command_name(&Command::Audit(AuditArgs { dir: p.clone() })),
+            "audit"
+        );
+        assert_eq!(command_name(&Command::Schema), "schema");
+        assert_eq!(
+            command_name(&Command::Batch(BatchArgs {
+                manifest: p.clone()
+            })),
+            "batch"
+        );
+        assert_eq!(
+            command_name(&Command::Exif(ExifArgs {
                 verbose: false,
+                raw: false,
                 tag: None,
+                output_format: None,
                 input: p.clone()
-            }),
+            })),
             "exif"
         );
         assert_eq!(
-            command_name(&Command::Crop {
+            command_name(&Command::SuggestName(SuggestNameArgs {
+                pattern: "{year}".to_string(),
+                require_exif: false,
+                input: p.clone()
+            })),
+            "suggest-name"
+        );
+        assert_eq!(
+            command_name(&Command::SetOrientation(SetOrientationArgs {
+                orientation: 6,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "set-orientation"
+        );
+        assert_eq!(
+            command_name(&Command::Crop(CropArgs {
                 x: 0,
                 y: 0,
                 width: 10,
                 height: 10,
                 anchor: Anchor::TopLeft,
+                position: None,
+                pad_if_needed: false,
+                background: "transparent".to_string(),
+                keep_palette: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "crop"
         );
         assert_eq!(
-            command_name(&Command::Rotate {
-                degrees: 90.0,
+            command_name(&Command::Region(RegionArgs {
+                x: 0,
+                y: 0,
+                width: 10,
+                height: 10,
+                out_width: 20,
+                out_height: 20,
+                filter: ResizeFilter::Lanczos,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "region"
+        );
+        assert_eq!(
+            command_name(&Command::Rotate(RotateArgs {
+                degrees: Some(90.0),
+                auto: false,
+                max_angle: 15.0,
                 expand: false,
                 background: "transparent".to_string(),
+                background_image: None,
+                keep_palette: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "rotate"
         );
+        for (command, name) in [
+            (
+                Command::RotateLeft(RotateLeftArgs {
+                    keep_palette: false,
+                    input: p.clone(),
+                    output: Some(p.clone()),
+                }),
+                "rotate-left",
+            ),
+            (
+                Command::RotateRight(RotateRightArgs {
+                    keep_palette: false,
+                    input: p.clone(),
+                    output: Some(p.clone()),
+                }),
+                "rotate-right",
+            ),
+            (
+                Command::Rotate180(Rotate180Args {
+                    keep_palette: false,
+                    input: p.clone(),
+                    output: Some(p.clone()),
+                }),
+                "rotate-180",
+            ),
+        ] {
+            assert_eq!(command_name(&command), name);
+        }
         assert_eq!(
-            command_name(&Command::Flip {
+            command_name(&Command::Flip(FlipArgs {
                 horizontal: true,
                 vertical: false,
+                keep_palette: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "flip"
         );
         assert_eq!(
-            command_name(&Command::Resize {
+            command_name(&Command::Resize(ResizeArgs {
                 width: Some(10),
                 height: None,
                 scale: None,
-                filter: ResizeFilter::Lanczos,
+                keep_other: false,
+                filter: vec![ResizeFilter::Lanczos],
+                lanczos_a: None,
+                pad: false,
+                background: "transparent".to_string(),
+                downscale_only: false,
+                upscale_only: false,
+                linear: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "resize"
         );
         assert_eq!(
-            command_name(&Command::Fit {
+            command_name(&Command::Fit(FitArgs {
                 max_width: Some(10),
                 max_height: None,
                 upscale: false,
+                mode: None,
                 filter: ResizeFilter::Lanczos,
+                linear: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "fit"
         );
         assert_eq!(
-            command_name(&Command::Convert {
+            command_name(&Command::Scale(ScaleArgs {
+                width: 10,
+                height: 10,
+                mode: ScaleMode::Cover,
+                filter: ResizeFilter::Lanczos,
+                background: "transparent".to_string(),
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "scale"
+        );
+        assert_eq!(
+            command_name(&Command::Limit(LimitArgs {
+                max: 10,
+                filter: ResizeFilter::Lanczos,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "limit"
+        );
+        assert_eq!(
+            command_name(&Command::SmartCrop(SmartCropArgs {
+                width: 10,
+                height: 10,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "smart-crop"
+        );
+        assert_eq!(
+            command_name(&Command::SeamCarve(SeamCarveArgs {
+                width: Some(10),
+                height: None,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "seam-carve"
+        );
+        assert_eq!(
+            command_name(&Command::Convert(ConvertArgs {
                 format: Some(ImageFormat::Png),
                 quality: 90,
+                frame: None,
+                optimize: false,
+                assign_profile: None,
+                dpi: None,
+                lossless: false,
+                only_if_smaller: false,
+                low_memory: false,
+                png_color_type: PngColorType::Auto,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "convert"
         );
         assert_eq!(
-            command_name(&Command::Grayscale {
+            command_name(&Command::Grayscale(GrayscaleArgs {
                 no_preserve_alpha: false,
+                method: GrayscaleMethod::Rgba,
+                weights: None,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "grayscale"
         );
         assert_eq!(
-            command_name(&Command::Depth {
+            command_name(&Command::Depth(DepthArgs {
                 bits: 8,
                 dither: false,
+                dither_algo: None,
+                dither_strength: 1.0,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "depth"
         );
         assert_eq!(
-            command_name(&Command::Invert {
+            command_name(&Command::Invert(InvertArgs {
                 invert_alpha: false,
+                low_memory: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "invert"
         );
         assert_eq!(
-            command_name(&Command::Brightness {
+            command_name(&Command::AlphaThreshold(AlphaThresholdArgs {
+                value: 128,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "alpha-threshold"
+        );
+        assert_eq!(
+            command_name(&Command::Premultiply(PremultiplyArgs {
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "premultiply"
+        );
+        assert_eq!(
+            command_name(&Command::Unpremultiply(UnpremultiplyArgs {
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "unpremultiply"
+        );
+        assert_eq!(
+            command_name(&Command::Brightness(BrightnessArgs {
                 value: 10,
+                clip_warn: false,
+                low_memory: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "brightness"
         );
         assert_eq!(
-            command_name(&Command::Contrast {
+            command_name(&Command::Contrast(ContrastArgs {
                 value: 1.0,
+                clip_warn: false,
+                low_memory: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "contrast"
         );
         assert_eq!(
-            command_name(&Command::Gamma {
+            command_name(&Command::Gamma(GammaArgs {
                 value: 1.0,
+                low_memory: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "gamma"
         );
         assert_eq!(
-            command_name(&Command::Blur {
+            command_name(&Command::Adjust(AdjustArgs {
+                brightness: Some(10),
+                contrast: None,
+                gamma: None,
+                saturation: None,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "adjust"
+        );
+        assert_eq!(
+            command_name(&Command::Curves(CurvesArgs {
+                all: None,
+                red: None,
+                green: None,
+                blue: Some("0,0;255,255".to_string()),
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "curves"
+        );
+        assert_eq!(
+            command_name(&Command::WhiteBalance(WhiteBalanceArgs {
+                gray_x: Some(0),
+                gray_y: Some(0),
+                temperature: None,
+                tint: None,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "white-balance"
+        );
+        assert_eq!(
+            command_name(&Command::Blur(BlurArgs {
                 radius: 1.0,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "blur"
         );
         assert_eq!(
-            command_name(&Command::Sharpen {
+            command_name(&Command::Sharpen(SharpenArgs {
                 amount: 1.0,
                 radius: 1.0,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "sharpen"
         );
         assert_eq!(
-            command_name(&Command::Pad {
+            command_name(&Command::Bilateral(BilateralArgs {
+                spatial_sigma: 3.0,
+                range_sigma: 30.0,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "bilateral"
+        );
+        assert_eq!(
+            command_name(&Command::Pad(PadArgs {
                 all: Some(10),
                 top: None,
                 bottom: None,
@@ -807,34 +3148,165 @@ mod tests {
                 horizontal: None,
                 vertical: None,
                 color: "transparent".to_string(),
+                top_color: None,
+                bottom_color: None,
+                left_color: None,
+                right_color: None,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "pad"
         );
         assert_eq!(
-            command_name(&Command::Canvas {
+            command_name(&Command::Frame(FrameArgs {
+                width: 5,
+                color: "black".to_string(),
+                inner_width: 0,
+                inner_color: "white".to_string(),
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "frame"
+        );
+        assert_eq!(
+            command_name(&Command::Shadow(ShadowArgs {
+                offset_x: 8,
+                offset_y: 8,
+                blur: 8.0,
+                color: "black".to_string(),
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "shadow"
+        );
+        assert_eq!(
+            command_name(&Command::Checkerboard(CheckerboardArgs {
+                size: 8,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "checkerboard"
+        );
+        assert_eq!(
+            command_name(&Command::Grid(GridArgs {
+                spacing: 50,
+                color: "#FF00FF80".to_string(),
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "grid"
+        );
+        assert_eq!(
+            command_name(&Command::FloodRemove(FloodRemoveArgs {
+                x: 0,
+                y: 0,
+                tolerance: 16,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "flood-remove"
+        );
+        assert_eq!(
+            command_name(&Command::Canvas(CanvasArgs {
                 width: 100,
                 height: 100,
                 anchor: Anchor::Center,
                 color: "transparent".to_string(),
+                background_image: None,
+                scale_content: false,
+                no_crop: false,
                 input: p.clone(),
-                output: p.clone()
-            }),
+                output: Some(p.clone())
+            })),
             "canvas"
         );
         assert_eq!(
-            command_name(&Command::Composite {
+            command_name(&Command::Annotate(AnnotateArgs {
+                text: Some("hi".to_string()),
+                timestamp: false,
+                time_format: "%Y".to_string(),
+                anchor: Anchor::BottomRight,
+                margin: 10,
+                scale: 2,
+                color: "white".to_string(),
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "annotate"
+        );
+        assert_eq!(
+            command_name(&Command::Composite(CompositeArgs {
                 x: None,
                 y: None,
                 anchor: None,
+                position: None,
                 opacity: 1.0,
                 blend: BlendMode::Normal,
+                chroma_key: None,
+                chroma_tolerance: 40,
+                mask_output: None,
+                feather: None,
+                guides: false,
+                grid_spacing: 50,
+                grid_color: "#FF00FF80".to_string(),
                 base: p.clone(),
                 overlay: p.clone(),
                 output: p.clone()
-            }),
+            })),
             "composite"
         );
+        assert_eq!(
+            command_name(&Command::GradientOverlay(GradientOverlayArgs {
+                start_color: "black".to_string(),
+                end_color: "transparent".to_string(),
+                angle: 90.0,
+                opacity: 1.0,
+                blend: BlendMode::Normal,
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "gradient-overlay"
+        );
+        assert_eq!(
+            command_name(&Command::Generate(GenerateArgs {
+                width: 100,
+                height: 50,
+                color: "black".to_string(),
+                gradient: None,
+                output: p.clone()
+            })),
+            "generate"
+        );
+        assert_eq!(
+            command_name(&Command::Circle(CircleArgs {
+                crop: true,
+                border_width: 0,
+                border_color: "white".to_string(),
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "circle"
+        );
+        assert_eq!(
+            command_name(&Command::ContactSheet(ContactSheetArgs {
+                inputs: vec![p.clone()],
+                input_dir: None,
+                columns: 4,
+                cell_size: 160,
+                label: SheetLabel::Name,
+                background: "white".to_string(),
+                label_color: "black".to_string(),
+                output: p.clone()
+            })),
+            "contact-sheet"
+        );
+        assert_eq!(
+            command_name(&Command::Apply(ApplyArgs {
+                recipe: p.clone(),
+                input: p.clone(),
+                output: Some(p.clone())
+            })),
+            "apply"
+        );
     }
 }
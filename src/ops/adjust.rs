@@ -1,15 +1,10 @@
-use crate::error::{ImgEditError, Result};
-use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
+use crate::error::{ImgEditError, Result, Warning};
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgba, RgbaImage};
 
 /// Adjust the brightness of an image
 /// value: -255 to 255 (0 = no change)
 pub fn brightness(img: &DynamicImage, value: i32) -> Result<DynamicImage> {
-    if !(-255..=255).contains(&value) {
-        return Err(ImgEditError::InvalidParameter(format!(
-            "Brightness value must be between -255 and 255, got {}",
-            value
-        )));
-    }
+    check_brightness(value)?;
 
     let rgba = img.to_rgba8();
     let (width, height) = (rgba.width(), rgba.height());
@@ -27,6 +22,16 @@ pub fn brightness(img: &DynamicImage, value: i32) -> Result<DynamicImage> {
     Ok(DynamicImage::ImageRgba8(result))
 }
 
+fn check_brightness(value: i32) -> Result<()> {
+    if !(-255..=255).contains(&value) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Brightness value must be between -255 and 255, got {}",
+            value
+        )));
+    }
+    Ok(())
+}
+
 fn adjust_channel(value: u8, adjustment: i32) -> u8 {
     (value as i32 + adjustment).clamp(0, 255) as u8
 }
@@ -34,12 +39,7 @@ fn adjust_channel(value: u8, adjustment: i32) -> u8 {
 /// Adjust the contrast of an image
 /// value: 0.0 to 10.0 (1.0 = no change)
 pub fn contrast(img: &DynamicImage, value: f64) -> Result<DynamicImage> {
-    if !(0.0..=10.0).contains(&value) {
-        return Err(ImgEditError::InvalidParameter(format!(
-            "Contrast value must be between 0.0 and 10.0, got {}",
-            value
-        )));
-    }
+    check_contrast(value)?;
 
     let rgba = img.to_rgba8();
     let (width, height) = (rgba.width(), rgba.height());
@@ -57,6 +57,16 @@ pub fn contrast(img: &DynamicImage, value: f64) -> Result<DynamicImage> {
     Ok(DynamicImage::ImageRgba8(result))
 }
 
+fn check_contrast(value: f64) -> Result<()> {
+    if !(0.0..=10.0).contains(&value) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Contrast value must be between 0.0 and 10.0, got {}",
+            value
+        )));
+    }
+    Ok(())
+}
+
 fn contrast_channel(value: u8, factor: f64) -> u8 {
     // Contrast adjustment around midpoint (128)
     let adjusted = ((value as f64 - 128.0) * factor + 128.0).round();
@@ -66,22 +76,8 @@ fn contrast_channel(value: u8, factor: f64) -> u8 {
 /// Apply gamma correction to an image
 /// value: 0.1 to 10.0 (1.0 = no change)
 pub fn gamma(img: &DynamicImage, value: f64) -> Result<DynamicImage> {
-    if !(0.1..=10.0).contains(&value) {
-        return Err(ImgEditError::InvalidParameter(format!(
-            "Gamma value must be between 0.1 and 10.0, got {}",
-            value
-        )));
-    }
-
-    // Build a lookup table for efficiency
-    // gamma < 1 lightens (raises dark values), gamma > 1 darkens (lowers mid values)
-    let lut: Vec<u8> = (0..=255)
-        .map(|i| {
-            let normalized = i as f64 / 255.0;
-            let corrected = normalized.powf(value);
-            (corrected * 255.0).round().clamp(0.0, 255.0) as u8
-        })
-        .collect();
+    check_gamma(value)?;
+    let lut = gamma_lut(value);
 
     let rgba = img.to_rgba8();
     let (width, height) = (rgba.width(), rgba.height());
@@ -99,6 +95,396 @@ pub fn gamma(img: &DynamicImage, value: f64) -> Result<DynamicImage> {
     Ok(DynamicImage::ImageRgba8(result))
 }
 
+fn check_gamma(value: f64) -> Result<()> {
+    if !(0.1..=10.0).contains(&value) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Gamma value must be between 0.1 and 10.0, got {}",
+            value
+        )));
+    }
+    Ok(())
+}
+
+/// Build a lookup table for efficiency
+/// gamma < 1 lightens (raises dark values), gamma > 1 darkens (lowers mid values)
+fn gamma_lut(value: f64) -> [u8; 256] {
+    let mut lut = [0u8; 256];
+    for (i, entry) in lut.iter_mut().enumerate() {
+        let normalized = i as f64 / 255.0;
+        let corrected = normalized.powf(value);
+        *entry = (corrected * 255.0).round().clamp(0.0, 255.0) as u8;
+    }
+    lut
+}
+
+/// A per-channel adjustment whose result for each value depends on nothing
+/// else, so it can be applied to any part of an image on its own
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointOp {
+    Brightness(i32),
+    Contrast(f64),
+    Gamma(f64),
+    Invert { invert_alpha: bool },
+}
+
+impl PointOp {
+    /// The lookup table applied to each color channel, after checking the
+    /// parameter is in range
+    pub fn lut(&self) -> Result<[u8; 256]> {
+        let table = |f: &dyn Fn(u8) -> u8| std::array::from_fn(|i| f(i as u8));
+        Ok(match *self {
+            PointOp::Brightness(value) => {
+                check_brightness(value)?;
+                table(&|v| adjust_channel(v, value))
+            }
+            PointOp::Contrast(value) => {
+                check_contrast(value)?;
+                table(&|v| contrast_channel(v, value))
+            }
+            PointOp::Gamma(value) => {
+                check_gamma(value)?;
+                gamma_lut(value)
+            }
+            PointOp::Invert { .. } => table(&|v| 255 - v),
+        })
+    }
+
+    /// Whether the table is applied to alpha as well
+    pub fn affects_alpha(&self) -> bool {
+        matches!(self, PointOp::Invert { invert_alpha: true })
+    }
+
+    /// Apply to a whole image, as the function of the same name does
+    pub fn apply(&self, img: &DynamicImage) -> Result<DynamicImage> {
+        match *self {
+            PointOp::Brightness(value) => brightness(img, value),
+            PointOp::Contrast(value) => contrast(img, value),
+            PointOp::Gamma(value) => gamma(img, value),
+            PointOp::Invert { invert_alpha } => crate::ops::color::invert(img, invert_alpha),
+        }
+    }
+}
+
+/// Parse a tone curve given as `x,y` control points separated by `;`, e.g.
+/// `0,0;128,160;255,255`, into a lookup table. Values between points are
+/// interpolated linearly; values before the first or after the last point
+/// take that point's output.
+pub fn parse_curve(spec: &str) -> Result<[u8; 256]> {
+    let invalid = |reason: &str| {
+        ImgEditError::InvalidParameter(format!("Invalid curve '{}': {}", spec, reason))
+    };
+
+    let mut points: Vec<(u8, u8)> = Vec::new();
+    for point in spec.split(';') {
+        let (x, y) = point
+            .split_once(',')
+            .ok_or_else(|| invalid("expected points as x,y separated by ';'"))?;
+        let parse = |v: &str| {
+            v.trim()
+                .parse::<u8>()
+                .map_err(|_| invalid("coordinates must be integers from 0 to 255"))
+        };
+        let (x, y) = (parse(x)?, parse(y)?);
+        if points.last().is_some_and(|&(last_x, _)| x <= last_x) {
+            return Err(invalid("x values must increase from point to point"));
+        }
+        points.push((x, y));
+    }
+    if points.len() < 2 {
+        return Err(invalid("at least two points are needed"));
+    }
+
+    let mut lut = [0u8; 256];
+    for (i, entry) in lut.iter_mut().enumerate() {
+        let i = i as f64;
+        let upper = points.iter().position(|&(x, _)| x as f64 >= i);
+        *entry = match upper {
+            None => points[points.len() - 1].1,
+            Some(0) => points[0].1,
+            Some(n) => {
+                let (x0, y0) = (points[n - 1].0 as f64, points[n - 1].1 as f64);
+                let (x1, y1) = (points[n].0 as f64, points[n].1 as f64);
+                (y0 + (y1 - y0) * (i - x0) / (x1 - x0)).round() as u8
+            }
+        };
+    }
+    Ok(lut)
+}
+
+/// Apply tone curves: `channels` holds a lookup table for each of red, green
+/// and blue (`None` passes that channel through), and `master` is applied to
+/// all three after them. Alpha is unchanged.
+pub fn curves(
+    img: &DynamicImage,
+    master: Option<&[u8; 256]>,
+    channels: [Option<&[u8; 256]>; 3],
+) -> Result<DynamicImage> {
+    // Fold each channel's curve and the master curve into one table
+    let luts: [[u8; 256]; 3] = std::array::from_fn(|c| {
+        std::array::from_fn(|i| {
+            let value = channels[c].map_or(i as u8, |lut| lut[i]);
+            master.map_or(value, |lut| lut[value as usize])
+        })
+    });
+
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        for (c, lut) in luts.iter().enumerate() {
+            pixel[c] = lut[pixel[c] as usize];
+        }
+    }
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// Daylight color temperature that `temperature_gains` corrects towards
+const NEUTRAL_KELVIN: f64 = 6500.0;
+
+/// Per-channel gains that make the pixel at (`x`, `y`) neutral gray while
+/// keeping its mean level (a von Kries-style diagonal correction).
+pub fn gray_point_gains(img: &DynamicImage, x: u32, y: u32) -> Result<[f64; 3]> {
+    if x >= img.width() || y >= img.height() {
+        return Err(ImgEditError::CropOutOfBounds(format!(
+            "Gray point ({}, {}) is outside image bounds {}x{}",
+            x,
+            y,
+            img.width(),
+            img.height()
+        )));
+    }
+
+    let [r, g, b, _] = img.get_pixel(x, y).0;
+    if r == 0 || g == 0 || b == 0 {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Gray point ({}, {}) has an empty channel (R={}, G={}, B={}) and cannot be made neutral",
+            x, y, r, g, b
+        )));
+    }
+    let target = (r as f64 + g as f64 + b as f64) / 3.0;
+    Ok([target / r as f64, target / g as f64, target / b as f64])
+}
+
+/// Per-channel gains that correct a photo lit at `kelvin` (2000 to 40000) to
+/// daylight. Green is kept at 1.0; `tint` (-100 to 100) then scales green by
+/// up to half, with positive values removing a green cast.
+pub fn temperature_gains(kelvin: f64, tint: f64) -> Result<[f64; 3]> {
+    if !(2000.0..=40000.0).contains(&kelvin) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Temperature must be between 2000 and 40000 K, got {}",
+            kelvin
+        )));
+    }
+    if !(-100.0..=100.0).contains(&tint) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Tint must be between -100 and 100, got {}",
+            tint
+        )));
+    }
+
+    let source = blackbody_rgb(kelvin);
+    let neutral = blackbody_rgb(NEUTRAL_KELVIN);
+    let gains: [f64; 3] = std::array::from_fn(|c| neutral[c] / source[c]);
+    Ok([gains[0] / gains[1], 1.0 - tint / 200.0, gains[2] / gains[1]])
+}
+
+/// Approximate RGB of a blackbody radiator at `kelvin` (Tanner Helland's fit
+/// of the CIE data), scaled 0-255
+fn blackbody_rgb(kelvin: f64) -> [f64; 3] {
+    let t = kelvin / 100.0;
+    let (r, g, b) = if t <= 66.0 {
+        (
+            255.0,
+            99.4708025861 * t.ln() - 161.1195681661,
+            if t <= 19.0 {
+                0.0
+            } else {
+                138.5177312231 * (t - 10.0).ln() - 305.0447927307
+            },
+        )
+    } else {
+        (
+            329.698727446 * (t - 60.0).powf(-0.1332047592),
+            288.1221695283 * (t - 60.0).powf(-0.0755148492),
+            255.0,
+        )
+    };
+    [r, g, b].map(|v| v.clamp(1.0, 255.0))
+}
+
+/// Scale the red, green and blue channels by `gains`, clamping to 0-255.
+/// Alpha is unchanged.
+pub fn white_balance(img: &DynamicImage, gains: [f64; 3]) -> Result<DynamicImage> {
+    if gains.iter().any(|g| !g.is_finite() || *g < 0.0) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "White balance gains must be non-negative, got {:?}",
+            gains
+        )));
+    }
+
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        for (c, gain) in gains.iter().enumerate() {
+            pixel[c] = (pixel[c] as f64 * gain).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// Adjust color saturation
+/// value: 0.0 to 10.0 (1.0 = no change, 0.0 = grayscale)
+pub fn saturation(img: &DynamicImage, value: f64) -> Result<DynamicImage> {
+    check_saturation(value)?;
+
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        saturate_pixel(pixel, value);
+    }
+
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+fn check_saturation(value: f64) -> Result<()> {
+    if !(0.0..=10.0).contains(&value) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Saturation value must be between 0.0 and 10.0, got {}",
+            value
+        )));
+    }
+    Ok(())
+}
+
+/// Scale each channel's distance from the pixel's Rec.601 luma
+fn saturate_pixel(pixel: &mut Rgba<u8>, factor: f64) {
+    let luma = 0.299 * pixel[0] as f64 + 0.587 * pixel[1] as f64 + 0.114 * pixel[2] as f64;
+    for channel in pixel.0.iter_mut().take(3) {
+        let adjusted = (luma + (*channel as f64 - luma) * factor).round();
+        *channel = adjusted.clamp(0.0, 255.0) as u8;
+    }
+}
+
+/// Channel values pushed past 0 or 255 by an adjustment, counted per R, G, B
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClipCounts {
+    pub shadows: [u64; 3],
+    pub highlights: [u64; 3],
+}
+
+impl ClipCounts {
+    pub fn total(&self) -> u64 {
+        self.shadows.iter().chain(&self.highlights).sum()
+    }
+}
+
+impl From<ClipCounts> for serde_json::Value {
+    fn from(counts: ClipCounts) -> Self {
+        let channels = |c: [u64; 3]| serde_json::json!({ "r": c[0], "g": c[1], "b": c[2] });
+        serde_json::json!({
+            "shadows": channels(counts.shadows),
+            "highlights": channels(counts.highlights),
+        })
+    }
+}
+
+/// Count the channel values that `brightness` followed by `contrast` would clip.
+/// Values already at 0 or 255 that stay there are not counted.
+pub fn clip_counts(img: &DynamicImage, brightness: i32, contrast: f64) -> ClipCounts {
+    // Per input value: 0 = kept, 1 = clipped to black, 2 = clipped to white
+    let mut clipped = [0u8; 256];
+    for (i, entry) in clipped.iter_mut().enumerate() {
+        let shifted = i as i32 + brightness;
+        let stretched = ((shifted.clamp(0, 255) as f64 - 128.0) * contrast + 128.0).round();
+        let out = contrast_channel(adjust_channel(i as u8, brightness), contrast);
+        let overflowed = !(0..=255).contains(&shifted) || !(0.0..=255.0).contains(&stretched);
+        if overflowed && out as usize != i {
+            *entry = if out == 0 { 1 } else { 2 };
+        }
+    }
+
+    let mut counts = ClipCounts::default();
+    for pixel in img.to_rgba8().pixels() {
+        for ch in 0..3 {
+            match clipped[pixel[ch] as usize] {
+                1 => counts.shadows[ch] += 1,
+                2 => counts.highlights[ch] += 1,
+                _ => {}
+            }
+        }
+    }
+    counts
+}
+
+/// Advisory warning when an adjustment clipped any channel values
+pub fn clip_warning(counts: &ClipCounts) -> Option<Warning> {
+    if counts.total() == 0 {
+        return None;
+    }
+    let shadows: u64 = counts.shadows.iter().sum();
+    let highlights: u64 = counts.highlights.iter().sum();
+    Some(Warning::new(
+        "CHANNELS_CLIPPED",
+        format!(
+            "Clipped {} channel values to black and {} to white",
+            shadows, highlights
+        ),
+    ))
+}
+
+/// Settings for `adjust`; the defaults leave the image unchanged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adjustments {
+    /// -255 to 255 (0 = no change)
+    pub brightness: i32,
+    /// 0.0 to 10.0 (1.0 = no change)
+    pub contrast: f64,
+    /// 0.1 to 10.0 (1.0 = no change)
+    pub gamma: f64,
+    /// 0.0 to 10.0 (1.0 = no change)
+    pub saturation: f64,
+}
+
+impl Default for Adjustments {
+    fn default() -> Self {
+        Self {
+            brightness: 0,
+            contrast: 1.0,
+            gamma: 1.0,
+            saturation: 1.0,
+        }
+    }
+}
+
+/// Apply brightness, contrast, gamma and saturation in one pass, in that order.
+///
+/// The per-channel steps are folded into a single lookup table, so the result
+/// matches running `brightness`, `contrast`, `gamma` and `saturation` one after
+/// another without the intermediate buffers.
+pub fn adjust(img: &DynamicImage, settings: &Adjustments) -> Result<DynamicImage> {
+    check_brightness(settings.brightness)?;
+    check_contrast(settings.contrast)?;
+    check_gamma(settings.gamma)?;
+    check_saturation(settings.saturation)?;
+
+    let gamma = gamma_lut(settings.gamma);
+    let mut lut = [0u8; 256];
+    for (i, entry) in lut.iter_mut().enumerate() {
+        let value = adjust_channel(i as u8, settings.brightness);
+        let value = contrast_channel(value, settings.contrast);
+        *entry = gamma[value as usize];
+    }
+
+    let saturate = settings.saturation != 1.0;
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        for channel in pixel.0.iter_mut().take(3) {
+            *channel = lut[*channel as usize];
+        }
+        if saturate {
+            saturate_pixel(pixel, settings.saturation);
+        }
+    }
+
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -252,4 +638,181 @@ mod tests {
         assert!(gamma(&img, 0.0).is_err());
         assert!(gamma(&img, 15.0).is_err());
     }
+
+    #[test]
+    fn test_saturation_zero_is_gray() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_pixel(1, 1, Rgba([200, 50, 50, 255])));
+        let pixel = *saturation(&img, 0.0).unwrap().to_rgba8().get_pixel(0, 0);
+        assert_eq!(pixel[0], pixel[1]);
+        assert_eq!(pixel[1], pixel[2]);
+        assert_eq!(pixel[3], 255);
+    }
+
+    #[test]
+    fn test_saturation_boost_spreads_channels() {
+        let img =
+            DynamicImage::ImageRgba8(ImageBuffer::from_pixel(1, 1, Rgba([150, 100, 100, 255])));
+        let pixel = *saturation(&img, 2.0).unwrap().to_rgba8().get_pixel(0, 0);
+        assert!(pixel[0] > 150);
+        assert!(pixel[1] < 100);
+        assert!(saturation(&img, -1.0).is_err());
+    }
+
+    #[test]
+    fn test_adjust_matches_sequential_ops() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(16, 16, |x, y| {
+            Rgba([(x * 16) as u8, (y * 16) as u8, ((x + y) * 8) as u8, 200])
+        }));
+        let settings = Adjustments {
+            brightness: 20,
+            contrast: 1.4,
+            gamma: 0.8,
+            saturation: 1.5,
+        };
+
+        let combined = adjust(&img, &settings).unwrap().to_rgba8();
+
+        let sequential = brightness(&img, 20).unwrap();
+        let sequential = contrast(&sequential, 1.4).unwrap();
+        let sequential = gamma(&sequential, 0.8).unwrap();
+        let sequential = saturation(&sequential, 1.5).unwrap().to_rgba8();
+
+        assert_eq!(combined, sequential);
+    }
+
+    #[test]
+    fn test_adjust_defaults_are_identity() {
+        let img = create_test_image();
+        let result = adjust(&img, &Adjustments::default()).unwrap();
+        assert_eq!(result.to_rgba8(), img.to_rgba8());
+    }
+
+    #[test]
+    fn test_adjust_invalid_value() {
+        let img = create_test_image();
+        let settings = Adjustments {
+            gamma: 0.0,
+            ..Adjustments::default()
+        };
+        assert!(adjust(&img, &settings).is_err());
+    }
+
+    #[test]
+    fn test_clip_counts_contrast_highlights() {
+        let img =
+            DynamicImage::ImageRgba8(ImageBuffer::from_pixel(4, 2, Rgba([220, 200, 128, 255])));
+        let counts = clip_counts(&img, 0, 3.0);
+        // (220 - 128) * 3 + 128 and (200 - 128) * 3 + 128 overflow; 128 stays put
+        assert_eq!(counts.highlights, [8, 8, 0]);
+        assert_eq!(counts.shadows, [0, 0, 0]);
+        assert!(clip_warning(&counts).is_some());
+    }
+
+    #[test]
+    fn test_clip_counts_ignores_unchanged_extremes() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_pixel(2, 2, Rgba([255, 0, 100, 255])));
+        let counts = clip_counts(&img, 0, 1.0);
+        assert_eq!(counts.total(), 0);
+        assert!(clip_warning(&counts).is_none());
+
+        let counts = clip_counts(&img, -120, 1.0);
+        assert_eq!(counts.shadows, [0, 0, 4]);
+    }
+
+    #[test]
+    fn test_parse_curve() {
+        let lut = parse_curve("0,0;128,192;255,255").unwrap();
+        assert_eq!((lut[0], lut[64], lut[128], lut[255]), (0, 96, 192, 255));
+
+        // Flat outside the given points
+        let lut = parse_curve("50,10; 200,240").unwrap();
+        assert_eq!((lut[0], lut[50], lut[200], lut[255]), (10, 10, 240, 240));
+
+        assert!(parse_curve("0,0").is_err());
+        assert!(parse_curve("0,0;0,255").is_err());
+        assert!(parse_curve("0,0;300,255").is_err());
+        assert!(parse_curve("0,0;255").is_err());
+    }
+
+    #[test]
+    fn test_curves_blue_only() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(16, 1, |x, _| {
+            let v = (x * 17) as u8;
+            Rgba([v, v, v, 200])
+        }));
+        let lift = parse_curve("0,0;128,170;255,255").unwrap();
+        let result = curves(&img, None, [None, None, Some(&lift)])
+            .unwrap()
+            .to_rgba8();
+
+        for (src, dst) in img.to_rgba8().pixels().zip(result.pixels()) {
+            assert_eq!(dst[0], src[0]);
+            assert_eq!(dst[1], src[1]);
+            assert_eq!(dst[3], src[3]);
+            if (64..=192).contains(&src[2]) {
+                assert!(dst[2] > src[2], "{} -> {}", src[2], dst[2]);
+            }
+        }
+        assert_eq!(result.get_pixel(0, 0)[2], 0);
+        assert_eq!(result.get_pixel(15, 0)[2], 255);
+    }
+
+    #[test]
+    fn test_curves_master_follows_channel_curve() {
+        let img = create_test_image();
+        let invert = parse_curve("0,255;255,0").unwrap();
+        let result = curves(&img, Some(&invert), [Some(&invert), None, None])
+            .unwrap()
+            .to_rgba8();
+        // Red is inverted twice, green and blue once
+        assert_eq!(result.get_pixel(0, 0), &Rgba([128, 127, 127, 255]));
+    }
+
+    #[test]
+    fn test_white_balance_gray_point_removes_cast() {
+        // A gray ramp under a uniform blue cast
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(8, 8, |x, _| {
+            let v = 40 + x * 20;
+            Rgba([
+                (v * 8 / 10) as u8,
+                (v * 9 / 10) as u8,
+                (v + 40).min(255) as u8,
+                255,
+            ])
+        }));
+        for x in [2, 5] {
+            let gains = gray_point_gains(&img, x, 3).unwrap();
+            let result = white_balance(&img, gains).unwrap().to_rgba8();
+            let pixel = result.get_pixel(x, 0);
+            let spread = pixel.0[..3].iter().max().unwrap() - pixel.0[..3].iter().min().unwrap();
+            assert!(spread <= 1, "{:?}", pixel);
+            assert_eq!(pixel[3], 255);
+        }
+
+        assert_eq!(
+            gray_point_gains(&img, 8, 0).unwrap_err().code(),
+            "CROP_OUT_OF_BOUNDS"
+        );
+        let black = create_gray_image(0);
+        assert!(gray_point_gains(&black, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_temperature_gains() {
+        let neutral = temperature_gains(6500.0, 0.0).unwrap();
+        assert!(
+            neutral.iter().all(|g| (g - 1.0).abs() < 1e-9),
+            "{:?}",
+            neutral
+        );
+
+        // Warm (tungsten) light is corrected by cutting red and boosting blue
+        let [r, g, b] = temperature_gains(3000.0, 0.0).unwrap();
+        assert!(r < 1.0 && b > 1.0, "{:?}", [r, g, b]);
+        assert_eq!(g, 1.0);
+
+        assert_eq!(temperature_gains(6500.0, 50.0).unwrap()[1], 0.75);
+        assert!(temperature_gains(1000.0, 0.0).is_err());
+        assert!(temperature_gains(6500.0, 150.0).is_err());
+    }
 }
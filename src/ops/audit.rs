@@ -0,0 +1,213 @@
+use crate::error::{ImgEditError, Result};
+use crate::ops::exif::read_exif;
+use crate::ops::info::{format_file_size, get_image_info};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Smallest, largest and mean value of one image dimension
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct DimensionStats {
+    pub min: u32,
+    pub max: u32,
+    pub mean: f64,
+}
+
+impl From<DimensionStats> for serde_json::Value {
+    fn from(stats: DimensionStats) -> Self {
+        serde_json::json!({ "min": stats.min, "max": stats.max, "mean": stats.mean })
+    }
+}
+
+/// Aggregate statistics over the images in a directory tree
+#[derive(Debug, Default, Serialize)]
+pub struct AuditSummary {
+    pub directory: String,
+    /// Files that decoded as images
+    pub images: u64,
+    /// Files that could not be read as images
+    pub skipped: u64,
+    /// Image count per format name, e.g. `PNG`
+    pub formats: BTreeMap<String, u64>,
+    /// Combined size of the image files
+    pub total_bytes: u64,
+    /// `None` when no images were found
+    pub width: Option<DimensionStats>,
+    pub height: Option<DimensionStats>,
+    pub with_alpha: u64,
+    pub with_exif: u64,
+}
+
+impl AuditSummary {
+    pub fn display(&self) -> String {
+        let formats = if self.formats.is_empty() {
+            "none".to_string()
+        } else {
+            self.formats
+                .iter()
+                .map(|(format, count)| format!("{} {}", format, count))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let stats = |stats: Option<DimensionStats>| match stats {
+            Some(s) => format!("min {}, max {}, mean {:.1}", s.min, s.max, s.mean),
+            None => "n/a".to_string(),
+        };
+        format!(
+            "Directory: {}\n\
+             Images: {} ({} skipped)\n\
+             Formats: {}\n\
+             Total Size: {}\n\
+             Width: {}\n\
+             Height: {}\n\
+             With Alpha: {}\n\
+             With EXIF: {}",
+            self.directory,
+            self.images,
+            self.skipped,
+            formats,
+            format_file_size(self.total_bytes),
+            stats(self.width),
+            stats(self.height),
+            self.with_alpha,
+            self.with_exif
+        )
+    }
+}
+
+/// All regular files under `dir`, recursively, in path order. Symlinked
+/// directories are not descended into, so a link back up the tree cannot
+/// loop; symlinks to files are followed.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.and_then(|e| Ok((e.path(), e.file_type()?))))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+
+    for (path, file_type) in entries {
+        if file_type.is_dir() {
+            collect_files(&path, files)?;
+        } else if path.is_file() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Walk `dir` and summarize every image in it without modifying anything.
+/// Files that are not readable images are counted as skipped.
+pub fn audit(dir: &Path) -> Result<AuditSummary> {
+    if !dir.exists() {
+        return Err(ImgEditError::InputNotFound(dir.display().to_string()));
+    }
+    if !dir.is_dir() {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "'{}' is not a directory",
+            dir.display()
+        )));
+    }
+
+    let mut files = Vec::new();
+    collect_files(dir, &mut files)?;
+
+    let mut summary = AuditSummary {
+        directory: dir.display().to_string(),
+        ..Default::default()
+    };
+    let (mut widths, mut heights) = (Vec::new(), Vec::new());
+    for path in &files {
+        let Ok(info) = get_image_info(path) else {
+            summary.skipped += 1;
+            continue;
+        };
+
+        summary.images += 1;
+        *summary.formats.entry(info.format).or_insert(0) += 1;
+        summary.total_bytes += info.file_size_bytes;
+        widths.push(info.width);
+        heights.push(info.height);
+        if info.has_alpha {
+            summary.with_alpha += 1;
+        }
+        // Formats without an EXIF container simply have none
+        if read_exif(path).is_ok_and(|exif| exif.has_exif) {
+            summary.with_exif += 1;
+        }
+    }
+
+    summary.width = dimension_stats(&widths);
+    summary.height = dimension_stats(&heights);
+    Ok(summary)
+}
+
+fn dimension_stats(values: &[u32]) -> Option<DimensionStats> {
+    let min = *values.iter().min()?;
+    let max = *values.iter().max()?;
+    let sum: u64 = values.iter().map(|&v| v as u64).sum();
+    Some(DimensionStats {
+        min,
+        max,
+        mean: sum as f64 / values.len() as f64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbImage, RgbaImage};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_audit_counts_formats_and_alpha() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("nested");
+        fs::create_dir(&nested).unwrap();
+
+        DynamicImage::ImageRgba8(RgbaImage::new(10, 20))
+            .save(temp_dir.path().join("a.png"))
+            .unwrap();
+        DynamicImage::ImageRgb8(RgbImage::new(30, 40))
+            .save(nested.join("b.jpg"))
+            .unwrap();
+        fs::write(temp_dir.path().join("notes.txt"), "not an image").unwrap();
+
+        let summary = audit(temp_dir.path()).unwrap();
+        assert_eq!(summary.images, 2);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.formats.get("PNG"), Some(&1));
+        assert_eq!(summary.formats.get("JPEG"), Some(&1));
+        assert_eq!(summary.with_alpha, 1);
+        assert_eq!(summary.with_exif, 0);
+
+        let width = summary.width.unwrap();
+        assert_eq!((width.min, width.max, width.mean), (10, 30, 20.0));
+        assert!(summary.display().contains("Formats: JPEG 1, PNG 1"));
+    }
+
+    #[test]
+    fn test_audit_empty_and_missing() {
+        let temp_dir = TempDir::new().unwrap();
+        let summary = audit(temp_dir.path()).unwrap();
+        assert_eq!(summary.images, 0);
+        assert!(summary.width.is_none());
+
+        let missing = audit(&temp_dir.path().join("missing")).unwrap_err();
+        assert_eq!(missing.code(), "INPUT_NOT_FOUND");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_audit_skips_symlinked_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        DynamicImage::ImageRgba8(RgbaImage::new(4, 4))
+            .save(temp_dir.path().join("a.png"))
+            .unwrap();
+        // A link back to the root would recurse forever if followed
+        std::os::unix::fs::symlink(temp_dir.path(), temp_dir.path().join("loop")).unwrap();
+
+        let summary = audit(temp_dir.path()).unwrap();
+        assert_eq!(summary.images, 1);
+        assert_eq!(summary.skipped, 0);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::error::{ImgEditError, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// One job in a batch manifest: a command with its options, and the input
+/// and output paths that end its command line
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchJob {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    pub input: Option<String>,
+    pub output: Option<String>,
+}
+
+impl BatchJob {
+    /// The command line this job runs, as it would be typed after `mdimgedit`
+    pub fn argv(&self) -> Vec<String> {
+        let mut argv = vec![self.command.clone()];
+        argv.extend(self.args.iter().cloned());
+        argv.extend(self.input.iter().cloned());
+        argv.extend(self.output.iter().cloned());
+        argv
+    }
+}
+
+/// Commands that only report and write no file. A job's own output is
+/// silenced, so they would have no effect in a batch.
+const REPORT_COMMANDS: &[&str] = &[
+    "info",
+    "pixel",
+    "region-average",
+    "validate",
+    "audit",
+    "schema",
+    "exif",
+    "suggest-name",
+];
+
+/// Parse a manifest: a JSON array of jobs, e.g.
+/// `[{"command": "resize", "args": ["--width", "200"], "input": "a.png", "output": "b.png"}]`
+pub fn parse_manifest(json: &str) -> Result<Vec<BatchJob>> {
+    let invalid =
+        |reason: String| ImgEditError::InvalidParameter(format!("Invalid manifest: {}", reason));
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+    let jobs = manifest
+        .as_array()
+        .ok_or_else(|| invalid("expected an array of jobs".to_string()))?;
+    if jobs.is_empty() {
+        return Err(invalid("the manifest has no jobs".to_string()));
+    }
+
+    jobs.iter()
+        .enumerate()
+        .map(|(i, job)| {
+            let job =
+                BatchJob::deserialize(job).map_err(|e| invalid(format!("job {}: {}", i + 1, e)))?;
+            if job.command == "batch" {
+                return Err(invalid(format!("job {}: batches cannot be nested", i + 1)));
+            }
+            if REPORT_COMMANDS.contains(&job.command.as_str()) {
+                return Err(invalid(format!(
+                    "job {}: '{}' writes no file; batches run commands that produce an output",
+                    i + 1,
+                    job.command
+                )));
+            }
+            Ok(job)
+        })
+        .collect()
+}
+
+/// Read and parse a manifest file
+pub fn load_manifest(path: &Path) -> Result<Vec<BatchJob>> {
+    if !path.exists() {
+        return Err(ImgEditError::InputNotFound(path.display().to_string()));
+    }
+    let json = std::fs::read_to_string(path).map_err(|e| ImgEditError::ReadError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    parse_manifest(&json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifest() {
+        let jobs = parse_manifest(
+            r#"[
+                {"command": "resize", "args": ["--width", "50"], "input": "a.png", "output": "b.png"},
+                {"command": "rotate-left", "input": "b.png"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(jobs.len(), 2);
+        assert_eq!(
+            jobs[0].argv(),
+            ["resize", "--width", "50", "a.png", "b.png"]
+        );
+        assert_eq!(jobs[1].argv(), ["rotate-left", "b.png"]);
+    }
+
+    #[test]
+    fn test_parse_manifest_errors() {
+        let err = parse_manifest(r#"[{"command": "flip"}, {"cmd": "flip"}]"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("job 2"), "{}", err);
+
+        let err = parse_manifest(r#"[{"command": "batch", "args": ["--manifest", "x.json"]}]"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("nested"), "{}", err);
+
+        let err = parse_manifest(r#"[{"command": "info", "input": "a.png"}]"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("writes no file"), "{}", err);
+
+        assert!(parse_manifest("[]").is_err());
+        assert!(parse_manifest(r#"{"jobs": []}"#).is_err());
+        assert!(parse_manifest("not json").is_err());
+    }
+}
@@ -1,6 +1,27 @@
 use crate::cli::args::{Anchor, BlendMode};
 use crate::error::{ImgEditError, Result};
-use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
+use image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgba, RgbaImage};
+
+/// Fill colors for each edge added by [`pad_edges`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeColors {
+    pub top: Rgba<u8>,
+    pub bottom: Rgba<u8>,
+    pub left: Rgba<u8>,
+    pub right: Rgba<u8>,
+}
+
+impl EdgeColors {
+    /// The same color on every edge
+    pub fn uniform(color: Rgba<u8>) -> Self {
+        Self {
+            top: color,
+            bottom: color,
+            left: color,
+            right: color,
+        }
+    }
+}
 
 /// Add padding around an image
 pub fn pad(
@@ -10,6 +31,21 @@ pub fn pad(
     left: u32,
     right: u32,
     color: Rgba<u8>,
+) -> Result<DynamicImage> {
+    pad_edges(img, top, bottom, left, right, EdgeColors::uniform(color))
+}
+
+/// Add padding around an image with a separate color per edge.
+///
+/// The top and bottom borders span the full width, so they take precedence
+/// over the left and right colors in the corners.
+pub fn pad_edges(
+    img: &DynamicImage,
+    top: u32,
+    bottom: u32,
+    left: u32,
+    right: u32,
+    colors: EdgeColors,
 ) -> Result<DynamicImage> {
     let rgba = img.to_rgba8();
     let (orig_width, orig_height) = rgba.dimensions();
@@ -23,8 +59,24 @@ pub fn pad(
         ));
     }
 
-    // Create new image filled with padding color
-    let mut result: RgbaImage = ImageBuffer::from_pixel(new_width, new_height, color);
+    let mut result: RgbaImage = ImageBuffer::from_pixel(new_width, new_height, colors.left);
+    fill_rect(
+        &mut result,
+        left + orig_width,
+        0,
+        right,
+        new_height,
+        colors.right,
+    );
+    fill_rect(&mut result, 0, 0, new_width, top, colors.top);
+    fill_rect(
+        &mut result,
+        0,
+        top + orig_height,
+        new_width,
+        bottom,
+        colors.bottom,
+    );
 
     // Copy original image to the padded position
     for y in 0..orig_height {
@@ -37,6 +89,43 @@ pub fn pad(
     Ok(DynamicImage::ImageRgba8(result))
 }
 
+/// Surround an image with a two-tone border: `width` pixels of `color` on the
+/// outside and `inner_width` pixels of `inner_color` next to the image
+pub fn frame(
+    img: &DynamicImage,
+    width: u32,
+    color: Rgba<u8>,
+    inner_width: u32,
+    inner_color: Rgba<u8>,
+) -> Result<DynamicImage> {
+    if width == 0 {
+        return Err(ImgEditError::InvalidParameter(
+            "Frame width must be greater than 0".to_string(),
+        ));
+    }
+
+    let inner = pad(
+        img,
+        inner_width,
+        inner_width,
+        inner_width,
+        inner_width,
+        inner_color,
+    )?;
+    pad(&inner, width, width, width, width, color)
+}
+
+/// Fill a rectangle with a solid color, clipped to the image bounds
+fn fill_rect(img: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    let x_end = x.saturating_add(width).min(img.width());
+    let y_end = y.saturating_add(height).min(img.height());
+    for py in y..y_end {
+        for px in x..x_end {
+            img.put_pixel(px, py, color);
+        }
+    }
+}
+
 /// Resize the canvas without scaling the image content
 pub fn canvas_resize(
     img: &DynamicImage,
@@ -51,16 +140,74 @@ pub fn canvas_resize(
         ));
     }
 
+    let canvas: RgbaImage = ImageBuffer::from_pixel(new_width, new_height, background);
+    Ok(DynamicImage::ImageRgba8(place_on_canvas(
+        canvas, img, anchor,
+    )))
+}
+
+/// Check that a `new_width`x`new_height` canvas holds all of `img`, for
+/// callers that would rather fail than let [`canvas_resize`] crop it
+pub fn check_canvas_fits(img: &DynamicImage, new_width: u32, new_height: u32) -> Result<()> {
+    if new_width < img.width() || new_height < img.height() {
+        return Err(ImgEditError::InvalidDimensions(format!(
+            "Canvas {}x{} is smaller than the {}x{} image and would crop it; \
+             use resize or fit to shrink the content instead",
+            new_width,
+            new_height,
+            img.width(),
+            img.height()
+        )));
+    }
+    Ok(())
+}
+
+/// Resize the canvas like [`canvas_resize`], filling new areas with `texture` tiled
+/// from the top-left corner instead of a solid color. Translucent parts of
+/// `img` are composited over the texture, as a textured rotate does.
+pub fn canvas_resize_textured(
+    img: &DynamicImage,
+    new_width: u32,
+    new_height: u32,
+    anchor: Anchor,
+    texture: &DynamicImage,
+) -> Result<DynamicImage> {
+    if new_width == 0 || new_height == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Canvas dimensions must be positive".to_string(),
+        ));
+    }
+
+    let canvas = tile(texture, new_width, new_height)?;
+    composite(&canvas, img, 0, 0, Some(anchor), 1.0, BlendMode::Normal)
+}
+
+/// Repeat `texture` across a `width` x `height` image, starting at the top-left
+pub fn tile(texture: &DynamicImage, width: u32, height: u32) -> Result<DynamicImage> {
+    let texture = texture.to_rgba8();
+    let (tex_w, tex_h) = texture.dimensions();
+    if tex_w == 0 || tex_h == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Texture image must not be empty".to_string(),
+        ));
+    }
+
+    let tiled = ImageBuffer::from_fn(width, height, |x, y| {
+        *texture.get_pixel(x % tex_w, y % tex_h)
+    });
+    Ok(DynamicImage::ImageRgba8(tiled))
+}
+
+/// Copy `img` onto `canvas` at the anchor position, clipping anything outside
+fn place_on_canvas(mut canvas: RgbaImage, img: &DynamicImage, anchor: Anchor) -> RgbaImage {
     let rgba = img.to_rgba8();
     let (orig_width, orig_height) = rgba.dimensions();
+    let (new_width, new_height) = canvas.dimensions();
 
     // Calculate where to place the original image
     let (offset_x, offset_y) =
         calculate_anchor_offset(new_width, new_height, orig_width, orig_height, anchor);
 
-    // Create new canvas
-    let mut result: RgbaImage = ImageBuffer::from_pixel(new_width, new_height, background);
-
     // Copy pixels from original, handling clipping
     for y in 0..orig_height {
         for x in 0..orig_width {
@@ -70,15 +217,15 @@ pub fn canvas_resize(
             if dest_x >= 0 && dest_x < new_width as i64 && dest_y >= 0 && dest_y < new_height as i64
             {
                 let pixel = rgba.get_pixel(x, y);
-                result.put_pixel(dest_x as u32, dest_y as u32, *pixel);
+                canvas.put_pixel(dest_x as u32, dest_y as u32, *pixel);
             }
         }
     }
 
-    Ok(DynamicImage::ImageRgba8(result))
+    canvas
 }
 
-fn calculate_anchor_offset(
+pub(crate) fn calculate_anchor_offset(
     canvas_w: u32,
     canvas_h: u32,
     img_w: u32,
@@ -101,6 +248,56 @@ fn calculate_anchor_offset(
     }
 }
 
+/// Where [`composite`] places the top-left corner of `overlay` on `base`:
+/// at the `anchor` if one is given, else at (`x`, `y`)
+fn overlay_position(
+    base: &DynamicImage,
+    overlay: &DynamicImage,
+    x: i32,
+    y: i32,
+    anchor: Option<Anchor>,
+) -> (i32, i32) {
+    match anchor {
+        Some(anchor) => {
+            let (ax, ay) = calculate_anchor_offset(
+                base.width(),
+                base.height(),
+                overlay.width(),
+                overlay.height(),
+                anchor,
+            );
+            (ax as i32, ay as i32)
+        }
+        None => (x, y),
+    }
+}
+
+/// The fraction of `overlay`'s area that lands on `base` when composited
+/// with the same position arguments as [`composite`]: 1.0 when it fits
+/// entirely, 0.0 when it falls wholly outside
+pub fn overlay_visible_fraction(
+    base: &DynamicImage,
+    overlay: &DynamicImage,
+    x: i32,
+    y: i32,
+    anchor: Option<Anchor>,
+) -> f64 {
+    let area = overlay.width() as u64 * overlay.height() as u64;
+    if area == 0 {
+        return 0.0;
+    }
+
+    let (pos_x, pos_y) = overlay_position(base, overlay, x, y, anchor);
+    let overlap = |pos: i32, len: u32, base_len: u32| {
+        let start = (pos as i64).max(0);
+        let end = (pos as i64 + len as i64).min(base_len as i64);
+        (end - start).max(0) as u64
+    };
+    let visible = overlap(pos_x, overlay.width(), base.width())
+        * overlap(pos_y, overlay.height(), base.height());
+    visible as f64 / area as f64
+}
+
 /// Composite (overlay) one image onto another
 pub fn composite(
     base: &DynamicImage,
@@ -123,14 +320,7 @@ pub fn composite(
 
     let (base_w, base_h) = base_rgba.dimensions();
     let (overlay_w, overlay_h) = overlay_rgba.dimensions();
-
-    // Calculate position based on anchor or explicit coordinates
-    let (pos_x, pos_y) = if let Some(anch) = anchor {
-        let (ax, ay) = calculate_anchor_offset(base_w, base_h, overlay_w, overlay_h, anch);
-        (ax as i32, ay as i32)
-    } else {
-        (x, y)
-    };
+    let (pos_x, pos_y) = overlay_position(base, overlay, x, y, anchor);
 
     // Composite the overlay onto the base
     for oy in 0..overlay_h {
@@ -151,7 +341,179 @@ pub fn composite(
     Ok(DynamicImage::ImageRgba8(base_rgba))
 }
 
-fn blend_pixels(base: Rgba<u8>, overlay: Rgba<u8>, opacity: f32, mode: BlendMode) -> Rgba<u8> {
+/// Light and dark checkerboard shades, matching common image editors
+pub const CHECKER_LIGHT: Rgba<u8> = Rgba([255, 255, 255, 255]);
+pub const CHECKER_DARK: Rgba<u8> = Rgba([204, 204, 204, 255]);
+
+/// Flatten `img` over a checkerboard of `size`-pixel cells, giving an opaque
+/// preview of its transparency. The top-left cell is light.
+pub fn checkerboard(img: &DynamicImage, size: u32) -> Result<DynamicImage> {
+    if size == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Checkerboard cell size must be greater than 0".to_string(),
+        ));
+    }
+
+    let rgba = img.to_rgba8();
+    let flattened = ImageBuffer::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let cell = if (x / size + y / size).is_multiple_of(2) {
+            CHECKER_LIGHT
+        } else {
+            CHECKER_DARK
+        };
+        blend_pixels(cell, *rgba.get_pixel(x, y), 1.0, BlendMode::Normal)
+    });
+    Ok(DynamicImage::ImageRgba8(flattened))
+}
+
+/// Overlay a grid of 1-pixel `color` lines every `spacing` pixels, starting
+/// at the top-left edge, blended over the image so a translucent color keeps
+/// the content beneath visible.
+pub fn grid(img: &DynamicImage, spacing: u32, color: Rgba<u8>) -> Result<DynamicImage> {
+    if spacing == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Grid spacing must be greater than 0".to_string(),
+        ));
+    }
+
+    let mut rgba = img.to_rgba8();
+    for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+        if x.is_multiple_of(spacing) || y.is_multiple_of(spacing) {
+            *pixel = blend_pixels(*pixel, color, 1.0, BlendMode::Normal);
+        }
+    }
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// Make every pixel within `tolerance` of `key` fully transparent.
+/// Distance is the largest per-channel RGB difference, so a tolerance of 0
+/// keys out only exact matches.
+pub fn chroma_key(img: &DynamicImage, key: Rgba<u8>, tolerance: u8) -> DynamicImage {
+    let mut rgba = img.to_rgba8();
+
+    for pixel in rgba.pixels_mut() {
+        if is_keyed(pixel, key, tolerance) {
+            *pixel = Rgba([pixel[0], pixel[1], pixel[2], 0]);
+        }
+    }
+
+    DynamicImage::ImageRgba8(rgba)
+}
+
+/// Grayscale mask of the pixels [`chroma_key`] would remove:
+/// white where the key color matched, black elsewhere
+pub fn chroma_key_mask(img: &DynamicImage, key: Rgba<u8>, tolerance: u8) -> DynamicImage {
+    let rgba = img.to_rgba8();
+    let mask = ImageBuffer::from_fn(rgba.width(), rgba.height(), |x, y| {
+        if is_keyed(rgba.get_pixel(x, y), key, tolerance) {
+            Luma([255u8])
+        } else {
+            Luma([0u8])
+        }
+    });
+
+    DynamicImage::ImageLuma8(mask)
+}
+
+/// Soften hard alpha edges by blurring only the alpha channel.
+/// radius: feather width in pixels (0.1 to 100.0); color channels are untouched.
+pub fn feather_alpha(img: &DynamicImage, radius: f32) -> Result<DynamicImage> {
+    if !(0.1..=100.0).contains(&radius) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Feather radius must be between 0.1 and 100.0, got {}",
+            radius
+        )));
+    }
+
+    let mut rgba = img.to_rgba8();
+    let alpha: GrayImage = ImageBuffer::from_fn(rgba.width(), rgba.height(), |x, y| {
+        Luma([rgba.get_pixel(x, y)[3]])
+    });
+
+    // Same radius-to-sigma mapping as the blur command
+    let blurred = imageproc::filter::gaussian_blur_f32(&alpha, radius / 3.0);
+
+    for (pixel, a) in rgba.pixels_mut().zip(blurred.pixels()) {
+        pixel[3] = a[0];
+    }
+
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// Render a drop shadow behind an image.
+///
+/// The canvas grows to fit the shadow at (`offset_x`, `offset_y`) plus `blur`
+/// pixels of falloff; the image's alpha is stamped in `color`, blurred as by
+/// `feather_alpha`, and the original is composited on top. The surround is
+/// transparent.
+pub fn shadow(
+    img: &DynamicImage,
+    offset_x: i32,
+    offset_y: i32,
+    blur: f32,
+    color: Rgba<u8>,
+) -> Result<DynamicImage> {
+    if !(0.0..=100.0).contains(&blur) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Shadow blur must be between 0.0 and 100.0, got {}",
+            blur
+        )));
+    }
+
+    let rgba = img.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let margin = blur.ceil() as u32;
+    let (dx, dy) = (offset_x.unsigned_abs(), offset_y.unsigned_abs());
+
+    let canvas_w = width + dx + 2 * margin;
+    let canvas_h = height + dy + 2 * margin;
+    // The image sits on the side opposite the offset so the shadow fits
+    let img_x = margin + if offset_x < 0 { dx } else { 0 };
+    let img_y = margin + if offset_y < 0 { dy } else { 0 };
+    let shadow_x = (img_x as i64 + offset_x as i64) as u32;
+    let shadow_y = (img_y as i64 + offset_y as i64) as u32;
+
+    // Fill with the shadow color at zero alpha so the blurred falloff keeps it
+    let mut silhouette =
+        RgbaImage::from_pixel(canvas_w, canvas_h, Rgba([color[0], color[1], color[2], 0]));
+    for (x, y, pixel) in rgba.enumerate_pixels() {
+        let alpha = (pixel[3] as u32 * color[3] as u32 / 255) as u8;
+        silhouette.put_pixel(
+            shadow_x + x,
+            shadow_y + y,
+            Rgba([color[0], color[1], color[2], alpha]),
+        );
+    }
+
+    let silhouette = DynamicImage::ImageRgba8(silhouette);
+    let silhouette = if blur > 0.0 {
+        feather_alpha(&silhouette, blur.max(0.1))?
+    } else {
+        silhouette
+    };
+
+    composite(
+        &silhouette,
+        img,
+        img_x as i32,
+        img_y as i32,
+        None,
+        1.0,
+        BlendMode::Normal,
+    )
+}
+
+pub(crate) fn is_keyed(pixel: &Rgba<u8>, key: Rgba<u8>, tolerance: u8) -> bool {
+    let distance = (0..3).map(|i| pixel[i].abs_diff(key[i])).max().unwrap_or(0);
+    distance <= tolerance
+}
+
+pub(crate) fn blend_pixels(
+    base: Rgba<u8>,
+    overlay: Rgba<u8>,
+    opacity: f32,
+    mode: BlendMode,
+) -> Rgba<u8> {
     // Apply opacity to overlay alpha
     let overlay_alpha = (overlay[3] as f32 / 255.0) * opacity;
 
@@ -252,6 +614,71 @@ mod tests {
         assert_eq!(rgba.get_pixel(0, 0)[3], 0);
     }
 
+    #[test]
+    fn test_pad_edges_distinct_colors() {
+        let img = create_test_image(10, 10, Rgba([255, 0, 0, 255]));
+        let top = Rgba([0, 0, 255, 255]);
+        let left = Rgba([0, 255, 0, 255]);
+        let rest = Rgba([0, 0, 0, 255]);
+        let colors = EdgeColors {
+            top,
+            left,
+            ..EdgeColors::uniform(rest)
+        };
+        let rgba = pad_edges(&img, 3, 3, 4, 4, colors).unwrap().to_rgba8();
+
+        assert_eq!(rgba.dimensions(), (18, 16));
+        // Top border, including its corners
+        for x in 0..18 {
+            assert_eq!(rgba.get_pixel(x, 0), &top);
+            assert_eq!(rgba.get_pixel(x, 2), &top);
+        }
+        // Left border beside the image
+        for y in 3..13 {
+            assert_eq!(rgba.get_pixel(0, y), &left);
+            assert_eq!(rgba.get_pixel(3, y), &left);
+            assert_eq!(rgba.get_pixel(17, y), &rest);
+        }
+        assert_eq!(rgba.get_pixel(0, 15), &rest);
+        assert_eq!(rgba.get_pixel(4, 3), &Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_frame_rings() {
+        let img = create_test_image(10, 10, Rgba([0, 0, 255, 255]));
+        let red = Rgba([255, 0, 0, 255]);
+        let white = Rgba([255, 255, 255, 255]);
+        let rgba = frame(&img, 5, red, 2, white).unwrap().to_rgba8();
+
+        assert_eq!(rgba.dimensions(), (24, 24));
+        for i in 0..24 {
+            // Outermost ring is red
+            for p in [(i, 0), (i, 23), (0, i), (23, i)] {
+                assert_eq!(rgba.get_pixel(p.0, p.1), &red);
+            }
+        }
+        for i in 5..19 {
+            // The ring just inside the outer border is white
+            for p in [(i, 5), (i, 18), (5, i), (18, i)] {
+                assert_eq!(rgba.get_pixel(p.0, p.1), &white);
+            }
+        }
+        assert_eq!(rgba.get_pixel(4, 12), &red);
+        assert_eq!(rgba.get_pixel(7, 12), &Rgba([0, 0, 255, 255]));
+    }
+
+    #[test]
+    fn test_frame_zero_width() {
+        let img = create_test_image(10, 10, Rgba([0, 0, 255, 255]));
+        assert!(frame(&img, 0, Rgba([0; 4]), 2, Rgba([255; 4])).is_err());
+        // A single-tone frame has no inner stripe
+        let rgba = frame(&img, 3, Rgba([9, 9, 9, 255]), 0, Rgba([255; 4]))
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(rgba.dimensions(), (16, 16));
+        assert_eq!(rgba.get_pixel(3, 3), &Rgba([0, 0, 255, 255]));
+    }
+
     #[test]
     fn test_canvas_expand() {
         let img = create_test_image(10, 10, Rgba([255, 0, 0, 255]));
@@ -468,4 +895,222 @@ mod tests {
         // Black screen gray = gray
         assert!(pixel[0] > 120 && pixel[0] < 136);
     }
+
+    #[test]
+    fn test_checkerboard_blends_over_cells() {
+        // Half-transparent black over the board halves each shade
+        let img = create_test_image(8, 8, Rgba([0, 0, 0, 128]));
+        let result = checkerboard(&img, 4).unwrap().to_rgba8();
+
+        let light = result.get_pixel(1, 1);
+        let dark = result.get_pixel(5, 1);
+        assert_eq!(result.get_pixel(5, 5), light);
+        assert_eq!(result.get_pixel(1, 5), dark);
+        assert!(light[0].abs_diff(127) <= 1, "{:?}", light);
+        assert!(dark[0].abs_diff(102) <= 1, "{:?}", dark);
+        assert!(result.pixels().all(|p| p[3] == 255));
+
+        // Opaque pixels hide the board entirely
+        let opaque = create_test_image(8, 8, Rgba([10, 20, 30, 255]));
+        let result = checkerboard(&opaque, 4).unwrap().to_rgba8();
+        assert!(result.pixels().all(|p| *p == Rgba([10, 20, 30, 255])));
+
+        assert!(checkerboard(&img, 0).is_err());
+    }
+
+    #[test]
+    fn test_grid_lines_at_spacing() {
+        let img = create_test_image(25, 25, Rgba([0, 0, 255, 255]));
+        let result = grid(&img, 10, Rgba([255, 0, 0, 255])).unwrap().to_rgba8();
+
+        for i in 0..25 {
+            for line in [0, 10, 20] {
+                assert_eq!(result.get_pixel(line, i), &Rgba([255, 0, 0, 255]));
+                assert_eq!(result.get_pixel(i, line), &Rgba([255, 0, 0, 255]));
+            }
+        }
+        // Cell interiors keep the content
+        assert_eq!(result.get_pixel(5, 5), &Rgba([0, 0, 255, 255]));
+        assert_eq!(result.get_pixel(15, 24), &Rgba([0, 0, 255, 255]));
+
+        // A translucent color blends with what is beneath
+        let result = grid(&img, 10, Rgba([255, 0, 0, 128])).unwrap().to_rgba8();
+        let line = result.get_pixel(10, 5);
+        assert!(line[0] > 100 && line[2] > 100, "{:?}", line);
+
+        assert!(grid(&img, 0, Rgba([255, 0, 0, 255])).is_err());
+    }
+
+    #[test]
+    fn test_chroma_key_tolerance() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(3, 1, |x, _| match x {
+            0 => Rgba([0, 255, 0, 255]),
+            1 => Rgba([20, 235, 10, 255]),
+            _ => Rgba([255, 0, 0, 255]),
+        }));
+
+        let exact = chroma_key(&img, Rgba([0, 255, 0, 255]), 0).to_rgba8();
+        assert_eq!(exact.get_pixel(0, 0)[3], 0);
+        assert_eq!(exact.get_pixel(1, 0)[3], 255);
+
+        let loose = chroma_key(&img, Rgba([0, 255, 0, 255]), 30).to_rgba8();
+        assert_eq!(loose.get_pixel(0, 0)[3], 0);
+        assert_eq!(loose.get_pixel(1, 0)[3], 0);
+        assert_eq!(loose.get_pixel(2, 0), &Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_chroma_key_mask() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                Rgba([0, 255, 0, 255])
+            } else {
+                Rgba([255, 0, 0, 255])
+            }
+        }));
+
+        let mask = chroma_key_mask(&img, Rgba([0, 255, 0, 255]), 10).to_luma8();
+        assert_eq!(mask.get_pixel(0, 0)[0], 255);
+        assert_eq!(mask.get_pixel(1, 0)[0], 0);
+    }
+
+    #[test]
+    fn test_feather_alpha_ramps_edge() {
+        // Opaque left half, transparent right half
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(20, 1, |x, _| {
+            Rgba([10, 20, 30, if x < 10 { 255 } else { 0 }])
+        }));
+
+        let result = feather_alpha(&img, 3.0).unwrap().to_rgba8();
+        let alphas: Vec<u8> = result.pixels().map(|p| p[3]).collect();
+
+        // Monotonic falloff across the edge with intermediate values
+        assert!(alphas.windows(2).all(|w| w[0] >= w[1]));
+        assert!(alphas[9] > 0 && alphas[9] < 255);
+        assert!(alphas[10] > 0 && alphas[10] < 255);
+        assert_eq!(result.get_pixel(9, 0)[0], 10);
+    }
+
+    #[test]
+    fn test_feather_alpha_invalid_radius() {
+        let img = create_test_image(4, 4, Rgba([0, 0, 0, 255]));
+        assert!(feather_alpha(&img, 0.0).is_err());
+        assert!(feather_alpha(&img, 200.0).is_err());
+    }
+
+    #[test]
+    fn test_shadow_offset_and_blurred() {
+        let red = Rgba([255, 0, 0, 255]);
+        let img = create_test_image(10, 10, red);
+        let result = shadow(&img, 6, 6, 4.0, Rgba([0, 0, 0, 255]))
+            .unwrap()
+            .to_rgba8();
+
+        // 10 + 6 offset + 4 blur margin on either side
+        assert_eq!(result.dimensions(), (24, 24));
+        // The square sits at the margin, unchanged
+        for y in 4..14 {
+            for x in 4..14 {
+                assert_eq!(result.get_pixel(x, y), &red);
+            }
+        }
+        // Solid shadow below-right of the square, fading out at its edge
+        let inside = result.get_pixel(16, 16);
+        assert!(inside[3] > 250, "inside alpha {}", inside[3]);
+        assert!(inside[0] < 10);
+        let edge = result.get_pixel(20, 17)[3];
+        assert!(edge > 0 && edge < 255, "edge alpha {}", edge);
+        // Transparent surround on the side away from the offset
+        assert_eq!(result.get_pixel(0, 0)[3], 0);
+        assert_eq!(result.get_pixel(23, 0)[3], 0);
+    }
+
+    #[test]
+    fn test_shadow_negative_offset_and_invalid_blur() {
+        let img = create_test_image(10, 10, Rgba([255, 0, 0, 255]));
+        let result = shadow(&img, -3, 0, 0.0, Rgba([0, 0, 0, 128]))
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(result.dimensions(), (13, 10));
+        // The image moves right; the unblurred shadow shows at the left
+        assert_eq!(result.get_pixel(0, 5), &Rgba([0, 0, 0, 128]));
+        assert_eq!(result.get_pixel(3, 5), &Rgba([255, 0, 0, 255]));
+
+        assert!(shadow(&img, 0, 0, -1.0, Rgba([0, 0, 0, 255])).is_err());
+    }
+
+    #[test]
+    fn test_canvas_resize_textured() {
+        let img = create_test_image(2, 2, Rgba([255, 0, 0, 255]));
+        let texture = DynamicImage::ImageRgba8(ImageBuffer::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        }));
+
+        let result = canvas_resize_textured(&img, 6, 6, Anchor::Center, &texture)
+            .unwrap()
+            .to_rgba8();
+
+        // Original in the middle, alternating texture columns around it
+        assert_eq!(result.get_pixel(2, 2), &Rgba([255, 0, 0, 255]));
+        assert_eq!(result.get_pixel(0, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(result.get_pixel(1, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(result.get_pixel(4, 5), &Rgba([0, 0, 0, 255]));
+        assert_eq!(result.get_pixel(5, 5), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_canvas_resize_textured_composites_translucent_source() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                Rgba([0, 0, 0, 0])
+            } else {
+                Rgba([255, 0, 0, 128])
+            }
+        }));
+        let texture = create_test_image(1, 1, Rgba([0, 0, 255, 255]));
+
+        let result = canvas_resize_textured(&img, 4, 1, Anchor::TopLeft, &texture)
+            .unwrap()
+            .to_rgba8();
+
+        // The texture shows through the transparent pixel and blends under
+        // the translucent one
+        assert_eq!(result.get_pixel(0, 0), &Rgba([0, 0, 255, 255]));
+        let blended = result.get_pixel(1, 0);
+        assert_eq!(blended[3], 255);
+        assert!(blended[0] > 100 && blended[2] > 100, "{:?}", blended);
+    }
+
+    #[test]
+    fn test_overlay_visible_fraction() {
+        let base = DynamicImage::ImageRgba8(RgbaImage::new(100, 100));
+        let overlay = DynamicImage::ImageRgba8(RgbaImage::new(40, 20));
+        let fraction = |x, y| overlay_visible_fraction(&base, &overlay, x, y, None);
+
+        assert_eq!(fraction(10, 10), 1.0);
+        assert_eq!(fraction(80, 10), 0.5);
+        assert_eq!(fraction(-20, -10), 0.25);
+        assert_eq!(fraction(100, 0), 0.0);
+        assert_eq!(fraction(-500, 30), 0.0);
+
+        let anchored =
+            overlay_visible_fraction(&base, &overlay, -999, -999, Some(Anchor::BottomRight));
+        assert_eq!(anchored, 1.0);
+    }
+
+    #[test]
+    fn test_check_canvas_fits() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::new(40, 30));
+        assert!(check_canvas_fits(&img, 40, 30).is_ok());
+        assert!(check_canvas_fits(&img, 100, 100).is_ok());
+
+        let err = check_canvas_fits(&img, 100, 20).unwrap_err();
+        assert_eq!(err.code(), "INVALID_DIMENSIONS");
+        assert!(err.to_string().contains("resize or fit"), "{}", err);
+        assert!(check_canvas_fits(&img, 39, 30).is_err());
+    }
 }
@@ -1,31 +1,121 @@
+use crate::cli::args::DitherAlgorithm;
 use crate::error::{ImgEditError, Result};
-use image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgba, RgbaImage};
+use image::{DynamicImage, GrayAlphaImage, GrayImage, ImageBuffer, Luma, LumaA, Rgba, RgbaImage};
+
+/// Rec.601 luminance coefficients for R, G and B
+pub const REC601_WEIGHTS: [f32; 3] = [0.299, 0.587, 0.114];
+
+/// How far the luminance weights may sum away from 1.0
+const WEIGHT_SUM_TOLERANCE: f32 = 0.01;
+
+/// Parse luminance weights given as `R,G,B`, e.g. `0.2126,0.7152,0.0722`.
+/// Each weight must be non-negative and together they must sum to about 1.0.
+pub fn parse_weights(spec: &str) -> Result<[f32; 3]> {
+    let parts: Vec<&str> = spec.split(',').map(str::trim).collect();
+    let invalid = || {
+        ImgEditError::InvalidParameter(format!(
+            "Invalid weights '{}': expected three comma-separated numbers R,G,B",
+            spec
+        ))
+    };
+    if parts.len() != 3 {
+        return Err(invalid());
+    }
+
+    let mut weights = [0.0; 3];
+    for (weight, part) in weights.iter_mut().zip(&parts) {
+        *weight = part.parse::<f32>().map_err(|_| invalid())?;
+        if !weight.is_finite() || *weight < 0.0 {
+            return Err(ImgEditError::InvalidParameter(format!(
+                "Invalid weights '{}': each weight must be a non-negative number",
+                spec
+            )));
+        }
+    }
+
+    let sum: f32 = weights.iter().sum();
+    if (sum - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Invalid weights '{}': they must sum to 1.0, got {}",
+            spec, sum
+        )));
+    }
+    Ok(weights)
+}
 
-/// Convert an image to grayscale
-pub fn grayscale(img: &DynamicImage, preserve_alpha: bool) -> Result<DynamicImage> {
+/// Weighted luminance of an RGB(A) pixel
+fn luminance(pixel: &Rgba<u8>, weights: [f32; 3]) -> u8 {
+    (weights[0] * pixel[0] as f32 + weights[1] * pixel[1] as f32 + weights[2] * pixel[2] as f32)
+        as u8
+}
+
+/// Convert an image to grayscale.
+///
+/// `weights` overrides the R, G, B luminance coefficients (see `parse_weights`);
+/// by default the standard Rec.601 formula is used.
+pub fn grayscale(
+    img: &DynamicImage,
+    preserve_alpha: bool,
+    weights: Option<[f32; 3]>,
+) -> Result<DynamicImage> {
     if preserve_alpha {
         // Convert to grayscale while keeping alpha channel
         let rgba = img.to_rgba8();
         let (width, height) = rgba.dimensions();
+        let weights = weights.unwrap_or(REC601_WEIGHTS);
 
         let result: RgbaImage = ImageBuffer::from_fn(width, height, |x, y| {
             let pixel = rgba.get_pixel(x, y);
-            // Standard luminance formula
-            let gray =
-                (0.299 * pixel[0] as f32 + 0.587 * pixel[1] as f32 + 0.114 * pixel[2] as f32) as u8;
+            let gray = luminance(pixel, weights);
             Rgba([gray, gray, gray, pixel[3]])
         });
 
         Ok(DynamicImage::ImageRgba8(result))
+    } else if let Some(weights) = weights {
+        let rgba = img.to_rgba8();
+        let result: GrayImage = ImageBuffer::from_fn(rgba.width(), rgba.height(), |x, y| {
+            Luma([luminance(rgba.get_pixel(x, y), weights)])
+        });
+        Ok(DynamicImage::ImageLuma8(result))
     } else {
         Ok(DynamicImage::ImageLuma8(img.to_luma8()))
     }
 }
 
+/// Convert an image to a gray+alpha image, keeping each pixel's alpha.
+///
+/// Unlike `grayscale` with `preserve_alpha`, the result stores luminance in a
+/// single channel rather than repeating it across R, G and B.
+pub fn grayscale_luma_alpha(img: &DynamicImage, weights: Option<[f32; 3]>) -> Result<DynamicImage> {
+    let rgba = img.to_rgba8();
+    let weights = weights.unwrap_or(REC601_WEIGHTS);
+    let result: GrayAlphaImage = ImageBuffer::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let pixel = rgba.get_pixel(x, y);
+        LumaA([luminance(pixel, weights), pixel[3]])
+    });
+    Ok(DynamicImage::ImageLumaA8(result))
+}
+
 /// Change the bit depth of an image
-pub fn change_depth(img: &DynamicImage, bits: u8, dither: bool) -> Result<DynamicImage> {
+///
+/// `dither` selects the error-diffusion algorithm used when reducing to 1 bit;
+/// `None` thresholds at mid-gray. `strength` (0.0-1.0) scales the error that
+/// is diffused, from a plain threshold at 0.0 to full dithering at 1.0.
+pub fn change_depth(
+    img: &DynamicImage,
+    bits: u8,
+    dither: Option<DitherAlgorithm>,
+    strength: f64,
+) -> Result<DynamicImage> {
+    if !(0.0..=1.0).contains(&strength) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Dither strength must be between 0.0 and 1.0, got {}",
+            strength
+        )));
+    }
+
     match bits {
-        1 => convert_to_1bit(img, dither),
+        1 => convert_to_1bit(img, dither, strength),
         8 => Ok(img.clone()), // Already 8-bit typically
         16 => convert_to_16bit(img),
         _ => Err(ImgEditError::InvalidParameter(format!(
@@ -35,12 +125,50 @@ pub fn change_depth(img: &DynamicImage, bits: u8, dither: bool) -> Result<Dynami
     }
 }
 
-fn convert_to_1bit(img: &DynamicImage, dither: bool) -> Result<DynamicImage> {
+/// Error-diffusion weights as (dx, dy, weight), and the divisor they share
+fn diffusion_matrix(algorithm: DitherAlgorithm) -> (&'static [(i32, i32, i32)], i32) {
+    match algorithm {
+        DitherAlgorithm::Floyd => (&[(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)], 16),
+        // Only 6/8 of the error is passed on, so highlights and shadows stay clean
+        DitherAlgorithm::Atkinson => (
+            &[
+                (1, 0, 1),
+                (2, 0, 1),
+                (-1, 1, 1),
+                (0, 1, 1),
+                (1, 1, 1),
+                (0, 2, 1),
+            ],
+            8,
+        ),
+        DitherAlgorithm::Sierra => (
+            &[
+                (1, 0, 5),
+                (2, 0, 3),
+                (-2, 1, 2),
+                (-1, 1, 4),
+                (0, 1, 5),
+                (1, 1, 4),
+                (2, 1, 2),
+                (-1, 2, 2),
+                (0, 2, 3),
+                (1, 2, 2),
+            ],
+            32,
+        ),
+    }
+}
+
+fn convert_to_1bit(
+    img: &DynamicImage,
+    dither: Option<DitherAlgorithm>,
+    strength: f64,
+) -> Result<DynamicImage> {
     let gray = img.to_luma8();
     let (width, height) = gray.dimensions();
 
-    if dither {
-        // Floyd-Steinberg dithering
+    if let Some(algorithm) = dither {
+        let (matrix, divisor) = diffusion_matrix(algorithm);
         let mut buffer: Vec<Vec<i32>> = gray
             .rows()
             .map(|row| row.map(|p| p[0] as i32).collect())
@@ -49,19 +177,14 @@ fn convert_to_1bit(img: &DynamicImage, dither: bool) -> Result<DynamicImage> {
         let result: GrayImage = ImageBuffer::from_fn(width, height, |x, y| {
             let old_pixel = buffer[y as usize][x as usize].clamp(0, 255);
             let new_pixel = if old_pixel > 127 { 255 } else { 0 };
-            let error = old_pixel - new_pixel;
-
-            // Distribute error to neighbors
-            if x + 1 < width {
-                buffer[y as usize][(x + 1) as usize] += error * 7 / 16;
-            }
-            if y + 1 < height {
-                if x > 0 {
-                    buffer[(y + 1) as usize][(x - 1) as usize] += error * 3 / 16;
-                }
-                buffer[(y + 1) as usize][x as usize] += error * 5 / 16;
-                if x + 1 < width {
-                    buffer[(y + 1) as usize][(x + 1) as usize] += error / 16;
+            let error = ((old_pixel - new_pixel) as f64 * strength).round() as i32;
+
+            // Distribute error to neighbors that have not been visited yet
+            for &(dx, dy, weight) in matrix {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                if nx >= 0 && nx < width as i32 && ny < height as i32 {
+                    buffer[ny as usize][nx as usize] += error * weight / divisor;
                 }
             }
 
@@ -108,6 +231,46 @@ pub fn invert(img: &DynamicImage, invert_alpha: bool) -> Result<DynamicImage> {
     Ok(DynamicImage::ImageRgba8(result))
 }
 
+/// Make alpha fully opaque or fully transparent: 255 where it is at least
+/// `threshold`, 0 below it. Color channels are left untouched.
+pub fn alpha_threshold(img: &DynamicImage, threshold: u8) -> Result<DynamicImage> {
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        pixel[3] = if pixel[3] >= threshold { 255 } else { 0 };
+    }
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// Multiply each color channel by alpha, for consumers that expect
+/// premultiplied alpha. Fully transparent pixels become transparent black.
+pub fn premultiply(img: &DynamicImage) -> Result<DynamicImage> {
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        let alpha = pixel[3] as u32;
+        for channel in pixel.0.iter_mut().take(3) {
+            *channel = ((*channel as u32 * alpha + 127) / 255) as u8;
+        }
+    }
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// Divide each color channel by alpha, undoing [`premultiply`] up to the
+/// rounding it introduced. Fully transparent pixels, whose color is lost,
+/// are left as they are.
+pub fn unpremultiply(img: &DynamicImage) -> Result<DynamicImage> {
+    let mut rgba = img.to_rgba8();
+    for pixel in rgba.pixels_mut() {
+        let alpha = pixel[3] as u32;
+        if alpha == 0 {
+            continue;
+        }
+        for channel in pixel.0.iter_mut().take(3) {
+            *channel = ((*channel as u32 * 255 + alpha / 2) / alpha).min(255) as u8;
+        }
+    }
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,7 +294,7 @@ mod tests {
     #[test]
     fn test_grayscale_preserve_alpha() {
         let img = create_test_image();
-        let result = grayscale(&img, true).unwrap();
+        let result = grayscale(&img, true, None).unwrap();
 
         let rgba = result.to_rgba8();
         let pixel = rgba.get_pixel(0, 0);
@@ -145,16 +308,64 @@ mod tests {
     #[test]
     fn test_grayscale_no_alpha() {
         let img = create_test_image();
-        let result = grayscale(&img, false).unwrap();
+        let result = grayscale(&img, false, None).unwrap();
 
         // Should be a luma image
         assert!(matches!(result, DynamicImage::ImageLuma8(_)));
     }
 
+    #[test]
+    fn test_grayscale_luma_alpha() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(8, 8, |x, y| {
+            Rgba([(x * 30) as u8, (y * 30) as u8, 90, (x * y * 4) as u8])
+        }));
+        let result = grayscale_luma_alpha(&img, None).unwrap();
+
+        let DynamicImage::ImageLumaA8(gray) = &result else {
+            panic!("expected ImageLumaA8, got {:?}", result.color());
+        };
+        // Same luma as the RGBA method, with the original alpha
+        let rgba = grayscale(&img, true, None).unwrap().to_rgba8();
+        let src = img.to_rgba8();
+        for ((la, expected), src) in gray.pixels().zip(rgba.pixels()).zip(src.pixels()) {
+            assert_eq!(la[0], expected[0]);
+            assert_eq!(la[1], src[3]);
+        }
+    }
+
+    #[test]
+    fn test_grayscale_red_weights_match_red_channel() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(16, 16, |x, y| {
+            Rgba([(x * 16) as u8, (y * 16) as u8, 200, 255])
+        }));
+        let rgba = img.to_rgba8();
+
+        for preserve_alpha in [true, false] {
+            let result = grayscale(&img, preserve_alpha, Some([1.0, 0.0, 0.0])).unwrap();
+            let gray = result.to_luma8();
+            for (src, dst) in rgba.pixels().zip(gray.pixels()) {
+                assert_eq!(dst[0], src[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_weights() {
+        assert_eq!(parse_weights("1,0,0").unwrap(), [1.0, 0.0, 0.0]);
+        assert_eq!(
+            parse_weights("0.2126, 0.7152, 0.0722").unwrap(),
+            [0.2126, 0.7152, 0.0722]
+        );
+        assert!(parse_weights("0.5,0.5").is_err());
+        assert!(parse_weights("0.5,0.5,0.5").is_err());
+        assert!(parse_weights("1.5,-0.5,0").is_err());
+        assert!(parse_weights("a,b,c").is_err());
+    }
+
     #[test]
     fn test_depth_1bit() {
         let img = create_gradient_image();
-        let result = change_depth(&img, 1, false).unwrap();
+        let result = change_depth(&img, 1, None, 1.0).unwrap();
 
         let gray = result.to_luma8();
         // All pixels should be either 0 or 255
@@ -166,7 +377,7 @@ mod tests {
     #[test]
     fn test_depth_1bit_dither() {
         let img = create_gradient_image();
-        let result = change_depth(&img, 1, true).unwrap();
+        let result = change_depth(&img, 1, Some(DitherAlgorithm::Floyd), 1.0).unwrap();
 
         let gray = result.to_luma8();
         // All pixels should be either 0 or 255
@@ -175,10 +386,88 @@ mod tests {
         }
     }
 
+    /// Pixels whose four neighbors all share their value: the inside of a
+    /// solid black or white cluster
+    fn cluster_pixels(img: &GrayImage) -> usize {
+        let (w, h) = img.dimensions();
+        (1..h - 1)
+            .flat_map(|y| (1..w - 1).map(move |x| (x, y)))
+            .filter(|&(x, y)| {
+                let v = img.get_pixel(x, y)[0];
+                [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                    .iter()
+                    .all(|&(nx, ny)| img.get_pixel(nx, ny)[0] == v)
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_depth_1bit_dither_algorithms() {
+        let ramp =
+            DynamicImage::ImageLuma8(ImageBuffer::from_fn(128, 32, |x, _| Luma([(x * 2) as u8])));
+
+        let dithered = |algorithm| {
+            change_depth(&ramp, 1, Some(algorithm), 1.0)
+                .unwrap()
+                .to_luma8()
+        };
+        let floyd = dithered(DitherAlgorithm::Floyd);
+        let atkinson = dithered(DitherAlgorithm::Atkinson);
+        let sierra = dithered(DitherAlgorithm::Sierra);
+
+        for result in [&floyd, &atkinson, &sierra] {
+            assert!(result.pixels().all(|p| p[0] == 0 || p[0] == 255));
+        }
+        assert_ne!(floyd, sierra);
+
+        // Atkinson drops a quarter of the error, leaving larger solid areas
+        assert!(
+            cluster_pixels(&atkinson) > cluster_pixels(&floyd),
+            "atkinson {} vs floyd {}",
+            cluster_pixels(&atkinson),
+            cluster_pixels(&floyd)
+        );
+    }
+
+    #[test]
+    fn test_depth_1bit_dither_strength() {
+        let ramp =
+            DynamicImage::ImageLuma8(ImageBuffer::from_fn(128, 32, |x, _| Luma([(x * 2) as u8])));
+        let floyd = Some(DitherAlgorithm::Floyd);
+        let depth = |dither, strength| change_depth(&ramp, 1, dither, strength).unwrap();
+
+        assert_eq!(depth(floyd, 0.0), depth(None, 1.0));
+
+        // Full strength is textbook Floyd-Steinberg
+        let mut buffer: Vec<i32> = ramp.to_luma8().pixels().map(|p| p[0] as i32).collect();
+        let mut expected = GrayImage::new(128, 32);
+        for y in 0..32i32 {
+            for x in 0..128i32 {
+                let old = buffer[(y * 128 + x) as usize].clamp(0, 255);
+                let new = if old > 127 { 255 } else { 0 };
+                expected.put_pixel(x as u32, y as u32, Luma([new as u8]));
+                for (dx, dy, weight) in [(1, 0, 7), (-1, 1, 3), (0, 1, 5), (1, 1, 1)] {
+                    let (nx, ny) = (x + dx, y + dy);
+                    if (0..128).contains(&nx) && ny < 32 {
+                        buffer[(ny * 128 + nx) as usize] += (old - new) * weight / 16;
+                    }
+                }
+            }
+        }
+        assert_eq!(depth(floyd, 1.0).to_luma8(), expected);
+
+        let partial = depth(floyd, 0.5);
+        assert_ne!(partial, depth(floyd, 0.0));
+        assert_ne!(partial, depth(floyd, 1.0));
+
+        assert!(change_depth(&ramp, 1, floyd, 1.5).is_err());
+        assert!(change_depth(&ramp, 1, floyd, -0.1).is_err());
+    }
+
     #[test]
     fn test_depth_16bit() {
         let img = create_test_image();
-        let result = change_depth(&img, 16, false).unwrap();
+        let result = change_depth(&img, 16, None, 1.0).unwrap();
 
         assert!(matches!(result, DynamicImage::ImageRgba16(_)));
     }
@@ -186,7 +475,7 @@ mod tests {
     #[test]
     fn test_depth_invalid() {
         let img = create_test_image();
-        let result = change_depth(&img, 4, false);
+        let result = change_depth(&img, 4, None, 1.0);
         assert!(result.is_err());
     }
 
@@ -247,4 +536,48 @@ mod tests {
         assert_eq!(pixel[1], 0);
         assert_eq!(pixel[2], 0);
     }
+
+    #[test]
+    fn test_alpha_threshold() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(3, 1, |x, _| match x {
+            0 => Rgba([10, 20, 30, 200]),
+            1 => Rgba([40, 50, 60, 50]),
+            _ => Rgba([70, 80, 90, 128]),
+        }));
+        let result = alpha_threshold(&img, 128).unwrap().to_rgba8();
+
+        assert_eq!(result.get_pixel(0, 0), &Rgba([10, 20, 30, 255]));
+        assert_eq!(result.get_pixel(1, 0), &Rgba([40, 50, 60, 0]));
+        // The threshold itself counts as opaque
+        assert_eq!(result.get_pixel(2, 0), &Rgba([70, 80, 90, 255]));
+    }
+
+    #[test]
+    fn test_premultiply_round_trip() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(3, 1, |x, _| match x {
+            0 => Rgba([200, 60, 255, 128]),
+            1 => Rgba([90, 180, 30, 255]),
+            _ => Rgba([50, 50, 50, 0]),
+        }));
+        let premultiplied = premultiply(&img).unwrap().to_rgba8();
+        assert_eq!(premultiplied.get_pixel(0, 0), &Rgba([100, 30, 128, 128]));
+        assert_eq!(premultiplied.get_pixel(1, 0), &Rgba([90, 180, 30, 255]));
+        assert_eq!(premultiplied.get_pixel(2, 0), &Rgba([0, 0, 0, 0]));
+
+        let restored = unpremultiply(&DynamicImage::ImageRgba8(premultiplied))
+            .unwrap()
+            .to_rgba8();
+        for (restored, original) in restored.pixels().zip(img.to_rgba8().pixels()).take(2) {
+            for c in 0..4 {
+                assert!(
+                    restored[c].abs_diff(original[c]) <= 1,
+                    "{:?} vs {:?}",
+                    restored,
+                    original
+                );
+            }
+        }
+        // Transparent pixels stay put rather than dividing by zero
+        assert_eq!(restored.get_pixel(2, 0), &Rgba([0, 0, 0, 0]));
+    }
 }
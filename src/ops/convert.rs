@@ -1,6 +1,8 @@
-use crate::cli::args::ImageFormat;
-use crate::error::{ImgEditError, Result};
-use image::DynamicImage;
+use crate::cli::args::{ColorProfile, ImageFormat, PngColorType};
+use crate::error::{ImgEditError, Result, Warning};
+use crate::ops::icc::icc_profile;
+use image::{DynamicImage, ImageEncoder};
+use std::io::{Seek, Write};
 use std::path::Path;
 
 /// Determine the output format from path extension or explicit format
@@ -48,6 +50,220 @@ fn image_format_from_cli(fmt: ImageFormat) -> image::ImageFormat {
     }
 }
 
+/// Encoder settings used when writing an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveOptions {
+    /// Quality for lossy formats (1-100)
+    pub quality: u8,
+    /// Shrink the output file: JPEG is encoded with mozjpeg (`mozjpeg` feature),
+    /// PNG is losslessly recompressed with oxipng (`oxipng` feature).
+    /// Ignored for other formats or when the feature is not built in.
+    pub optimize: bool,
+    /// ICC profile to embed (PNG, JPEG and WebP only); pixels are written unchanged
+    pub assign_profile: Option<ColorProfile>,
+    /// Resolution to record in the file's metadata (PNG, JPEG and TIFF only)
+    pub dpi: Option<u16>,
+    /// Color type to write PNG output as; ignored for other formats
+    pub png_color_type: PngColorType,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            quality: 90,
+            optimize: false,
+            assign_profile: None,
+            dpi: None,
+            png_color_type: PngColorType::Auto,
+        }
+    }
+}
+
+/// Whether `format` can carry an embedded ICC profile
+fn supports_icc(format: image::ImageFormat) -> bool {
+    matches!(
+        format,
+        image::ImageFormat::Png | image::ImageFormat::Jpeg | image::ImageFormat::WebP
+    )
+}
+
+/// Warning to report when `SaveOptions::dpi` cannot be recorded in `format`
+pub fn dpi_warning(format: image::ImageFormat) -> Option<Warning> {
+    let supported = matches!(
+        format,
+        image::ImageFormat::Png | image::ImageFormat::Jpeg | image::ImageFormat::Tiff
+    );
+    (!supported).then(|| {
+        Warning::new(
+            "DPI_NOT_WRITTEN",
+            format!(
+                "{:?} output has no resolution metadata; --dpi was ignored",
+                format
+            ),
+        )
+    })
+}
+
+/// Warning to report when `SaveOptions::png_color_type` is set for output
+/// that is not PNG
+pub fn png_color_type_warning(format: image::ImageFormat) -> Option<Warning> {
+    (format != image::ImageFormat::Png).then(|| {
+        Warning::new(
+            "PNG_COLOR_TYPE_IGNORED",
+            format!(
+                "{:?} output is not PNG; --png-color-type was ignored",
+                format
+            ),
+        )
+    })
+}
+
+/// Check that `format` can store pixels exactly, as `--lossless` requires.
+/// JPEG has no lossless mode and GIF quantizes to a 256-color palette; every
+/// other writable format, WebP included, is encoded losslessly already.
+pub fn check_lossless(format: image::ImageFormat) -> Result<()> {
+    match format {
+        image::ImageFormat::Jpeg | image::ImageFormat::Gif => {
+            Err(ImgEditError::UnsupportedFormat(format!(
+                "{:?} cannot be written losslessly; use PNG or WebP for lossless output",
+                format
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Warning to report when `SaveOptions::assign_profile` cannot be embedded in `format`
+pub fn profile_warning(format: image::ImageFormat) -> Option<Warning> {
+    (!supports_icc(format)).then(|| {
+        Warning::new(
+            "PROFILE_NOT_EMBEDDED",
+            format!(
+                "{:?} output cannot carry an ICC profile; saved without one",
+                format
+            ),
+        )
+    })
+}
+
+/// The copy of a gray `img` to write as `format`, so `Luma8` and `LumaA8`
+/// results stay single-channel instead of being promoted to RGBA. PNG stores
+/// both natively; TIFF's encoder has no gray+alpha mode, so `LumaA8` becomes
+/// `Luma8` there when fully opaque and RGBA otherwise. Returns `None` when
+/// the image should be written as it is; other color types are never changed.
+pub fn single_channel(img: &DynamicImage, format: image::ImageFormat) -> Option<DynamicImage> {
+    match img {
+        DynamicImage::ImageLumaA8(luma_alpha) if format == image::ImageFormat::Tiff => {
+            if luma_alpha.pixels().all(|p| p[1] == 255) {
+                Some(DynamicImage::ImageLuma8(img.to_luma8()))
+            } else {
+                Some(DynamicImage::ImageRgba8(img.to_rgba8()))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The copy of `img` to hand the encoder for `format`: PNG output with a
+/// forced color type is converted to it (keeping 16-bit samples), otherwise
+/// see [`single_channel`]. Palette output is indexed by the encoder itself.
+fn encoder_input(
+    img: &DynamicImage,
+    format: image::ImageFormat,
+    options: &SaveOptions,
+) -> Option<DynamicImage> {
+    if format != image::ImageFormat::Png {
+        return single_channel(img, format);
+    }
+    let color = img.color();
+    let sixteen_bit = color.bits_per_pixel() > 8 * color.channel_count() as u16;
+    let coerced = match (options.png_color_type, sixteen_bit) {
+        (PngColorType::Auto, _) => return single_channel(img, format),
+        (PngColorType::Palette, _) => return None,
+        (PngColorType::Rgb, false) => DynamicImage::ImageRgb8(img.to_rgb8()),
+        (PngColorType::Rgb, true) => DynamicImage::ImageRgb16(img.to_rgb16()),
+        (PngColorType::Rgba, false) => DynamicImage::ImageRgba8(img.to_rgba8()),
+        (PngColorType::Rgba, true) => DynamicImage::ImageRgba16(img.to_rgba16()),
+        (PngColorType::Gray, false) => DynamicImage::ImageLuma8(img.to_luma8()),
+        (PngColorType::Gray, true) => DynamicImage::ImageLuma16(img.to_luma16()),
+        (PngColorType::Graya, false) => DynamicImage::ImageLumaA8(img.to_luma_alpha8()),
+        (PngColorType::Graya, true) => DynamicImage::ImageLumaA16(img.to_luma_alpha16()),
+    };
+    (coerced.color() != color).then_some(coerced)
+}
+
+/// Warning to report when saving `img` as `format` discards transparency,
+/// or `None` if the format keeps alpha or the image is fully opaque
+pub fn alpha_warning(img: &DynamicImage, format: image::ImageFormat) -> Option<Warning> {
+    if format != image::ImageFormat::Jpeg {
+        return None;
+    }
+    discarded_alpha_warning(img, &format!("{:?}", format))
+}
+
+/// Warning to report when `--png-color-type` forces a layout without alpha
+/// on a translucent image saved as `format`, or `None` otherwise
+pub fn png_alpha_warning(
+    img: &DynamicImage,
+    format: image::ImageFormat,
+    color_type: PngColorType,
+) -> Option<Warning> {
+    let target = match color_type {
+        PngColorType::Rgb => "PNG color type rgb",
+        PngColorType::Gray => "PNG color type gray",
+        _ => return None,
+    };
+    if format != image::ImageFormat::Png {
+        return None;
+    }
+    discarded_alpha_warning(img, target)
+}
+
+/// `ALPHA_DISCARDED` for `img` written to `target`, which has no alpha
+/// channel, unless the image is fully opaque
+fn discarded_alpha_warning(img: &DynamicImage, target: &str) -> Option<Warning> {
+    if !img.color().has_alpha() {
+        return None;
+    }
+    let transparent = img.to_rgba8().pixels().filter(|p| p[3] < 255).count();
+    (transparent > 0).then(|| {
+        Warning::new(
+            "ALPHA_DISCARDED",
+            format!(
+                "{} has no alpha channel; transparency of {} pixels was discarded",
+                target, transparent
+            ),
+        )
+    })
+}
+
+/// Warning to report when `SaveOptions::optimize` has no effect for `format`
+/// in this build, or `None` if the output will be optimized
+pub fn optimize_warning(format: image::ImageFormat) -> Option<Warning> {
+    let feature = match format {
+        image::ImageFormat::Jpeg if cfg!(feature = "mozjpeg") => return None,
+        image::ImageFormat::Png if cfg!(feature = "oxipng") => return None,
+        image::ImageFormat::Jpeg => "mozjpeg",
+        image::ImageFormat::Png => "oxipng",
+        _ => {
+            return Some(Warning::new(
+                "OPTIMIZE_UNAVAILABLE",
+                format!(
+                    "--optimize only applies to JPEG and PNG output; {:?} was saved normally",
+                    format
+                ),
+            ))
+        }
+    };
+    Some(Warning::new(
+        "OPTIMIZE_UNAVAILABLE",
+        format!(
+            "--optimize for {:?} requires the `{}` build feature; saved with the standard encoder",
+            format, feature
+        ),
+    ))
+}
+
 /// Save an image in the specified format with quality settings
 pub fn save_with_format(
     img: &DynamicImage,
@@ -55,75 +271,221 @@ pub fn save_with_format(
     format: image::ImageFormat,
     quality: u8,
 ) -> Result<()> {
-    use std::fs::File;
-    use std::io::BufWriter;
+    let options = SaveOptions {
+        quality,
+        ..SaveOptions::default()
+    };
+    save_with_options(img, output_path, format, &options)
+}
+
+/// Encode an image in memory with quality settings, producing the same bytes
+/// [`save_with_format`] writes. Errors name the output as `<memory>`.
+pub fn encode_to_bytes(
+    img: &DynamicImage,
+    format: image::ImageFormat,
+    quality: u8,
+) -> Result<Vec<u8>> {
+    let options = SaveOptions {
+        quality,
+        ..SaveOptions::default()
+    };
+    encode_with_options(img, Path::new("<memory>"), format, &options)
+}
+
+/// Make sure the directory `path` will be written into exists.
+///
+/// With `create` a missing directory (and any missing parents) is created;
+/// otherwise a `WriteError` naming the missing directory is returned, instead
+/// of the bare OS error the file create would give.
+pub fn ensure_parent_dir(path: &Path, create: bool) -> Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => return Ok(()),
+    };
+    if parent.is_dir() {
+        return Ok(());
+    }
+    if create {
+        return std::fs::create_dir_all(parent).map_err(|e| ImgEditError::WriteError {
+            path: parent.display().to_string(),
+            reason: format!("Could not create directory: {}", e),
+        });
+    }
+    Err(ImgEditError::WriteError {
+        path: path.display().to_string(),
+        reason: format!(
+            "Directory {} does not exist. Use --create-dirs to create it.",
+            parent.display()
+        ),
+    })
+}
+
+/// Save an image in the specified format with the given encoder settings
+pub fn save_with_options(
+    img: &DynamicImage,
+    output_path: &Path,
+    format: image::ImageFormat,
+    options: &SaveOptions,
+) -> Result<()> {
+    ensure_parent_dir(output_path, false)?;
+    let bytes = encode_with_options(img, output_path, format, options)?;
+    std::fs::write(output_path, bytes).map_err(|e| ImgEditError::WriteError {
+        path: output_path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Encode an image in memory exactly as [`save_with_options`] would write it.
+/// `output_path` is only used to attribute errors.
+pub fn encode_with_options(
+    img: &DynamicImage,
+    output_path: &Path,
+    format: image::ImageFormat,
+    options: &SaveOptions,
+) -> Result<Vec<u8>> {
+    let narrowed = encoder_input(img, format, options);
+    let img = narrowed.as_ref().unwrap_or(img);
+    let mut buffer = std::io::Cursor::new(Vec::new());
+    encode_into(img, &mut buffer, output_path, format, options)?;
+    let bytes = buffer.into_inner();
 
-    let file = File::create(output_path).map_err(|e| ImgEditError::WriteError {
+    #[cfg(feature = "oxipng")]
+    if options.optimize && format == image::ImageFormat::Png {
+        let color = img.color();
+        let high_depth = color.bits_per_pixel() > 8 * color.channel_count() as u16;
+        let forced = options.png_color_type != PngColorType::Auto;
+        return optimize_png(bytes, output_path, high_depth, forced);
+    }
+
+    Ok(bytes)
+}
+
+/// Save an image like [`save_with_options`] while holding as little as
+/// possible in memory: `img` is taken by value and dropped as soon as a
+/// narrowed copy replaces it, and the encoder writes straight to the file
+/// instead of assembling the whole encoded output first.
+///
+/// The output is byte-identical to [`save_with_options`]. `optimize` is not
+/// supported, as recompression needs the complete encoded file in memory.
+/// A failed encode leaves a partial file behind.
+pub fn save_low_memory(
+    img: DynamicImage,
+    output_path: &Path,
+    format: image::ImageFormat,
+    options: &SaveOptions,
+) -> Result<()> {
+    if options.optimize {
+        return Err(ImgEditError::InvalidParameter(
+            "--optimize cannot be combined with --low-memory".to_string(),
+        ));
+    }
+    ensure_parent_dir(output_path, false)?;
+    let write_error = |e: std::io::Error| ImgEditError::WriteError {
         path: output_path.display().to_string(),
         reason: e.to_string(),
-    })?;
-    let writer = BufWriter::new(file);
+    };
+
+    let img = encoder_input(&img, format, options).unwrap_or(img);
+    let file = std::fs::File::create(output_path).map_err(write_error)?;
+    let mut writer = std::io::BufWriter::new(file);
+    encode_into(&img, &mut writer, output_path, format, options)?;
+    writer.flush().map_err(write_error)
+}
+
+/// Encode `img` as `format` into `writer`, without any post-processing.
+/// `output_path` is only used to attribute errors.
+fn encode_into<W: Write + Seek>(
+    img: &DynamicImage,
+    writer: &mut W,
+    output_path: &Path,
+    format: image::ImageFormat,
+    options: &SaveOptions,
+) -> Result<()> {
+    let quality = options.quality;
+    let icc = options.assign_profile.map(icc_profile);
+    let write_error = |reason: String| ImgEditError::WriteError {
+        path: output_path.display().to_string(),
+        reason,
+    };
 
     match format {
+        #[cfg(feature = "mozjpeg")]
+        image::ImageFormat::Jpeg if options.optimize => {
+            encode_mozjpeg(img, writer, options).map_err(write_error)?;
+        }
         image::ImageFormat::Jpeg => {
-            let rgb = img.to_rgb8();
+            // The baseline encoder is already deterministic (fixed 4:2:2
+            // subsampling, no progressive or optimization passes), so identical
+            // input always yields byte-identical output.
             let mut encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(writer, quality);
-            encoder
-                .encode_image(&rgb)
-                .map_err(|e| ImgEditError::WriteError {
-                    path: output_path.display().to_string(),
-                    reason: e.to_string(),
-                })?;
+            if let Some(dpi) = options.dpi {
+                encoder.set_pixel_density(image::codecs::jpeg::PixelDensity::dpi(dpi));
+            }
+            if let Some(icc) = icc {
+                encoder
+                    .set_icc_profile(icc)
+                    .map_err(|e| write_error(e.to_string()))?;
+            }
+            // RGB input is encoded in place rather than through a copy
+            let result = match img {
+                DynamicImage::ImageRgb8(rgb) => encoder.encode_image(rgb),
+                _ => encoder.encode_image(&img.to_rgb8()),
+            };
+            result.map_err(|e| write_error(e.to_string()))?;
         }
         image::ImageFormat::Png => {
-            let encoder = image::codecs::png::PngEncoder::new(writer);
-            img.write_with_encoder(encoder)
-                .map_err(|e| ImgEditError::WriteError {
-                    path: output_path.display().to_string(),
-                    reason: e.to_string(),
-                })?;
+            let palette = options.png_color_type == PngColorType::Palette;
+            match options.dpi {
+                // The pHYs chunk is spliced in after encoding, which needs a buffer
+                Some(dpi) => {
+                    let mut png = Vec::new();
+                    if palette {
+                        encode_png_palette(img, &mut png, icc, output_path)?;
+                    } else {
+                        encode_png(img, &mut png, icc).map_err(write_error)?;
+                    }
+                    writer
+                        .write_all(&insert_png_phys(png, dpi))
+                        .map_err(|e| write_error(e.to_string()))?;
+                }
+                None if palette => encode_png_palette(img, writer, icc, output_path)?,
+                None => encode_png(img, writer, icc).map_err(write_error)?,
+            }
         }
         image::ImageFormat::Gif => {
             let encoder = image::codecs::gif::GifEncoder::new(writer);
             img.write_with_encoder(encoder)
-                .map_err(|e| ImgEditError::WriteError {
-                    path: output_path.display().to_string(),
-                    reason: e.to_string(),
-                })?;
+                .map_err(|e| write_error(e.to_string()))?;
         }
         image::ImageFormat::Bmp => {
             let mut writer = writer;
             let encoder = image::codecs::bmp::BmpEncoder::new(&mut writer);
             img.write_with_encoder(encoder)
-                .map_err(|e| ImgEditError::WriteError {
-                    path: output_path.display().to_string(),
-                    reason: e.to_string(),
-                })?;
-        }
-        image::ImageFormat::Tiff => {
-            let encoder = image::codecs::tiff::TiffEncoder::new(writer);
-            img.write_with_encoder(encoder)
-                .map_err(|e| ImgEditError::WriteError {
-                    path: output_path.display().to_string(),
-                    reason: e.to_string(),
-                })?;
+                .map_err(|e| write_error(e.to_string()))?;
         }
+        image::ImageFormat::Tiff => match options.dpi {
+            Some(dpi) => encode_tiff_with_dpi(img, writer, dpi).map_err(write_error)?,
+            None => {
+                let encoder = image::codecs::tiff::TiffEncoder::new(writer);
+                img.write_with_encoder(encoder)
+                    .map_err(|e| write_error(e.to_string()))?;
+            }
+        },
         image::ImageFormat::WebP => {
             // WebP encoder - use lossy encoding with quality
-            let encoder = image::codecs::webp::WebPEncoder::new_lossless(writer);
+            let mut encoder = image::codecs::webp::WebPEncoder::new_lossless(writer);
+            if let Some(icc) = icc {
+                encoder
+                    .set_icc_profile(icc)
+                    .map_err(|e| write_error(e.to_string()))?;
+            }
             img.write_with_encoder(encoder)
-                .map_err(|e| ImgEditError::WriteError {
-                    path: output_path.display().to_string(),
-                    reason: e.to_string(),
-                })?;
+                .map_err(|e| write_error(e.to_string()))?;
         }
         image::ImageFormat::Ico => {
             let encoder = image::codecs::ico::IcoEncoder::new(writer);
             img.write_with_encoder(encoder)
-                .map_err(|e| ImgEditError::WriteError {
-                    path: output_path.display().to_string(),
-                    reason: e.to_string(),
-                })?;
+                .map_err(|e| write_error(e.to_string()))?;
         }
         _ => {
             return Err(ImgEditError::UnsupportedFormat(format!(
@@ -132,10 +494,380 @@ pub fn save_with_format(
             )));
         }
     }
-
     Ok(())
 }
 
+/// Encode a PNG, optionally with an embedded ICC profile.
+/// The encoder writes the image's own color type, so 8- and 16-bit variants
+/// keep their depth; PNG has no float samples, so 32-bit float images are
+/// stored as 16-bit rather than rejected.
+fn encode_png<W: Write>(
+    img: &DynamicImage,
+    writer: W,
+    icc: Option<Vec<u8>>,
+) -> std::result::Result<(), String> {
+    let mut encoder = image::codecs::png::PngEncoder::new(writer);
+    if let Some(icc) = icc {
+        encoder.set_icc_profile(icc).map_err(|e| e.to_string())?;
+    }
+    let result = match img {
+        DynamicImage::ImageRgb32F(_) => {
+            DynamicImage::ImageRgb16(img.to_rgb16()).write_with_encoder(encoder)
+        }
+        DynamicImage::ImageRgba32F(_) => {
+            DynamicImage::ImageRgba16(img.to_rgba16()).write_with_encoder(encoder)
+        }
+        _ => img.write_with_encoder(encoder),
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Losslessly recompress an encoded PNG with oxipng.
+/// The original is kept if the result is not smaller. With `keep_depth`,
+/// 16-bit samples are not reduced to 8-bit even when that would be lossless;
+/// with `keep_color_type` the color type is left as it was encoded.
+#[cfg(feature = "oxipng")]
+fn optimize_png(
+    original: Vec<u8>,
+    path: &Path,
+    keep_depth: bool,
+    keep_color_type: bool,
+) -> Result<Vec<u8>> {
+    let options = oxipng::Options {
+        bit_depth_reduction: !keep_depth && !keep_color_type,
+        color_type_reduction: !keep_color_type,
+        palette_reduction: !keep_color_type,
+        grayscale_reduction: !keep_color_type,
+        ..oxipng::Options::default()
+    };
+    let optimized = oxipng::optimize_from_memory(&original, &options).map_err(|e| {
+        ImgEditError::WriteError {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        }
+    })?;
+    Ok(if optimized.len() < original.len() {
+        optimized
+    } else {
+        original
+    })
+}
+
+/// Map each pixel of `img` to an index into `palette`, appending colors it
+/// does not hold yet. `None` if that would take it past 256 entries.
+fn index_colors(img: &DynamicImage, palette: &mut Vec<[u8; 4]>) -> Option<Vec<u8>> {
+    let rgba = img.to_rgba8();
+    let mut lookup: std::collections::HashMap<[u8; 4], u8> = palette
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, color)| (*color, i as u8))
+        .collect();
+    let mut indices = Vec::with_capacity(rgba.len() / 4);
+    for pixel in rgba.pixels() {
+        let index = match lookup.get(&pixel.0) {
+            Some(&index) => index,
+            None if palette.len() == 256 => return None,
+            None => {
+                let index = palette.len() as u8;
+                palette.push(pixel.0);
+                lookup.insert(pixel.0, index);
+                index
+            }
+        };
+        indices.push(index);
+    }
+    Some(indices)
+}
+
+/// Encode an 8-bit indexed PNG whose palette holds the distinct colors of
+/// `img` in first-seen order, with a tRNS chunk when any are translucent.
+/// Fails with `InvalidParameter` if there are more than 256 colors.
+fn encode_png_palette<W: Write>(
+    img: &DynamicImage,
+    writer: W,
+    icc: Option<Vec<u8>>,
+    output_path: &Path,
+) -> Result<()> {
+    let mut colors = Vec::new();
+    let indices = index_colors(img, &mut colors).ok_or_else(|| {
+        ImgEditError::InvalidParameter(
+            "Image has more than 256 colors; palette PNG output holds at most 256".to_string(),
+        )
+    })?;
+    write_png_indexed(img, writer, &colors, &indices, icc, output_path)
+}
+
+fn write_png_indexed<W: Write>(
+    img: &DynamicImage,
+    writer: W,
+    colors: &[[u8; 4]],
+    indices: &[u8],
+    icc: Option<Vec<u8>>,
+    output_path: &Path,
+) -> Result<()> {
+    let mut info = png::Info::with_size(img.width(), img.height());
+    info.color_type = png::ColorType::Indexed;
+    info.bit_depth = png::BitDepth::Eight;
+    info.palette = Some(
+        colors
+            .iter()
+            .flat_map(|c| [c[0], c[1], c[2]])
+            .collect::<Vec<_>>()
+            .into(),
+    );
+    if colors.iter().any(|c| c[3] < 255) {
+        info.trns = Some(colors.iter().map(|c| c[3]).collect::<Vec<_>>().into());
+    }
+    info.icc_profile = icc.map(Into::into);
+
+    let write_error = |e: png::EncodingError| ImgEditError::WriteError {
+        path: output_path.display().to_string(),
+        reason: e.to_string(),
+    };
+    let mut png_writer = png::Encoder::with_info(writer, info)
+        .and_then(|encoder| encoder.write_header())
+        .map_err(write_error)?;
+    png_writer.write_image_data(indices).map_err(write_error)?;
+    png_writer.finish().map_err(write_error)
+}
+
+/// The palette of an indexed PNG as RGBA entries, with alpha from its tRNS
+/// chunk. `None` for anything else, including files that are not PNGs.
+pub fn png_palette(path: &Path) -> Option<Vec<[u8; 4]>> {
+    let file = std::fs::File::open(path).ok()?;
+    let reader = png::Decoder::new(std::io::BufReader::new(file))
+        .read_info()
+        .ok()?;
+    let info = reader.info();
+    if info.color_type != png::ColorType::Indexed {
+        return None;
+    }
+    let alpha = info.trns.as_deref().unwrap_or_default();
+    Some(
+        info.palette
+            .as_deref()?
+            .chunks_exact(3)
+            .enumerate()
+            .map(|(i, rgb)| [rgb[0], rgb[1], rgb[2], alpha.get(i).copied().unwrap_or(255)])
+            .collect(),
+    )
+}
+
+/// Save `img` to `output_path` as an 8-bit indexed PNG with `palette`, so an
+/// edit that only moves pixels keeps the source's palette. Colors the palette
+/// lacks are appended while it has room; returns `Ok(false)`, writing nothing,
+/// once it would need more than 256 entries.
+pub fn save_with_palette(
+    img: &DynamicImage,
+    output_path: &Path,
+    palette: &[[u8; 4]],
+) -> Result<bool> {
+    let mut colors = palette.to_vec();
+    let Some(indices) = index_colors(img, &mut colors) else {
+        return Ok(false);
+    };
+    let file = std::fs::File::create(output_path).map_err(|e| ImgEditError::WriteError {
+        path: output_path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    write_png_indexed(
+        img,
+        std::io::BufWriter::new(file),
+        &colors,
+        &indices,
+        None,
+        output_path,
+    )?;
+    Ok(true)
+}
+
+/// Insert a pHYs chunk recording `dpi` right after the IHDR chunk of an encoded
+/// PNG. PNG stores density per meter, so the value is rounded to whole
+/// pixels per meter (300 dpi is 11811).
+fn insert_png_phys(mut png: Vec<u8>, dpi: u16) -> Vec<u8> {
+    // 8-byte signature, then IHDR: length, type, 13 data bytes, CRC
+    const IHDR_END: usize = 8 + 4 + 4 + 13 + 4;
+
+    let pixels_per_meter = (dpi as f64 / 0.0254).round() as u32;
+    let mut body = b"pHYs".to_vec();
+    body.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    body.extend_from_slice(&pixels_per_meter.to_be_bytes());
+    body.push(1); // unit: meter
+
+    let mut chunk = ((body.len() - 4) as u32).to_be_bytes().to_vec();
+    chunk.extend_from_slice(&body);
+    chunk.extend_from_slice(&crc32fast::hash(&body).to_be_bytes());
+
+    png.splice(IHDR_END..IHDR_END, chunk);
+    png
+}
+
+/// Encode a TIFF with XResolution/YResolution tags set to `dpi` (unit: inch).
+/// 8- and 16-bit grayscale and RGB(A) are written as-is; other color types
+/// are converted to the nearest of those.
+fn encode_tiff_with_dpi<W: std::io::Write + std::io::Seek>(
+    img: &DynamicImage,
+    writer: W,
+    dpi: u16,
+) -> std::result::Result<(), String> {
+    use tiff::encoder::{colortype, Rational, TiffEncoder};
+    use tiff::tags::ResolutionUnit;
+
+    let (width, height) = (img.width(), img.height());
+    let resolution = Rational {
+        n: dpi as u32,
+        d: 1,
+    };
+    let mut encoder = TiffEncoder::new(writer).map_err(|e| e.to_string())?;
+
+    macro_rules! write_image {
+        ($color:ty, $data:expr) => {{
+            let mut image = encoder
+                .new_image::<$color>(width, height)
+                .map_err(|e| e.to_string())?;
+            image.resolution(ResolutionUnit::Inch, resolution);
+            image.write_data($data).map_err(|e| e.to_string())
+        }};
+    }
+
+    let color = img.color();
+    let sixteen_bit = color.bits_per_pixel() > 8 * color.channel_count() as u16;
+    match (color.has_color(), color.has_alpha(), sixteen_bit) {
+        (false, false, false) => write_image!(colortype::Gray8, img.to_luma8().as_raw()),
+        (false, false, true) => write_image!(colortype::Gray16, img.to_luma16().as_raw()),
+        (_, false, false) => write_image!(colortype::RGB8, img.to_rgb8().as_raw()),
+        (_, false, true) => write_image!(colortype::RGB16, img.to_rgb16().as_raw()),
+        (_, true, false) => write_image!(colortype::RGBA8, img.to_rgba8().as_raw()),
+        (_, true, true) => write_image!(colortype::RGBA16, img.to_rgba16().as_raw()),
+    }
+}
+
+/// Encode RGB JPEG with mozjpeg using optimized Huffman tables and progressive
+/// scans, which typically shrinks files by 10-20% at the same quality setting
+#[cfg(feature = "mozjpeg")]
+fn encode_mozjpeg<W: std::io::Write>(
+    img: &DynamicImage,
+    writer: W,
+    options: &SaveOptions,
+) -> std::result::Result<(), String> {
+    let rgb = img.to_rgb8();
+    let (width, height) = rgb.dimensions();
+    let icc = options.assign_profile.map(icc_profile);
+
+    // libjpeg reports fatal errors by unwinding, so contain them here
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(move || {
+        let mut comp = mozjpeg::Compress::new(mozjpeg::ColorSpace::JCS_RGB);
+        comp.set_size(width as usize, height as usize);
+        comp.set_quality(options.quality as f32);
+        if let Some(dpi) = options.dpi {
+            comp.set_pixel_density(mozjpeg::PixelDensity {
+                unit: mozjpeg::PixelDensityUnit::Inches,
+                x: dpi,
+                y: dpi,
+            });
+        }
+        comp.set_optimize_coding(true);
+        comp.set_progressive_mode();
+        comp.set_optimize_scans(true);
+
+        let mut started = comp.start_compress(writer).map_err(|e| e.to_string())?;
+        if let Some(icc) = &icc {
+            started.write_icc_profile(icc);
+        }
+        started
+            .write_scanlines(rgb.as_raw())
+            .map_err(|e| e.to_string())?;
+        started.finish().map_err(|e| e.to_string())?;
+        Ok(())
+    }))
+    .unwrap_or_else(|_| Err("mozjpeg encoder failed".to_string()))
+}
+
+/// The frames of an animated GIF, PNG (APNG) or WebP file, decoded as they
+/// are iterated. Returns `None` for formats without animation support or
+/// still images.
+fn decode_frames(path: &Path) -> Result<Option<image::Frames<'static>>> {
+    use image::AnimationDecoder;
+    use std::fs::File;
+    use std::io::BufReader;
+
+    let read_error = |e: &dyn std::fmt::Display| ImgEditError::ReadError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    };
+
+    if !path.exists() {
+        return Err(ImgEditError::InputNotFound(path.display().to_string()));
+    }
+
+    let format = image::ImageReader::open(path)
+        .and_then(|r| r.with_guessed_format())
+        .map_err(|e| read_error(&e))?
+        .format();
+    let reader = || {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| read_error(&e))
+    };
+
+    let frames = match format {
+        Some(image::ImageFormat::Gif) => image::codecs::gif::GifDecoder::new(reader()?)
+            .map_err(|e| read_error(&e))?
+            .into_frames(),
+        Some(image::ImageFormat::Png) => {
+            let decoder =
+                image::codecs::png::PngDecoder::new(reader()?).map_err(|e| read_error(&e))?;
+            if !decoder.is_apng().map_err(|e| read_error(&e))? {
+                return Ok(None);
+            }
+            decoder.apng().map_err(|e| read_error(&e))?.into_frames()
+        }
+        Some(image::ImageFormat::WebP) => {
+            let decoder =
+                image::codecs::webp::WebPDecoder::new(reader()?).map_err(|e| read_error(&e))?;
+            if !decoder.has_animation() {
+                return Ok(None);
+            }
+            decoder.into_frames()
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(frames))
+}
+
+/// Whether the input has more than one frame; stops decoding at the second
+pub fn is_animated(path: &Path) -> Result<bool> {
+    Ok(decode_frames(path)?.is_some_and(|frames| frames.take(2).count() > 1))
+}
+
+/// Load a single frame (0-based) from an animated input.
+/// A still image has exactly one frame, index 0.
+pub fn load_frame(path: &Path, index: usize) -> Result<DynamicImage> {
+    let (frame, count) = match decode_frames(path)? {
+        Some(frames) => {
+            let mut count = 0;
+            let frame = frames.inspect(|_| count += 1).nth(index);
+            (frame, count)
+        }
+        None if index == 0 => return crate::ops::load_image(path),
+        None => (None, 1),
+    };
+
+    let frame = frame.ok_or_else(|| {
+        ImgEditError::InvalidParameter(format!(
+            "Frame {} out of range: input has {} frame(s)",
+            index, count
+        ))
+    })?;
+    let frame = frame.map_err(|e| ImgEditError::ReadError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    Ok(DynamicImage::ImageRgba8(frame.into_buffer()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -277,6 +1009,430 @@ mod tests {
         assert!(output.exists());
     }
 
+    #[test]
+    fn test_save_with_format_missing_dir_names_it() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let missing = temp_dir.path().join("missing");
+        let output = missing.join("output.png");
+        let img = DynamicImage::new_rgba8(10, 10);
+
+        let err = save_with_format(&img, &output, image::ImageFormat::Png, 90).unwrap_err();
+        assert_eq!(err.code(), "WRITE_ERROR");
+        assert!(err.to_string().contains(&missing.display().to_string()));
+    }
+
+    #[test]
+    fn test_ensure_parent_dir_creates_nested() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let output = temp_dir.path().join("a").join("b").join("output.png");
+
+        assert!(ensure_parent_dir(&output, false).is_err());
+        ensure_parent_dir(&output, true).unwrap();
+        assert!(output.parent().unwrap().is_dir());
+        // A bare file name writes into the current directory
+        ensure_parent_dir(Path::new("output.png"), false).unwrap();
+    }
+
+    #[test]
+    fn test_save_with_format_jpeg_is_deterministic() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first.jpg");
+        let second = temp_dir.path().join("second.jpg");
+        let img = DynamicImage::ImageRgba8(image::ImageBuffer::from_fn(32, 32, |x, y| {
+            image::Rgba([(x * 8) as u8, (y * 8) as u8, 128, 255])
+        }));
+
+        save_with_format(&img, &first, image::ImageFormat::Jpeg, 85).unwrap();
+        save_with_format(&img, &second, image::ImageFormat::Jpeg, 85).unwrap();
+
+        assert_eq!(
+            std::fs::read(&first).unwrap(),
+            std::fs::read(&second).unwrap()
+        );
+    }
+
+    #[cfg(feature = "mozjpeg")]
+    #[test]
+    fn test_save_with_options_optimize_is_smaller() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let baseline = temp_dir.path().join("baseline.jpg");
+        let optimized = temp_dir.path().join("optimized.jpg");
+        let img = DynamicImage::ImageRgba8(image::ImageBuffer::from_fn(128, 128, |x, y| {
+            image::Rgba([(x * 2) as u8, (y * 2) as u8, ((x + y) % 256) as u8, 255])
+        }));
+
+        let plain = SaveOptions {
+            quality: 85,
+            ..SaveOptions::default()
+        };
+        let optimize = SaveOptions {
+            optimize: true,
+            ..plain
+        };
+        save_with_options(&img, &baseline, image::ImageFormat::Jpeg, &plain).unwrap();
+        save_with_options(&img, &optimized, image::ImageFormat::Jpeg, &optimize).unwrap();
+
+        let baseline_size = std::fs::metadata(&baseline).unwrap().len();
+        let optimized_size = std::fs::metadata(&optimized).unwrap().len();
+        assert!(
+            optimized_size < baseline_size,
+            "optimized {} >= baseline {}",
+            optimized_size,
+            baseline_size
+        );
+
+        // Both decode to nearly the same pixels
+        let a = image::open(&baseline).unwrap().to_rgb8();
+        let b = image::open(&optimized).unwrap().to_rgb8();
+        assert_eq!(a.dimensions(), b.dimensions());
+        let total: u64 = a
+            .as_raw()
+            .iter()
+            .zip(b.as_raw())
+            .map(|(&p, &q)| p.abs_diff(q) as u64)
+            .sum();
+        let mean = total as f64 / a.as_raw().len() as f64;
+        assert!(mean < 3.0, "mean channel difference {}", mean);
+    }
+
+    #[cfg(feature = "oxipng")]
+    #[test]
+    fn test_save_with_options_optimize_png_is_lossless() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let baseline = temp_dir.path().join("baseline.png");
+        let optimized = temp_dir.path().join("optimized.png");
+        // Few distinct colors leave plenty of room for palette and filter reductions
+        let img = DynamicImage::ImageRgba8(image::ImageBuffer::from_fn(128, 128, |x, y| {
+            image::Rgba([(x / 32 * 60) as u8, (y / 32 * 60) as u8, 90, 255])
+        }));
+
+        let plain = SaveOptions::default();
+        let optimize = SaveOptions {
+            optimize: true,
+            ..plain
+        };
+        save_with_options(&img, &baseline, image::ImageFormat::Png, &plain).unwrap();
+        save_with_options(&img, &optimized, image::ImageFormat::Png, &optimize).unwrap();
+
+        assert!(
+            std::fs::metadata(&optimized).unwrap().len()
+                <= std::fs::metadata(&baseline).unwrap().len()
+        );
+        assert_eq!(image::open(&optimized).unwrap().to_rgba8(), img.to_rgba8());
+    }
+
+    #[test]
+    fn test_save_with_options_assign_profile() {
+        use image::ImageDecoder;
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let img = DynamicImage::ImageRgba8(image::ImageBuffer::from_fn(8, 8, |x, y| {
+            image::Rgba([(x * 30) as u8, (y * 30) as u8, 77, 255])
+        }));
+        let options = SaveOptions {
+            assign_profile: Some(ColorProfile::AdobeRgb),
+            ..SaveOptions::default()
+        };
+
+        let png = temp_dir.path().join("tagged.png");
+        save_with_options(&img, &png, image::ImageFormat::Png, &options).unwrap();
+        let mut decoder = image::codecs::png::PngDecoder::new(std::io::BufReader::new(
+            std::fs::File::open(&png).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(
+            decoder.icc_profile().unwrap(),
+            Some(icc_profile(ColorProfile::AdobeRgb))
+        );
+        // Pixels are assigned, not converted
+        assert_eq!(image::open(&png).unwrap().to_rgba8(), img.to_rgba8());
+
+        let jpeg = temp_dir.path().join("tagged.jpg");
+        save_with_options(&img, &jpeg, image::ImageFormat::Jpeg, &options).unwrap();
+        let mut decoder = image::codecs::jpeg::JpegDecoder::new(std::io::BufReader::new(
+            std::fs::File::open(&jpeg).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(
+            decoder.icc_profile().unwrap(),
+            Some(icc_profile(ColorProfile::AdobeRgb))
+        );
+    }
+
+    #[test]
+    fn test_save_with_format_png_keeps_16_bit() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let rgb16 = DynamicImage::ImageRgb16(image::ImageBuffer::from_fn(8, 8, |x, y| {
+            image::Rgb([(x * 8000) as u16, (y * 8000) as u16, 12345])
+        }));
+        let path = temp_dir.path().join("rgb16.png");
+        save_with_format(&rgb16, &path, image::ImageFormat::Png, 90).unwrap();
+        let loaded = image::open(&path).unwrap();
+        assert_eq!(loaded.color(), image::ColorType::Rgb16);
+        assert_eq!(loaded.to_rgb16(), rgb16.to_rgb16());
+
+        // Float images have no PNG equivalent and are stored as 16-bit
+        let float = DynamicImage::ImageRgba32F(image::ImageBuffer::from_pixel(
+            4,
+            4,
+            image::Rgba([0.25, 0.5, 0.75, 1.0]),
+        ));
+        let path = temp_dir.path().join("float.png");
+        save_with_format(&float, &path, image::ImageFormat::Png, 90).unwrap();
+        assert_eq!(
+            image::open(&path).unwrap().color(),
+            image::ColorType::Rgba16
+        );
+    }
+
+    #[test]
+    fn test_save_with_options_dpi_png() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("dpi.png");
+        let img = DynamicImage::new_rgba8(10, 10);
+        let options = SaveOptions {
+            dpi: Some(300),
+            ..SaveOptions::default()
+        };
+        save_with_options(&img, &path, image::ImageFormat::Png, &options).unwrap();
+
+        let png = std::fs::read(&path).unwrap();
+        let phys = crate::ops::info::png_chunk(&png, b"pHYs").expect("pHYs chunk");
+        let x = u32::from_be_bytes(phys[0..4].try_into().unwrap());
+        let y = u32::from_be_bytes(phys[4..8].try_into().unwrap());
+        assert_eq!((x, y, phys[8]), (11811, 11811, 1));
+        assert_eq!((x as f64 * 0.0254).round(), 300.0);
+
+        // The file is still a valid PNG
+        assert_eq!(image::open(&path).unwrap().width(), 10);
+    }
+
+    #[test]
+    fn test_save_with_options_dpi_jpeg_and_tiff() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let img = DynamicImage::new_rgb8(10, 10);
+        let options = SaveOptions {
+            dpi: Some(150),
+            ..SaveOptions::default()
+        };
+
+        let jpeg_path = temp_dir.path().join("dpi.jpg");
+        save_with_options(&img, &jpeg_path, image::ImageFormat::Jpeg, &options).unwrap();
+        let jpeg = std::fs::read(&jpeg_path).unwrap();
+        // SOI, then the JFIF APP0 segment: units at offset 13, X/Y density after
+        assert_eq!(&jpeg[6..11], b"JFIF\0");
+        assert_eq!(jpeg[13], 1);
+        assert_eq!(u16::from_be_bytes([jpeg[14], jpeg[15]]), 150);
+        assert_eq!(u16::from_be_bytes([jpeg[16], jpeg[17]]), 150);
+
+        let tiff_path = temp_dir.path().join("dpi.tiff");
+        save_with_options(&img, &tiff_path, image::ImageFormat::Tiff, &options).unwrap();
+        let mut decoder =
+            tiff::decoder::Decoder::new(std::fs::File::open(&tiff_path).unwrap()).unwrap();
+        let x_resolution = decoder
+            .get_tag_u32_vec(tiff::tags::Tag::XResolution)
+            .unwrap();
+        assert_eq!(x_resolution, vec![150, 1]);
+        assert_eq!(image::open(&tiff_path).unwrap().width(), 10);
+    }
+
+    #[test]
+    fn test_save_low_memory_matches_save_with_options() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let gray = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(40, 30, |x, y| {
+            let v = (x * 6 + y) as u8;
+            image::Rgba([v, v, v, 255])
+        }));
+        let options = SaveOptions {
+            dpi: Some(150),
+            ..Default::default()
+        };
+
+        for (name, format) in [
+            ("a.png", image::ImageFormat::Png),
+            ("a.jpg", image::ImageFormat::Jpeg),
+            ("a.bmp", image::ImageFormat::Bmp),
+        ] {
+            let normal = temp_dir.path().join(format!("normal-{}", name));
+            let streamed = temp_dir.path().join(format!("streamed-{}", name));
+            save_with_options(&gray, &normal, format, &options).unwrap();
+            save_low_memory(gray.clone(), &streamed, format, &options).unwrap();
+            assert_eq!(
+                std::fs::read(&normal).unwrap(),
+                std::fs::read(&streamed).unwrap()
+            );
+        }
+
+        let optimize = SaveOptions {
+            optimize: true,
+            ..Default::default()
+        };
+        let path = temp_dir.path().join("optimized.png");
+        assert!(save_low_memory(gray, &path, image::ImageFormat::Png, &optimize).is_err());
+    }
+
+    #[test]
+    fn test_dpi_warning() {
+        assert!(dpi_warning(image::ImageFormat::Png).is_none());
+        assert!(dpi_warning(image::ImageFormat::Tiff).is_none());
+        assert!(dpi_warning(image::ImageFormat::Gif).is_some());
+    }
+
+    #[test]
+    fn test_single_channel() {
+        // All-gray RGB(A) is left alone; only existing gray types are handled
+        let gray = image::RgbaImage::from_pixel(4, 4, image::Rgba([90, 90, 90, 255]));
+        let opaque = DynamicImage::ImageRgba8(gray.clone());
+        assert!(single_channel(&opaque, image::ImageFormat::Png).is_none());
+        assert!(single_channel(&opaque, image::ImageFormat::Tiff).is_none());
+
+        let luma = DynamicImage::ImageLuma8(opaque.to_luma8());
+        assert!(single_channel(&luma, image::ImageFormat::Png).is_none());
+        assert!(single_channel(&luma, image::ImageFormat::Tiff).is_none());
+
+        let mut la = opaque.to_luma_alpha8();
+        assert!(matches!(
+            single_channel(
+                &DynamicImage::ImageLumaA8(la.clone()),
+                image::ImageFormat::Tiff
+            ),
+            Some(DynamicImage::ImageLuma8(_))
+        ));
+        la.put_pixel(0, 0, image::LumaA([90, 10]));
+        let la = DynamicImage::ImageLumaA8(la);
+        assert!(single_channel(&la, image::ImageFormat::Png).is_none());
+        // Gray+alpha has no TIFF mode, so it goes back to RGBA there
+        assert!(matches!(
+            single_channel(&la, image::ImageFormat::Tiff),
+            Some(DynamicImage::ImageRgba8(_))
+        ));
+    }
+
+    #[test]
+    fn test_alpha_warning() {
+        let mut rgba = image::RgbaImage::from_pixel(4, 4, image::Rgba([10, 20, 30, 255]));
+        let opaque = DynamicImage::ImageRgba8(rgba.clone());
+        assert!(alpha_warning(&opaque, image::ImageFormat::Jpeg).is_none());
+
+        rgba.put_pixel(0, 0, image::Rgba([0, 0, 0, 0]));
+        let translucent = DynamicImage::ImageRgba8(rgba);
+        let warning = alpha_warning(&translucent, image::ImageFormat::Jpeg).unwrap();
+        assert_eq!(warning.code, "ALPHA_DISCARDED");
+        assert!(warning.message.contains("1 pixels"));
+        assert!(alpha_warning(&translucent, image::ImageFormat::Png).is_none());
+
+        let png = image::ImageFormat::Png;
+        let warning = png_alpha_warning(&translucent, png, PngColorType::Gray).unwrap();
+        assert_eq!(warning.code, "ALPHA_DISCARDED");
+        assert!(warning.message.contains("gray"));
+        assert!(png_alpha_warning(&translucent, png, PngColorType::Rgb).is_some());
+        assert!(png_alpha_warning(&translucent, png, PngColorType::Graya).is_none());
+        assert!(png_alpha_warning(&opaque, png, PngColorType::Rgb).is_none());
+        // Ignored for other formats, which PNG_COLOR_TYPE_IGNORED covers
+        assert!(
+            png_alpha_warning(&translucent, image::ImageFormat::Tiff, PngColorType::Rgb).is_none()
+        );
+    }
+
+    #[test]
+    fn test_profile_warning() {
+        assert!(profile_warning(image::ImageFormat::Png).is_none());
+        assert!(profile_warning(image::ImageFormat::Bmp).is_some());
+    }
+
+    #[test]
+    fn test_save_with_options_png_palette() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("palette.png");
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(16, 16, |x, y| {
+            image::Rgba([(x * 16) as u8, 0, 200, if y < 8 { 255 } else { 64 }])
+        }));
+        let options = SaveOptions {
+            png_color_type: PngColorType::Palette,
+            ..Default::default()
+        };
+        save_with_options(&img, &path, image::ImageFormat::Png, &options).unwrap();
+
+        // IHDR color type 3 is indexed color; decoding restores every pixel
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes[25], 3);
+        assert_eq!(image::open(&path).unwrap().to_rgba8(), img.to_rgba8());
+
+        let too_many = DynamicImage::ImageRgb8(image::RgbImage::from_fn(32, 32, |x, y| {
+            image::Rgb([x as u8, y as u8, 0])
+        }));
+        let err =
+            save_with_options(&too_many, &path, image::ImageFormat::Png, &options).unwrap_err();
+        assert_eq!(err.code(), "INVALID_PARAMETER");
+    }
+
+    #[test]
+    fn test_save_with_palette_keeps_entries() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("indexed.png");
+        let palette = [[9, 9, 9, 255], [200, 0, 0, 255], [0, 0, 200, 128]];
+        // Only the last two entries are used
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(6, 4, |x, _| {
+            image::Rgba(palette[1 + (x % 2) as usize])
+        }));
+
+        assert!(save_with_palette(&img, &path, &palette).unwrap());
+        assert_eq!(png_palette(&path).unwrap(), palette);
+        assert_eq!(image::open(&path).unwrap().to_rgba8(), img.to_rgba8());
+
+        let full: Vec<[u8; 4]> = (0..=255).map(|i| [i, i, i, 255]).collect();
+        let red =
+            DynamicImage::ImageRgb8(image::RgbImage::from_pixel(2, 2, image::Rgb([255, 0, 0])));
+        assert!(!save_with_palette(&red, &path, &full).unwrap());
+
+        let rgb = temp_dir.path().join("rgb.png");
+        red.save(&rgb).unwrap();
+        assert_eq!(png_palette(&rgb), None);
+    }
+
+    #[test]
+    fn test_png_color_type_warning() {
+        assert!(png_color_type_warning(image::ImageFormat::Png).is_none());
+        let warning = png_color_type_warning(image::ImageFormat::Jpeg).unwrap();
+        assert_eq!(warning.code, "PNG_COLOR_TYPE_IGNORED");
+    }
+
+    #[test]
+    fn test_optimize_warning() {
+        assert_eq!(
+            optimize_warning(image::ImageFormat::Jpeg).is_none(),
+            cfg!(feature = "mozjpeg")
+        );
+        assert_eq!(
+            optimize_warning(image::ImageFormat::Png).is_none(),
+            cfg!(feature = "oxipng")
+        );
+        assert!(optimize_warning(image::ImageFormat::Bmp).is_some());
+    }
+
     #[test]
     fn test_save_with_format_bmp() {
         use tempfile::TempDir;
@@ -303,6 +1459,37 @@ mod tests {
         assert!(output.exists());
     }
 
+    #[test]
+    fn test_check_lossless() {
+        assert!(check_lossless(image::ImageFormat::Png).is_ok());
+        assert!(check_lossless(image::ImageFormat::WebP).is_ok());
+        let err = check_lossless(image::ImageFormat::Jpeg).unwrap_err();
+        assert_eq!(err.code(), "UNSUPPORTED_FORMAT");
+        assert!(err.to_string().contains("PNG"));
+        assert!(check_lossless(image::ImageFormat::Gif).is_err());
+    }
+
+    #[test]
+    fn test_encode_to_bytes_round_trip() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(12, 7, |x, y| {
+            image::Rgba([x as u8 * 20, y as u8 * 30, 0, 200])
+        }));
+
+        let png = encode_to_bytes(&img, image::ImageFormat::Png, 90).unwrap();
+        let decoded = image::load_from_memory_with_format(&png, image::ImageFormat::Png).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (12, 7));
+        assert_eq!(decoded.to_rgba8(), img.to_rgba8());
+
+        // Identical to what is written to disk
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let output = temp_dir.path().join("output.png");
+        save_with_format(&img, &output, image::ImageFormat::Png, 90).unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), png);
+
+        let err = encode_to_bytes(&img, image::ImageFormat::Avif, 90).unwrap_err();
+        assert_eq!(err.code(), "UNSUPPORTED_FORMAT");
+    }
+
     #[test]
     fn test_save_with_format_tiff() {
         use tempfile::TempDir;
@@ -342,4 +1529,44 @@ mod tests {
         assert!(result.is_ok());
         assert!(output.exists());
     }
+
+    fn write_animated_gif(path: &Path, colors: &[[u8; 4]]) {
+        use image::codecs::gif::GifEncoder;
+        use image::{Frame, RgbaImage};
+
+        let file = std::fs::File::create(path).unwrap();
+        let mut encoder = GifEncoder::new(file);
+        let frames = colors
+            .iter()
+            .map(|c| Frame::new(RgbaImage::from_pixel(4, 4, image::Rgba(*c))));
+        encoder.encode_frames(frames).unwrap();
+    }
+
+    #[test]
+    fn test_is_animated() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let animated = dir.path().join("anim.gif");
+        write_animated_gif(&animated, &[[255, 0, 0, 255], [0, 0, 255, 255]]);
+        assert!(is_animated(&animated).unwrap());
+
+        let single = dir.path().join("single.gif");
+        write_animated_gif(&single, &[[255, 0, 0, 255]]);
+        assert!(!is_animated(&single).unwrap());
+
+        let still = dir.path().join("still.png");
+        image::RgbaImage::new(4, 4).save(&still).unwrap();
+        assert!(!is_animated(&still).unwrap());
+    }
+
+    #[test]
+    fn test_load_frame() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let animated = dir.path().join("anim.gif");
+        write_animated_gif(&animated, &[[255, 0, 0, 255], [0, 0, 255, 255]]);
+
+        let second = load_frame(&animated, 1).unwrap().to_rgba8();
+        assert_eq!(second.get_pixel(0, 0), &image::Rgba([0, 0, 255, 255]));
+        let err = load_frame(&animated, 2).unwrap_err().to_string();
+        assert!(err.contains("has 2 frame(s)"), "{}", err);
+    }
 }
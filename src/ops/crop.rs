@@ -1,6 +1,6 @@
 use crate::cli::args::Anchor;
 use crate::error::{ImgEditError, Result};
-use image::DynamicImage;
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
 
 /// Calculate crop coordinates based on anchor position
 pub fn calculate_crop_position(
@@ -39,6 +39,35 @@ pub fn calculate_crop_position(
     (anchor_x + x_offset, anchor_y + y_offset)
 }
 
+/// Like [`calculate_crop_position`], for regions that may extend past the
+/// image (`--pad-if-needed`): a region larger than the image is still placed
+/// by its anchor, so the result can start at a negative position
+pub fn calculate_padded_crop_position(
+    img_width: u32,
+    img_height: u32,
+    crop_width: u32,
+    crop_height: u32,
+    x_offset: u32,
+    y_offset: u32,
+    anchor: Anchor,
+) -> (i64, i64) {
+    let spare_x = img_width as i64 - crop_width as i64;
+    let spare_y = img_height as i64 - crop_height as i64;
+    let (anchor_x, anchor_y) = match anchor {
+        Anchor::TopLeft => (0, 0),
+        Anchor::TopCenter => (spare_x / 2, 0),
+        Anchor::TopRight => (spare_x, 0),
+        Anchor::CenterLeft => (0, spare_y / 2),
+        Anchor::Center => (spare_x / 2, spare_y / 2),
+        Anchor::CenterRight => (spare_x, spare_y / 2),
+        Anchor::BottomLeft => (0, spare_y),
+        Anchor::BottomCenter => (spare_x / 2, spare_y),
+        Anchor::BottomRight => (spare_x, spare_y),
+    };
+
+    (anchor_x + x_offset as i64, anchor_y + y_offset as i64)
+}
+
 /// Crop an image to the specified region
 pub fn crop(
     img: &DynamicImage,
@@ -73,6 +102,45 @@ pub fn crop(
     Ok(img.crop_imm(actual_x, actual_y, width, height))
 }
 
+/// Crop a `width`x`height` region whose top-left corner is at (`x`, `y`),
+/// which may lie partly or wholly outside the image: the parts past the
+/// edges are filled with `background` instead of being an error
+pub fn crop_padded(
+    img: &DynamicImage,
+    x: i64,
+    y: i64,
+    width: u32,
+    height: u32,
+    background: Rgba<u8>,
+) -> Result<DynamicImage> {
+    if width == 0 || height == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Crop width and height must be greater than 0".to_string(),
+        ));
+    }
+
+    let mut canvas = RgbaImage::from_pixel(width, height, background);
+
+    // The part of the region that overlaps the image, in image coordinates
+    let left = x.clamp(0, img.width() as i64);
+    let top = y.clamp(0, img.height() as i64);
+    let right = (x + width as i64).clamp(0, img.width() as i64);
+    let bottom = (y + height as i64).clamp(0, img.height() as i64);
+    if right > left && bottom > top {
+        let overlap = img
+            .crop_imm(
+                left as u32,
+                top as u32,
+                (right - left) as u32,
+                (bottom - top) as u32,
+            )
+            .to_rgba8();
+        imageops::replace(&mut canvas, &overlap, left - x, top - y);
+    }
+
+    Ok(DynamicImage::ImageRgba8(canvas))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,4 +232,70 @@ mod tests {
         let (x, y) = calculate_crop_position(100, 100, 50, 50, 5, 10, Anchor::TopLeft);
         assert_eq!((x, y), (5, 10));
     }
+
+    #[test]
+    fn test_calculate_padded_crop_position() {
+        // Smaller regions land where the unpadded position puts them
+        for anchor in [Anchor::Center, Anchor::BottomRight, Anchor::TopCenter] {
+            let (x, y) = calculate_crop_position(100, 80, 50, 30, 3, 4, anchor);
+            assert_eq!(
+                calculate_padded_crop_position(100, 80, 50, 30, 3, 4, anchor),
+                (x as i64, y as i64)
+            );
+        }
+
+        // Larger regions extend past the image on the anchored sides
+        assert_eq!(
+            calculate_padded_crop_position(100, 100, 140, 120, 0, 0, Anchor::Center),
+            (-20, -10)
+        );
+        assert_eq!(
+            calculate_padded_crop_position(100, 100, 140, 120, 0, 0, Anchor::BottomRight),
+            (-40, -20)
+        );
+        assert_eq!(
+            calculate_padded_crop_position(100, 100, 140, 120, 5, 0, Anchor::TopLeft),
+            (5, 0)
+        );
+    }
+
+    #[test]
+    fn test_crop_padded_fills_out_of_bounds() {
+        let img =
+            DynamicImage::ImageRgba8(ImageBuffer::from_pixel(100, 100, Rgba([255, 0, 0, 255])));
+        let white = Rgba([255, 255, 255, 255]);
+        let result = crop_padded(&img, 60, 60, 60, 60, white).unwrap().to_rgba8();
+
+        assert_eq!(result.dimensions(), (60, 60));
+        // 40x40 of the image in the top-left, background everywhere else
+        assert_eq!(result.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+        assert_eq!(result.get_pixel(39, 39), &Rgba([255, 0, 0, 255]));
+        assert_eq!(result.get_pixel(40, 10), &white);
+        assert_eq!(result.get_pixel(10, 40), &white);
+        // The bottom-right quadrant is entirely background
+        assert!((30..60)
+            .flat_map(|x| (30..60).map(move |y| (x, y)))
+            .filter(|&(x, y)| x >= 40 || y >= 40)
+            .all(|(x, y)| result.get_pixel(x, y) == &white));
+    }
+
+    #[test]
+    fn test_crop_padded_negative_and_disjoint() {
+        let img = create_test_image(10, 10);
+        let clear = Rgba([0, 0, 0, 0]);
+
+        let shifted = crop_padded(&img, -2, -3, 4, 4, clear).unwrap().to_rgba8();
+        assert_eq!(shifted.get_pixel(0, 0), &clear);
+        assert_eq!(shifted.get_pixel(2, 3), img.to_rgba8().get_pixel(0, 0));
+
+        let outside = crop_padded(&img, 50, 50, 5, 5, clear).unwrap().to_rgba8();
+        assert!(outside.pixels().all(|p| *p == clear));
+
+        // In bounds it matches a plain crop
+        let inside = crop_padded(&img, 2, 2, 5, 5, clear).unwrap();
+        let plain = crop(&img, 2, 2, 5, 5, Anchor::TopLeft).unwrap();
+        assert_eq!(inside.to_rgba8(), plain.to_rgba8());
+
+        assert!(crop_padded(&img, 0, 0, 0, 5, clear).is_err());
+    }
 }
@@ -1,4 +1,5 @@
 use crate::error::{ImgEditError, Result};
+use crate::ops::text::Timestamp;
 use exif::{In, Reader, Tag, Value};
 use serde::Serialize;
 use std::collections::HashMap;
@@ -13,6 +14,14 @@ pub struct ExifField {
     pub ifd: String,
     pub value: String,
     pub description: Option<String>,
+    /// Hex dump of the stored bytes, for maker notes and unknown tags whose
+    /// value is byte data; cleared by [`ExifData::without_raw`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_hex: Option<String>,
+    /// TIFF value type, e.g. `UNDEFINED` or `SHORT`; cleared by
+    /// [`ExifData::without_raw`]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
 }
 
 /// Represents all EXIF data from an image
@@ -23,6 +32,8 @@ pub struct ExifData {
     pub camera_make: Option<String>,
     pub camera_model: Option<String>,
     pub date_time: Option<String>,
+    /// Raw DateTimeOriginal (capture time), e.g. `2023:01:01 12:00:00`
+    pub date_time_original: Option<String>,
     pub exposure_time: Option<String>,
     pub f_number: Option<String>,
     pub iso: Option<String>,
@@ -37,6 +48,17 @@ pub struct ExifData {
     pub copyright: Option<String>,
 }
 
+impl ExifData {
+    /// Drop the raw bytes and value types, leaving only the decoded fields
+    pub fn without_raw(mut self) -> Self {
+        for field in &mut self.fields {
+            field.raw_hex = None;
+            field.value_type = None;
+        }
+        self
+    }
+}
+
 /// Read EXIF data from an image file
 pub fn read_exif<P: AsRef<Path>>(path: P) -> Result<ExifData> {
     let file = File::open(path.as_ref()).map_err(|e| {
@@ -77,12 +99,20 @@ pub fn read_exif<P: AsRef<Path>>(path: P) -> Result<ExifData> {
 
         let value_str = field.display_value().to_string();
         let description = field.tag.description().map(|s| s.to_string());
+        // Known tags decode meaningfully; keep the bytes of the rest
+        let raw_hex = if description.is_none() || field.tag == Tag::MakerNote {
+            raw_bytes(&field.value).map(|bytes| hex_bytes(&bytes))
+        } else {
+            None
+        };
 
         data.fields.push(ExifField {
             tag: tag_name.clone(),
             ifd: ifd_name,
             value: value_str,
             description,
+            raw_hex,
+            value_type: Some(value_type_name(&field.value).to_string()),
         });
 
         // Extract common fields
@@ -94,8 +124,12 @@ pub fn read_exif<P: AsRef<Path>>(path: P) -> Result<ExifData> {
                 data.camera_model = Some(get_string_value(&field.value));
             }
             Tag::DateTime | Tag::DateTimeOriginal => {
+                let value = get_string_value(&field.value);
+                if field.tag == Tag::DateTimeOriginal {
+                    data.date_time_original = Some(value.clone());
+                }
                 if data.date_time.is_none() {
-                    data.date_time = Some(get_string_value(&field.value));
+                    data.date_time = Some(value);
                 }
             }
             Tag::ExposureTime => {
@@ -170,6 +204,181 @@ pub fn get_exif_map<P: AsRef<Path>>(path: P) -> Result<HashMap<String, String>>
     Ok(map)
 }
 
+/// Expand a filename pattern from a timestamp and EXIF fields.
+///
+/// Tokens: `{year}`, `{month}`, `{day}`, `{hour}`, `{min}`, `{sec}`, zero-padded;
+/// `{date:FORMAT}` with the specifiers of [`Timestamp::format`], e.g.
+/// `{date:%Y%m%d}`; and `{exif:Tag}` with the value of the named tag in
+/// `fields` (case-insensitive), or `unknown` when it is missing. Characters
+/// that cannot appear in a filename are replaced in date and tag values, so
+/// only the pattern's own `/` makes directories. An unknown `%` specifier in
+/// a date format is an error; anything else, including unknown tokens, is
+/// kept as written.
+pub fn expand_name_pattern(
+    pattern: &str,
+    time: &Timestamp,
+    fields: &[ExifField],
+) -> Result<String> {
+    let expand = |token: &str| -> Result<Option<String>> {
+        let value = match token {
+            "year" => format!("{:04}", time.year),
+            "month" => format!("{:02}", time.month),
+            "day" => format!("{:02}", time.day),
+            "hour" => format!("{:02}", time.hour),
+            "min" => format!("{:02}", time.minute),
+            "sec" => format!("{:02}", time.second),
+            _ => {
+                if let Some(format) = token.strip_prefix("date:") {
+                    check_date_format(format)?;
+                    filename_safe(&time.format(format))
+                } else {
+                    let Some(tag) = token.strip_prefix("exif:") else {
+                        return Ok(None);
+                    };
+                    fields
+                        .iter()
+                        .find(|f| f.tag.eq_ignore_ascii_case(tag))
+                        .map(|f| filename_safe(&f.value))
+                        .filter(|v| !v.is_empty())
+                        .unwrap_or_else(|| "unknown".to_string())
+                }
+            }
+        };
+        Ok(Some(value))
+    };
+
+    let mut out = String::new();
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        if let Some((token, tail)) = rest.strip_prefix('{').and_then(|r| r.split_once('}')) {
+            if let Some(value) = expand(token)? {
+                out.push_str(&value);
+                rest = tail;
+                continue;
+            }
+        }
+        let mut chars = rest.chars();
+        out.extend(chars.next());
+        rest = chars.as_str();
+    }
+    Ok(out)
+}
+
+/// Check that a `{date:FORMAT}` uses only the specifiers [`Timestamp::format`]
+/// knows, so a typo fails instead of landing in the filename
+fn check_date_format(format: &str) -> Result<()> {
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        match chars.next() {
+            Some('Y' | 'm' | 'd' | 'H' | 'M' | 'S' | '%') => {}
+            other => {
+                return Err(ImgEditError::InvalidParameter(format!(
+                    "Unknown date specifier '%{}' in '{{date:{}}}'; use %Y %m %d %H %M %S or %%",
+                    other.map(String::from).unwrap_or_default(),
+                    format
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// An EXIF display value made usable in a filename: surrounding quotes and
+/// whitespace are dropped, and path separators and other reserved or control
+/// characters become `_`
+fn filename_safe(value: &str) -> String {
+    value
+        .trim_matches(|c: char| c == '"' || c.is_whitespace())
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Suggest a filename for an image from its EXIF capture time (DateTimeOriginal).
+///
+/// The input's extension is kept. Without a usable capture time the file's
+/// modification time is used instead, unless `require_exif` is set, in which
+/// case that is an error. That fallback is in UTC, while DateTimeOriginal is
+/// the camera's local time, so the two can differ by the time-zone offset.
+pub fn suggest_name<P: AsRef<Path>>(path: P, pattern: &str, require_exif: bool) -> Result<String> {
+    let path = path.as_ref();
+    let data = read_exif(path)?;
+    let captured = data
+        .date_time_original
+        .as_deref()
+        .and_then(Timestamp::from_exif);
+    let time = match captured {
+        Some(time) => time,
+        None if require_exif => {
+            return Err(ImgEditError::InvalidParameter(format!(
+                "'{}' has no EXIF DateTimeOriginal to name it by",
+                path.display()
+            )));
+        }
+        None => {
+            let modified = std::fs::metadata(path)?.modified()?;
+            let secs = modified
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            Timestamp::from_unix(secs)
+        }
+    };
+
+    let mut name = expand_name_pattern(pattern, &time, &data.fields)?;
+    if let Some(ext) = path.extension() {
+        name.push('.');
+        name.push_str(&ext.to_string_lossy());
+    }
+    Ok(name)
+}
+
+/// TIFF name of the type a value was stored as
+fn value_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Byte(_) => "BYTE",
+        Value::Ascii(_) => "ASCII",
+        Value::Short(_) => "SHORT",
+        Value::Long(_) => "LONG",
+        Value::Rational(_) => "RATIONAL",
+        Value::SByte(_) => "SBYTE",
+        Value::Undefined(..) => "UNDEFINED",
+        Value::SShort(_) => "SSHORT",
+        Value::SLong(_) => "SLONG",
+        Value::SRational(_) => "SRATIONAL",
+        Value::Float(_) => "FLOAT",
+        Value::Double(_) => "DOUBLE",
+        Value::Unknown(..) => "UNKNOWN",
+    }
+}
+
+/// The stored bytes of a byte-typed value. Numeric values are already exact
+/// when decoded, so they have none.
+fn raw_bytes(value: &Value) -> Option<Vec<u8>> {
+    match value {
+        Value::Byte(bytes) | Value::Undefined(bytes, _) => Some(bytes.clone()),
+        Value::SByte(bytes) => Some(bytes.iter().map(|&b| b as u8).collect()),
+        Value::Ascii(strings) => Some(strings.join(&0u8)),
+        _ => None,
+    }
+}
+
+/// Space-separated lowercase hex, e.g. `de ad be ef`
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn get_string_value(value: &Value) -> String {
     match value {
         Value::Ascii(ref strings) => strings
@@ -189,6 +398,254 @@ fn get_uint_value(value: &Value) -> Option<u32> {
     }
 }
 
+/// Marker bytes of the JPEG segments we need to recognize
+const JPEG_SOI: u8 = 0xD8;
+const JPEG_APP1: u8 = 0xE1;
+const JPEG_SOS: u8 = 0xDA;
+const JPEG_EOI: u8 = 0xD9;
+
+/// Identifier that starts an EXIF APP1 payload, before the TIFF header
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+/// Width and height a viewer shows for a `width` x `height` image with EXIF
+/// `orientation`: orientations 5-8 include a quarter turn, swapping the axes
+pub fn display_dimensions(width: u32, height: u32, orientation: u16) -> (u32, u32) {
+    if (5..=8).contains(&orientation) {
+        (height, width)
+    } else {
+        (width, height)
+    }
+}
+
+/// Set the EXIF Orientation tag (1-8) of a JPEG without re-encoding it.
+///
+/// The compressed image data is copied byte for byte; only the EXIF APP1
+/// segment changes. An existing Orientation entry is patched in place, other
+/// EXIF data is rebuilt with the new tag added, and a JPEG without EXIF gets
+/// a new APP1 segment holding just the orientation.
+pub fn set_orientation<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+    orientation: u16,
+) -> Result<()> {
+    let input = input.as_ref();
+    let output = output.as_ref();
+
+    if !(1..=8).contains(&orientation) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Orientation must be between 1 and 8, got {}",
+            orientation
+        )));
+    }
+
+    let jpeg = std::fs::read(input).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ImgEditError::InputNotFound(input.display().to_string())
+        } else {
+            ImgEditError::IoError(e)
+        }
+    })?;
+
+    let result = with_orientation(&jpeg, orientation, input)?;
+
+    std::fs::write(output, result).map_err(|e| ImgEditError::WriteError {
+        path: output.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Return a copy of `jpeg` with its EXIF Orientation set to `orientation`.
+/// `path` is only used in error messages.
+fn with_orientation(jpeg: &[u8], orientation: u16, path: &Path) -> Result<Vec<u8>> {
+    let segment = find_exif_segment(jpeg, path)?;
+
+    let (insert_at, resume_at, tiff) = match segment {
+        Some((start, end)) => {
+            let mut tiff = jpeg[start + 4 + EXIF_HEADER.len()..end].to_vec();
+            if !patch_orientation(&mut tiff, orientation) {
+                tiff = rebuild_exif(Some(tiff), orientation)?;
+            }
+            (start, end, tiff)
+        }
+        // No EXIF yet: place a new APP1 right after SOI
+        None => (2, 2, rebuild_exif(None, orientation)?),
+    };
+
+    let payload_len = 2 + EXIF_HEADER.len() + tiff.len();
+    if payload_len > u16::MAX as usize {
+        return Err(ImgEditError::InvalidParameter(
+            "EXIF data is too large for a JPEG APP1 segment".to_string(),
+        ));
+    }
+
+    let mut out = Vec::with_capacity(jpeg.len() + payload_len);
+    out.extend_from_slice(&jpeg[..insert_at]);
+    out.extend_from_slice(&[0xFF, JPEG_APP1]);
+    out.extend_from_slice(&(payload_len as u16).to_be_bytes());
+    out.extend_from_slice(EXIF_HEADER);
+    out.extend_from_slice(&tiff);
+    out.extend_from_slice(&jpeg[resume_at..]);
+    Ok(out)
+}
+
+/// Locate the EXIF APP1 segment of a JPEG, returning the byte range of the
+/// whole segment (marker included)
+fn find_exif_segment(jpeg: &[u8], path: &Path) -> Result<Option<(usize, usize)>> {
+    let not_jpeg =
+        || ImgEditError::UnsupportedFormat("EXIF orientation can only be set on JPEG files".into());
+
+    if jpeg.len() < 4 || jpeg[0] != 0xFF || jpeg[1] != JPEG_SOI {
+        return Err(not_jpeg());
+    }
+
+    let mut pos = 2;
+    while pos + 4 <= jpeg.len() {
+        if jpeg[pos] != 0xFF {
+            return Err(ImgEditError::ReadError {
+                path: path.display().to_string(),
+                reason: format!("Malformed JPEG segment at offset {}", pos),
+            });
+        }
+        let marker = jpeg[pos + 1];
+        if marker == JPEG_SOS || marker == JPEG_EOI {
+            break;
+        }
+        // Fill bytes before a marker
+        if marker == 0xFF {
+            pos += 1;
+            continue;
+        }
+
+        let len = u16::from_be_bytes([jpeg[pos + 2], jpeg[pos + 3]]) as usize;
+        let end = pos + 2 + len;
+        if len < 2 || end > jpeg.len() {
+            return Err(ImgEditError::ReadError {
+                path: path.display().to_string(),
+                reason: format!("Truncated JPEG segment at offset {}", pos),
+            });
+        }
+
+        if marker == JPEG_APP1 && jpeg[pos + 4..end].starts_with(EXIF_HEADER) {
+            return Ok(Some((pos, end)));
+        }
+        pos = end;
+    }
+
+    Ok(None)
+}
+
+/// Overwrite an existing single-value Orientation entry in IFD0 of a TIFF
+/// buffer. Returns false if there is no such entry to patch.
+fn patch_orientation(tiff: &mut [u8], orientation: u16) -> bool {
+    let little_endian = match tiff.get(..2) {
+        Some(b"II") => true,
+        Some(b"MM") => false,
+        _ => return false,
+    };
+    let read_u16 = |buf: &[u8], at: usize| -> Option<u16> {
+        let bytes = [*buf.get(at)?, *buf.get(at + 1)?];
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let read_u32 = |buf: &[u8], at: usize| -> Option<u32> {
+        let bytes = [
+            *buf.get(at)?,
+            *buf.get(at + 1)?,
+            *buf.get(at + 2)?,
+            *buf.get(at + 3)?,
+        ];
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    let Some(ifd0) = read_u32(tiff, 4).map(|o| o as usize) else {
+        return false;
+    };
+    let Some(count) = read_u16(tiff, ifd0) else {
+        return false;
+    };
+
+    for i in 0..count as usize {
+        let entry = ifd0 + 2 + i * 12;
+        let (Some(tag), Some(kind), Some(n)) = (
+            read_u16(tiff, entry),
+            read_u16(tiff, entry + 2),
+            read_u32(tiff, entry + 4),
+        ) else {
+            return false;
+        };
+        // Orientation, SHORT, count 1: the value sits in the entry itself
+        if tag == 0x0112 && kind == 3 && n == 1 && entry + 10 <= tiff.len() {
+            let bytes = if little_endian {
+                orientation.to_le_bytes()
+            } else {
+                orientation.to_be_bytes()
+            };
+            tiff[entry + 8..entry + 10].copy_from_slice(&bytes);
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Encode a TIFF/EXIF buffer holding the fields of `existing` (if any) with
+/// the primary Orientation replaced by `orientation`
+fn rebuild_exif(existing: Option<Vec<u8>>, orientation: u16) -> Result<Vec<u8>> {
+    use exif::experimental::Writer;
+    use std::io::Cursor;
+
+    let parsed = existing
+        .map(|tiff| Reader::new().read_raw(tiff))
+        .transpose()
+        .map_err(|e| ImgEditError::UnsupportedFormat(format!("Failed to read EXIF data: {}", e)))?;
+
+    let orientation_field = exif::Field {
+        tag: Tag::Orientation,
+        ifd_num: In::PRIMARY,
+        value: Value::Short(vec![orientation]),
+    };
+
+    let mut writer = Writer::new();
+    writer.push_field(&orientation_field);
+
+    let mut little_endian = false;
+    if let Some(ref exif) = parsed {
+        little_endian = exif.little_endian();
+        for field in exif.fields() {
+            if field.tag == Tag::Orientation && field.ifd_num == In::PRIMARY {
+                continue;
+            }
+            writer.push_field(field);
+        }
+
+        // Carry the embedded thumbnail over, if there is one
+        let thumbnail = (
+            exif.get_field(Tag::JPEGInterchangeFormat, In::THUMBNAIL),
+            exif.get_field(Tag::JPEGInterchangeFormatLength, In::THUMBNAIL),
+        );
+        if let (Some(offset), Some(length)) = thumbnail {
+            let offset = get_uint_value(&offset.value).unwrap_or(0) as usize;
+            let length = get_uint_value(&length.value).unwrap_or(0) as usize;
+            if let Some(jpeg) = exif.buf().get(offset..offset + length) {
+                writer.set_jpeg(jpeg, In::THUMBNAIL);
+            }
+        }
+    }
+
+    let mut buf = Cursor::new(Vec::new());
+    writer
+        .write(&mut buf, little_endian)
+        .map_err(|e| ImgEditError::InvalidParameter(format!("Failed to write EXIF data: {}", e)))?;
+    Ok(buf.into_inner())
+}
+
 /// Format EXIF data for human-readable text output
 pub fn format_exif_text(data: &ExifData) -> String {
     if !data.has_exif {
@@ -263,6 +720,12 @@ pub fn format_exif_verbose(data: &ExifData) -> String {
             "[{}] {}: {}{}",
             field.ifd, field.tag, field.value, desc_str
         ));
+        if let Some(ref value_type) = field.value_type {
+            lines.push(format!("    Type: {}", value_type));
+        }
+        if let Some(ref hex) = field.raw_hex {
+            lines.push(format!("    Raw: {}", hex));
+        }
     }
 
     lines.join("\n")
@@ -272,6 +735,14 @@ pub fn format_exif_verbose(data: &ExifData) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_display_dimensions() {
+        assert_eq!(display_dimensions(100, 50, 1), (100, 50));
+        assert_eq!(display_dimensions(100, 50, 3), (100, 50));
+        assert_eq!(display_dimensions(100, 50, 6), (50, 100));
+        assert_eq!(display_dimensions(100, 50, 8), (50, 100));
+    }
+
     #[test]
     fn test_exif_data_default() {
         let data = ExifData::default();
@@ -298,6 +769,8 @@ mod tests {
                 ifd: "Primary".to_string(),
                 value: "Canon".to_string(),
                 description: Some("Camera manufacturer".to_string()),
+                raw_hex: None,
+                value_type: None,
             }],
             ..Default::default()
         };
@@ -316,6 +789,8 @@ mod tests {
                 ifd: "Primary".to_string(),
                 value: "Nikon".to_string(),
                 description: Some("Camera manufacturer".to_string()),
+                raw_hex: None,
+                value_type: None,
             }],
             ..Default::default()
         };
@@ -323,6 +798,21 @@ mod tests {
         let text = format_exif_verbose(&data);
         assert!(text.contains("[Primary] Make: Nikon"));
         assert!(text.contains("Camera manufacturer"));
+        assert!(!text.contains("Raw:"));
+    }
+
+    #[test]
+    fn test_raw_bytes_and_types() {
+        let undefined = Value::Undefined(vec![0xde, 0xad, 0xbe, 0xef], 0);
+        assert_eq!(value_type_name(&undefined), "UNDEFINED");
+        assert_eq!(
+            raw_bytes(&undefined).map(|b| hex_bytes(&b)).as_deref(),
+            Some("de ad be ef")
+        );
+
+        let short = Value::Short(vec![6]);
+        assert_eq!(value_type_name(&short), "SHORT");
+        assert_eq!(raw_bytes(&short), None);
     }
 
     #[test]
@@ -338,6 +828,7 @@ mod tests {
             camera_make: Some("TestMake".to_string()),
             camera_model: Some("TestModel".to_string()),
             date_time: Some("2023:01:01 12:00:00".to_string()),
+            date_time_original: None,
             exposure_time: Some("1/100".to_string()),
             f_number: Some("f/2.8".to_string()),
             iso: Some("100".to_string()),
@@ -378,4 +869,171 @@ mod tests {
         assert!(text.contains("Latitude: 51.5074"));
         assert!(text.contains("Longitude: -0.1278"));
     }
+
+    #[test]
+    fn test_expand_name_pattern() {
+        let time = Timestamp::from_exif("2023:07:04 09:08:07").unwrap();
+        assert_eq!(
+            expand_name_pattern("{year}{month}{day}_{hour}{min}{sec}", &time, &[]).unwrap(),
+            "20230704_090807"
+        );
+        assert_eq!(
+            expand_name_pattern("IMG-{year}-{bogus}-{", &time, &[]).unwrap(),
+            "IMG-2023-{bogus}-{"
+        );
+    }
+
+    #[test]
+    fn test_expand_name_pattern_date_and_exif_tokens() {
+        let time = Timestamp::from_exif("2023:07:04 09:08:07").unwrap();
+        let model = ExifField {
+            tag: "Model".to_string(),
+            ifd: "Primary".to_string(),
+            value: "\"EOS R/5\"".to_string(),
+            description: None,
+            raw_hex: None,
+            value_type: None,
+        };
+        assert_eq!(
+            expand_name_pattern("{date:%Y%m%d}_{exif:model}", &time, &[model]).unwrap(),
+            "20230704_EOS R_5"
+        );
+        assert_eq!(
+            expand_name_pattern("{date:%H-%M}_{exif:Model}", &time, &[]).unwrap(),
+            "09-08_unknown"
+        );
+    }
+
+    #[test]
+    fn test_expand_name_pattern_date_is_filename_safe() {
+        let time = Timestamp::from_exif("2023:07:04 09:08:07").unwrap();
+        // Separators from the date become `_`; the pattern's own `/` stays
+        assert_eq!(
+            expand_name_pattern("{year}/{date:%m/%d %H:%M}", &time, &[]).unwrap(),
+            "2023/07_04 09_08"
+        );
+        assert_eq!(
+            expand_name_pattern("{date:100%%}", &time, &[]).unwrap(),
+            "100%"
+        );
+
+        for format in ["{date:%Q}", "{date:%Y%}"] {
+            let err = expand_name_pattern(format, &time, &[]).unwrap_err();
+            assert!(
+                matches!(err, ImgEditError::InvalidParameter(_)),
+                "{}: {:?}",
+                format,
+                err
+            );
+        }
+        assert!(expand_name_pattern("{date:%Q}", &time, &[])
+            .unwrap_err()
+            .to_string()
+            .contains("'%Q'"));
+    }
+
+    #[test]
+    fn test_suggest_name_without_exif_fails() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("plain.png");
+        image::RgbImage::new(2, 2).save(&path).unwrap();
+
+        let err = suggest_name(&path, "{year}", true).unwrap_err();
+        assert!(err.to_string().contains("DateTimeOriginal"));
+    }
+
+    #[test]
+    fn test_suggest_name_falls_back_to_mtime() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("plain.png");
+        image::RgbImage::new(2, 2).save(&path).unwrap();
+        // 2021-03-04 05:06:07 UTC
+        let mtime = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_614_834_367);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(mtime)
+            .unwrap();
+
+        assert_eq!(
+            suggest_name(&path, "{date:%Y%m%d_%H%M%S}", false).unwrap(),
+            "20210304_050607.png"
+        );
+    }
+
+    fn encode_jpeg() -> Vec<u8> {
+        let img =
+            image::RgbImage::from_fn(16, 8, |x, y| image::Rgb([x as u8 * 16, y as u8 * 32, 0]));
+        let mut jpeg = Vec::new();
+        img.write_to(
+            &mut std::io::Cursor::new(&mut jpeg),
+            image::ImageFormat::Jpeg,
+        )
+        .unwrap();
+        jpeg
+    }
+
+    fn read_orientation(jpeg: &[u8]) -> Option<u32> {
+        let exif = Reader::new()
+            .read_from_container(&mut std::io::Cursor::new(jpeg))
+            .unwrap();
+        exif.get_field(Tag::Orientation, In::PRIMARY)
+            .and_then(|f| get_uint_value(&f.value))
+    }
+
+    #[test]
+    fn test_with_orientation_adds_exif() {
+        let jpeg = encode_jpeg();
+        let result = with_orientation(&jpeg, 6, Path::new("test.jpg")).unwrap();
+
+        assert_eq!(read_orientation(&result), Some(6));
+        // Everything after SOI is carried over untouched
+        assert!(result.ends_with(&jpeg[2..]));
+    }
+
+    #[test]
+    fn test_with_orientation_patches_existing() {
+        let once = with_orientation(&encode_jpeg(), 3, Path::new("test.jpg")).unwrap();
+        let twice = with_orientation(&once, 8, Path::new("test.jpg")).unwrap();
+
+        assert_eq!(read_orientation(&twice), Some(8));
+        // Patched in place: same size, single APP1 segment
+        assert_eq!(once.len(), twice.len());
+    }
+
+    #[test]
+    fn test_with_orientation_keeps_other_fields() {
+        let date = exif::Field {
+            tag: Tag::DateTime,
+            ifd_num: In::PRIMARY,
+            value: Value::Ascii(vec![b"2020:01:01 00:00:00".to_vec()]),
+        };
+        let mut writer = exif::experimental::Writer::new();
+        writer.push_field(&date);
+        let mut tiff = std::io::Cursor::new(Vec::new());
+        writer.write(&mut tiff, true).unwrap();
+
+        let jpeg = encode_jpeg();
+        let mut with_date = jpeg[..2].to_vec();
+        with_date.extend_from_slice(&[0xFF, JPEG_APP1]);
+        with_date.extend_from_slice(&((tiff.get_ref().len() + 8) as u16).to_be_bytes());
+        with_date.extend_from_slice(EXIF_HEADER);
+        with_date.extend_from_slice(tiff.get_ref());
+        with_date.extend_from_slice(&jpeg[2..]);
+
+        let result = with_orientation(&with_date, 6, Path::new("test.jpg")).unwrap();
+        assert_eq!(read_orientation(&result), Some(6));
+
+        let exif = Reader::new()
+            .read_from_container(&mut std::io::Cursor::new(&result))
+            .unwrap();
+        let dt = exif.get_field(Tag::DateTime, In::PRIMARY).unwrap();
+        assert_eq!(get_string_value(&dt.value), "2020:01:01 00:00:00");
+    }
+
+    #[test]
+    fn test_with_orientation_rejects_non_jpeg() {
+        assert!(with_orientation(b"\x89PNG\r\n\x1a\n", 6, Path::new("test.png")).is_err());
+    }
 }
@@ -75,6 +75,78 @@ fn sharpen_channel(original: u8, blurred: u8, amount: f32) -> u8 {
     result.round().clamp(0.0, 255.0) as u8
 }
 
+/// Apply an edge-preserving bilateral filter to an image
+/// spatial_sigma: spread of the neighbourhood in pixels (0.1 to 20.0)
+/// range_sigma: how different a neighbour's color may be and still be
+/// averaged in, in 0-255 channel units (0.1 to 255.0)
+///
+/// Each output pixel is a weighted mean of the window around it, weighting
+/// neighbours by both distance and color similarity, so flat regions are
+/// smoothed while edges between distinct colors stay sharp. Alpha is kept.
+pub fn bilateral(img: &DynamicImage, spatial_sigma: f32, range_sigma: f32) -> Result<DynamicImage> {
+    if !(0.1..=20.0).contains(&spatial_sigma) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Spatial sigma must be between 0.1 and 20.0, got {}",
+            spatial_sigma
+        )));
+    }
+    if !(0.1..=255.0).contains(&range_sigma) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Range sigma must be between 0.1 and 255.0, got {}",
+            range_sigma
+        )));
+    }
+
+    let rgba = img.to_rgba8();
+    let (width, height) = (rgba.width() as i64, rgba.height() as i64);
+
+    // The window covers three standard deviations each way
+    let radius = (3.0 * spatial_sigma).ceil() as i64;
+    let size = (2 * radius + 1) as usize;
+    let spatial: Vec<f32> = (0..size * size)
+        .map(|i| {
+            let dx = (i % size) as i64 - radius;
+            let dy = (i / size) as i64 - radius;
+            (-((dx * dx + dy * dy) as f32) / (2.0 * spatial_sigma * spatial_sigma)).exp()
+        })
+        .collect();
+    // The color weight factors per channel, so one table covers any difference
+    let range: Vec<f32> = (0..256)
+        .map(|d| (-((d * d) as f32) / (2.0 * range_sigma * range_sigma)).exp())
+        .collect();
+
+    let filtered = image::ImageBuffer::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let center = rgba.get_pixel(x, y);
+        let (x, y) = (x as i64, y as i64);
+        let mut sum = [0.0f32; 3];
+        let mut total = 0.0f32;
+
+        for ny in (y - radius).max(0)..=(y + radius).min(height - 1) {
+            for nx in (x - radius).max(0)..=(x + radius).min(width - 1) {
+                let neighbour = rgba.get_pixel(nx as u32, ny as u32);
+                let offset = ((ny - y + radius) as usize) * size + (nx - x + radius) as usize;
+                let weight = (0..3).fold(spatial[offset], |w, c| {
+                    w * range[center[c].abs_diff(neighbour[c]) as usize]
+                });
+                for (acc, &value) in sum.iter_mut().zip(neighbour.0.iter()) {
+                    *acc += weight * value as f32;
+                }
+                total += weight;
+            }
+        }
+
+        // The center pixel always has weight 1, so total is never zero
+        image::Rgba([
+            (sum[0] / total).round() as u8,
+            (sum[1] / total).round() as u8,
+            (sum[2] / total).round() as u8,
+            center[3],
+        ])
+    });
+
+    Ok(DynamicImage::ImageRgba8(filtered))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -197,4 +269,47 @@ mod tests {
         // The difference should be at least as large as the original
         assert!(pixel_15 > pixel_5 || pixel_15 == 255);
     }
+
+    #[test]
+    fn test_bilateral_keeps_edge_and_smooths_noise() {
+        // A dark and a light half, each with deterministic +-12 noise
+        let img = ImageBuffer::from_fn(40, 20, |x, y| {
+            let base: i32 = if x < 20 { 60 } else { 190 };
+            let noise = ((x * 7 + y * 13) % 5) as i32 * 6 - 12;
+            let val = (base + noise) as u8;
+            Rgba([val, val, val, 255])
+        });
+        let img = DynamicImage::ImageRgba8(img);
+        let result = bilateral(&img, 2.0, 40.0).unwrap().to_rgba8();
+
+        // The edge stays a single-pixel step
+        for y in 0..20 {
+            assert!(result.get_pixel(19, y)[0] < 80, "dark side bled at y={}", y);
+            assert!(
+                result.get_pixel(20, y)[0] > 170,
+                "light side bled at y={}",
+                y
+            );
+        }
+
+        let variance = |img: &image::RgbaImage| {
+            let values: Vec<f32> = (2..16)
+                .flat_map(|x| (2..18).map(move |y| (x, y)))
+                .map(|(x, y)| img.get_pixel(x, y)[0] as f32)
+                .collect();
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / values.len() as f32
+        };
+        assert!(variance(&result) < variance(&img.to_rgba8()) / 4.0);
+    }
+
+    #[test]
+    fn test_bilateral_preserves_alpha_and_validates() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_pixel(6, 6, Rgba([10, 200, 30, 77])));
+        let result = bilateral(&img, 1.0, 20.0).unwrap().to_rgba8();
+        assert!(result.pixels().all(|p| *p == Rgba([10, 200, 30, 77])));
+
+        assert!(bilateral(&img, 0.0, 20.0).is_err());
+        assert!(bilateral(&img, 1.0, 300.0).is_err());
+    }
 }
@@ -0,0 +1,104 @@
+use crate::error::{ImgEditError, Result};
+use crate::ops::canvas::is_keyed;
+use image::DynamicImage;
+
+/// Make the region connected to (x, y) transparent ("magic wand" removal).
+///
+/// Starting from the seed, every 4-connected pixel within `tolerance` of the
+/// seed color (largest per-channel RGB difference, as in chroma keying) is
+/// cleared. Matching pixels that are not connected to the seed are kept.
+pub fn flood_remove(img: &DynamicImage, x: u32, y: u32, tolerance: u8) -> Result<DynamicImage> {
+    let mut rgba = img.to_rgba8();
+    let (width, height) = rgba.dimensions();
+
+    if x >= width || y >= height {
+        return Err(ImgEditError::CropOutOfBounds(format!(
+            "Seed ({}, {}) is outside image bounds {}x{}",
+            x, y, width, height
+        )));
+    }
+
+    let seed = *rgba.get_pixel(x, y);
+    let mut visited = vec![false; (width * height) as usize];
+    let mut stack = vec![(x, y)];
+    visited[(y * width + x) as usize] = true;
+
+    while let Some((px, py)) = stack.pop() {
+        rgba.get_pixel_mut(px, py).0 = [0, 0, 0, 0];
+
+        let neighbors = [
+            (px.wrapping_sub(1), py),
+            (px + 1, py),
+            (px, py.wrapping_sub(1)),
+            (px, py + 1),
+        ];
+        for (nx, ny) in neighbors {
+            if nx >= width || ny >= height {
+                continue;
+            }
+            let index = (ny * width + nx) as usize;
+            if !visited[index] && is_keyed(rgba.get_pixel(nx, ny), seed, tolerance) {
+                visited[index] = true;
+                stack.push((nx, ny));
+            }
+        }
+    }
+
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgba, RgbaImage};
+
+    /// White 20x20 image with a black ring at 5..15 enclosing a white interior
+    fn create_ring() -> DynamicImage {
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(20, 20, |x, y| {
+            let on_ring = (5..15).contains(&x)
+                && (5..15).contains(&y)
+                && (x == 5 || x == 14 || y == 5 || y == 14);
+            if on_ring {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_flood_remove_keeps_disconnected_interior() {
+        let result = flood_remove(&create_ring(), 0, 0, 10).unwrap().to_rgba8();
+
+        // The outer white border is cleared
+        assert_eq!(result.get_pixel(0, 0)[3], 0);
+        assert_eq!(result.get_pixel(19, 19)[3], 0);
+        assert_eq!(result.get_pixel(4, 10)[3], 0);
+        // The ring and the enclosed white area survive
+        assert_eq!(result.get_pixel(5, 10), &Rgba([0, 0, 0, 255]));
+        assert_eq!(result.get_pixel(10, 10), &Rgba([255, 255, 255, 255]));
+    }
+
+    #[test]
+    fn test_flood_remove_tolerance() {
+        // Left half pure white, right half off-white
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_fn(10, 1, |x, _| {
+            if x < 5 {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([240, 240, 240, 255])
+            }
+        }));
+
+        let strict = flood_remove(&img, 0, 0, 0).unwrap().to_rgba8();
+        assert_eq!(strict.get_pixel(9, 0)[3], 255);
+
+        let loose = flood_remove(&img, 0, 0, 20).unwrap().to_rgba8();
+        assert_eq!(loose.get_pixel(9, 0)[3], 0);
+    }
+
+    #[test]
+    fn test_flood_remove_seed_out_of_bounds() {
+        assert!(flood_remove(&create_ring(), 20, 0, 0).is_err());
+    }
+}
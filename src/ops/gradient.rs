@@ -0,0 +1,194 @@
+use crate::cli::args::BlendMode;
+use crate::color::parse_color;
+use crate::error::{ImgEditError, Result};
+use crate::ops::canvas::blend_pixels;
+use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
+
+/// A `width`x`height` linear gradient from `start` to `end` along `angle`
+/// degrees: 0 runs left to right, 90 top to bottom. The first and last pixels
+/// along that direction take the end colors exactly; channels, including
+/// alpha, are interpolated linearly.
+pub fn linear_gradient(
+    width: u32,
+    height: u32,
+    start: Rgba<u8>,
+    end: Rgba<u8>,
+    angle: f64,
+) -> RgbaImage {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let project = |x: f64, y: f64| x * cos + y * sin;
+
+    // The extreme projections are at the corner pixel centers
+    let (max_x, max_y) = (
+        width.saturating_sub(1) as f64,
+        height.saturating_sub(1) as f64,
+    );
+    let corners = [(0.0, 0.0), (max_x, 0.0), (0.0, max_y), (max_x, max_y)];
+    let low = corners
+        .iter()
+        .map(|&(x, y)| project(x, y))
+        .fold(f64::INFINITY, f64::min);
+    let high = corners
+        .iter()
+        .map(|&(x, y)| project(x, y))
+        .fold(f64::NEG_INFINITY, f64::max);
+    let span = high - low;
+
+    ImageBuffer::from_fn(width, height, |x, y| {
+        let t = if span > 1e-9 {
+            (project(x as f64, y as f64) - low) / span
+        } else {
+            0.0
+        };
+        Rgba(std::array::from_fn(|c| {
+            (start[c] as f64 + (end[c] as f64 - start[c] as f64) * t).round() as u8
+        }))
+    })
+}
+
+/// Composite a linear gradient (see [`linear_gradient`]) covering the whole
+/// image over it with `opacity` (0.0 to 1.0) and `blend_mode`
+pub fn gradient_overlay(
+    img: &DynamicImage,
+    start: Rgba<u8>,
+    end: Rgba<u8>,
+    angle: f64,
+    opacity: f32,
+    blend_mode: BlendMode,
+) -> Result<DynamicImage> {
+    if !(0.0..=1.0).contains(&opacity) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Opacity must be between 0.0 and 1.0, got {}",
+            opacity
+        )));
+    }
+
+    let mut rgba = img.to_rgba8();
+    let gradient = linear_gradient(rgba.width(), rgba.height(), start, end, angle);
+    for (pixel, overlay) in rgba.pixels_mut().zip(gradient.pixels()) {
+        *pixel = blend_pixels(*pixel, *overlay, opacity, blend_mode);
+    }
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// Parse a `start,end,angle` gradient spec such as `red,#0000FF,90`. Colors
+/// may contain commas themselves (`rgb(255,0,0)`), so the split between them
+/// is the first comma where both halves are valid colors.
+pub fn parse_gradient_spec(spec: &str) -> Result<(Rgba<u8>, Rgba<u8>, f64)> {
+    let invalid = || {
+        ImgEditError::InvalidParameter(format!(
+            "Invalid gradient '{}': expected start,end,angle",
+            spec
+        ))
+    };
+
+    let (colors, angle) = spec.rsplit_once(',').ok_or_else(invalid)?;
+    let angle: f64 = angle.trim().parse().map_err(|_| invalid())?;
+    if !angle.is_finite() {
+        return Err(invalid());
+    }
+    colors
+        .match_indices(',')
+        .find_map(|(i, _)| {
+            let start = parse_color(&colors[..i]).ok()?;
+            let end = parse_color(&colors[i + 1..]).ok()?;
+            Some((start, end, angle))
+        })
+        .ok_or_else(invalid)
+}
+
+/// A new `width`x`height` image filled with `color`, or with a linear
+/// `(start, end, angle)` gradient when one is given
+pub fn generate(
+    width: u32,
+    height: u32,
+    color: Rgba<u8>,
+    gradient: Option<(Rgba<u8>, Rgba<u8>, f64)>,
+) -> Result<DynamicImage> {
+    if width == 0 || height == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Width and height must be greater than 0".to_string(),
+        ));
+    }
+
+    let image = match gradient {
+        Some((start, end, angle)) => linear_gradient(width, height, start, end, angle),
+        None => RgbaImage::from_pixel(width, height, color),
+    };
+    Ok(DynamicImage::ImageRgba8(image))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+    const CLEAR: Rgba<u8> = Rgba([0, 0, 0, 0]);
+
+    #[test]
+    fn test_linear_gradient_directions() {
+        let horizontal = linear_gradient(5, 3, BLACK, Rgba([200, 100, 0, 255]), 0.0);
+        assert_eq!(horizontal.get_pixel(0, 1), &BLACK);
+        assert_eq!(horizontal.get_pixel(2, 2), &Rgba([100, 50, 0, 255]));
+        assert_eq!(horizontal.get_pixel(4, 0), &Rgba([200, 100, 0, 255]));
+
+        // 270 runs bottom to top
+        let upward = linear_gradient(3, 5, BLACK, CLEAR, 270.0);
+        assert_eq!(upward.get_pixel(1, 4), &BLACK);
+        assert_eq!(upward.get_pixel(1, 0), &CLEAR);
+
+        // A single pixel takes the start color
+        assert_eq!(
+            linear_gradient(1, 1, BLACK, CLEAR, 45.0).get_pixel(0, 0),
+            &BLACK
+        );
+    }
+
+    #[test]
+    fn test_gradient_overlay_darkens_top_only() {
+        let img =
+            DynamicImage::ImageRgba8(RgbaImage::from_pixel(8, 20, Rgba([200, 180, 160, 255])));
+        let result = gradient_overlay(&img, BLACK, CLEAR, 90.0, 1.0, BlendMode::Normal)
+            .unwrap()
+            .to_rgba8();
+
+        assert_eq!(result.get_pixel(3, 0), &BLACK);
+        assert!(result.get_pixel(3, 4)[0] < 200);
+        let bottom = result.get_pixel(3, 19);
+        assert!(bottom[0].abs_diff(200) <= 1, "{:?}", bottom);
+        assert_eq!(bottom[3], 255);
+
+        assert!(gradient_overlay(&img, BLACK, CLEAR, 90.0, 1.5, BlendMode::Normal).is_err());
+    }
+
+    #[test]
+    fn test_parse_gradient_spec() {
+        assert_eq!(
+            parse_gradient_spec("black,transparent,90").unwrap(),
+            (BLACK, CLEAR, 90.0)
+        );
+        assert_eq!(
+            parse_gradient_spec("rgb(255,0,0), rgba(0,0,0,0), -45").unwrap(),
+            (Rgba([255, 0, 0, 255]), CLEAR, -45.0)
+        );
+        assert!(parse_gradient_spec("black,white").is_err());
+        assert!(parse_gradient_spec("black,90").is_err());
+        assert!(parse_gradient_spec("black,white,up").is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        let red = Rgba([255, 0, 0, 255]);
+        let solid = generate(100, 50, red, None).unwrap().to_rgba8();
+        assert_eq!(solid.dimensions(), (100, 50));
+        assert!(solid.pixels().all(|p| *p == red));
+
+        let gradient = generate(10, 4, red, Some((BLACK, CLEAR, 0.0)))
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(gradient.get_pixel(0, 2), &BLACK);
+        assert_eq!(gradient.get_pixel(9, 2), &CLEAR);
+
+        assert!(generate(0, 10, red, None).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+use crate::cli::args::ColorProfile;
+
+/// D65 white point chromaticity
+const D65: (f64, f64) = (0.3127, 0.3290);
+
+/// ICC profile connection space illuminant (D50) in XYZ
+const D50_XYZ: [f64; 3] = [0.9642, 1.0, 0.8249];
+
+/// Bradford cone response matrix used for chromatic adaptation
+const BRADFORD: [[f64; 3]; 3] = [
+    [0.8951, 0.2664, -0.1614],
+    [-0.7502, 1.7135, 0.0367],
+    [0.0389, -0.0685, 1.0296],
+];
+
+const HEADER_SIZE: usize = 128;
+
+type Matrix = [[f64; 3]; 3];
+
+/// Definition of an RGB working space: primaries, white point and tone curve
+struct SpaceDef {
+    description: &'static str,
+    /// Red, green and blue primary chromaticities (x, y)
+    primaries: [(f64, f64); 3],
+    /// Parametric curve: function type and its parameters (ICC `para` tag)
+    curve: (u16, &'static [f64]),
+}
+
+fn space_def(profile: ColorProfile) -> SpaceDef {
+    // sRGB piecewise curve: Y = (aX + b)^g for X >= d, else cX
+    const SRGB_CURVE: &[f64] = &[2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045];
+
+    match profile {
+        ColorProfile::Srgb => SpaceDef {
+            description: "sRGB",
+            primaries: [(0.64, 0.33), (0.30, 0.60), (0.15, 0.06)],
+            curve: (3, SRGB_CURVE),
+        },
+        ColorProfile::DisplayP3 => SpaceDef {
+            description: "Display P3",
+            primaries: [(0.680, 0.320), (0.265, 0.690), (0.150, 0.060)],
+            curve: (3, SRGB_CURVE),
+        },
+        ColorProfile::AdobeRgb => SpaceDef {
+            description: "Adobe RGB (1998)",
+            primaries: [(0.64, 0.33), (0.21, 0.71), (0.15, 0.06)],
+            curve: (0, &[563.0 / 256.0]),
+        },
+    }
+}
+
+/// Human-readable name embedded as the profile description
+pub fn profile_name(profile: ColorProfile) -> &'static str {
+    space_def(profile).description
+}
+
+/// Build an ICC v4 display profile for a standard RGB space.
+///
+/// The profile is a matrix/TRC profile generated from the space's published
+/// primaries and D65 white point, adapted to the D50 connection space with the
+/// Bradford transform. Output is deterministic (fixed creation date, no ID).
+pub fn icc_profile(profile: ColorProfile) -> Vec<u8> {
+    let def = space_def(profile);
+    let chad = adaptation_matrix(xy_to_xyz(D65), D50_XYZ);
+    let colorants = multiply(&chad, &rgb_to_xyz(def.primaries, D65));
+
+    let column = |i: usize| [colorants[0][i], colorants[1][i], colorants[2][i]];
+    let curve = para_tag(def.curve.0, def.curve.1);
+
+    // Tag data, in table order; identical data (the three curves) is shared
+    let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"desc", mluc_tag(def.description)),
+        (b"cprt", mluc_tag("No copyright, use freely")),
+        (b"wtpt", xyz_tag(D50_XYZ)),
+        (b"chad", sf32_tag(&chad)),
+        (b"rXYZ", xyz_tag(column(0))),
+        (b"gXYZ", xyz_tag(column(1))),
+        (b"bXYZ", xyz_tag(column(2))),
+        (b"rTRC", curve.clone()),
+        (b"gTRC", curve.clone()),
+        (b"bTRC", curve),
+    ];
+
+    let table_size = 4 + 12 * tags.len();
+    let mut data = Vec::new();
+    let mut table = Vec::with_capacity(table_size);
+    table.extend_from_slice(&(tags.len() as u32).to_be_bytes());
+
+    let mut written: Vec<(Vec<u8>, u32)> = Vec::new();
+    for (signature, bytes) in &tags {
+        let offset = match written.iter().find(|(b, _)| b == bytes) {
+            Some((_, offset)) => *offset,
+            None => {
+                let offset = (HEADER_SIZE + table_size + data.len()) as u32;
+                data.extend_from_slice(bytes);
+                // Every tag starts on a 4-byte boundary
+                while data.len() % 4 != 0 {
+                    data.push(0);
+                }
+                written.push((bytes.clone(), offset));
+                offset
+            }
+        };
+        table.extend_from_slice(*signature);
+        table.extend_from_slice(&offset.to_be_bytes());
+        table.extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+    }
+
+    let size = HEADER_SIZE + table.len() + data.len();
+    let mut out = Vec::with_capacity(size);
+    out.extend_from_slice(&(size as u32).to_be_bytes());
+    out.extend_from_slice(&[0; 4]); // preferred CMM
+    out.extend_from_slice(&0x0430_0000u32.to_be_bytes()); // version 4.3
+    out.extend_from_slice(b"mntr");
+    out.extend_from_slice(b"RGB ");
+    out.extend_from_slice(b"XYZ ");
+    for field in [2024u16, 1, 1, 0, 0, 0] {
+        out.extend_from_slice(&field.to_be_bytes());
+    }
+    out.extend_from_slice(b"acsp");
+    out.extend_from_slice(&[0; 24]); // platform, flags, manufacturer, model, attributes
+    out.extend_from_slice(&0u32.to_be_bytes()); // perceptual intent
+    for v in D50_XYZ {
+        out.extend_from_slice(&s15_fixed16(v));
+    }
+    out.extend_from_slice(&[0; 4]); // creator
+    out.extend_from_slice(&[0; 16]); // profile ID (not computed)
+    out.resize(HEADER_SIZE, 0);
+
+    out.extend_from_slice(&table);
+    out.extend_from_slice(&data);
+    out
+}
+
+/// Read the profile description (`desc` tag) from ICC profile bytes.
+/// Supports both v4 `mluc` and v2 `desc` text types.
+pub fn icc_description(icc: &[u8]) -> Option<String> {
+    let be32 = |at: usize| -> Option<usize> {
+        Some(u32::from_be_bytes(icc.get(at..at + 4)?.try_into().ok()?) as usize)
+    };
+
+    let count = be32(HEADER_SIZE)?;
+    let (offset, size) = (0..count).find_map(|i| {
+        let entry = HEADER_SIZE + 4 + 12 * i;
+        (icc.get(entry..entry + 4)? == b"desc").then_some(())?;
+        Some((be32(entry + 4)?, be32(entry + 8)?))
+    })?;
+    let tag = icc.get(offset..offset.checked_add(size)?)?;
+
+    match tag.get(0..4)? {
+        b"mluc" => {
+            // First record: language, country, length, offset (from tag start)
+            let len = u32::from_be_bytes(tag.get(20..24)?.try_into().ok()?) as usize;
+            let start = u32::from_be_bytes(tag.get(24..28)?.try_into().ok()?) as usize;
+            let text = tag.get(start..start.checked_add(len)?)?;
+            let units: Vec<u16> = text
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            String::from_utf16(&units).ok()
+        }
+        b"desc" => {
+            let len = u32::from_be_bytes(tag.get(8..12)?.try_into().ok()?) as usize;
+            let text = tag.get(12..12 + len)?;
+            let text = text.split(|&b| b == 0).next()?;
+            String::from_utf8(text.to_vec()).ok()
+        }
+        _ => None,
+    }
+}
+
+fn s15_fixed16(v: f64) -> [u8; 4] {
+    ((v * 65536.0).round() as i32).to_be_bytes()
+}
+
+fn xyz_tag(xyz: [f64; 3]) -> Vec<u8> {
+    let mut tag = b"XYZ \0\0\0\0".to_vec();
+    for v in xyz {
+        tag.extend_from_slice(&s15_fixed16(v));
+    }
+    tag
+}
+
+fn sf32_tag(m: &Matrix) -> Vec<u8> {
+    let mut tag = b"sf32\0\0\0\0".to_vec();
+    for v in m.iter().flatten() {
+        tag.extend_from_slice(&s15_fixed16(*v));
+    }
+    tag
+}
+
+fn para_tag(function: u16, params: &[f64]) -> Vec<u8> {
+    let mut tag = b"para\0\0\0\0".to_vec();
+    tag.extend_from_slice(&function.to_be_bytes());
+    tag.extend_from_slice(&[0; 2]);
+    for &v in params {
+        tag.extend_from_slice(&s15_fixed16(v));
+    }
+    tag
+}
+
+/// Multi-localized Unicode text with a single en-US record
+fn mluc_tag(text: &str) -> Vec<u8> {
+    let utf16: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+    let mut tag = b"mluc\0\0\0\0".to_vec();
+    tag.extend_from_slice(&1u32.to_be_bytes()); // record count
+    tag.extend_from_slice(&12u32.to_be_bytes()); // record size
+    tag.extend_from_slice(b"enUS");
+    tag.extend_from_slice(&(utf16.len() as u32).to_be_bytes());
+    tag.extend_from_slice(&28u32.to_be_bytes());
+    tag.extend_from_slice(&utf16);
+    tag
+}
+
+fn xy_to_xyz((x, y): (f64, f64)) -> [f64; 3] {
+    [x / y, 1.0, (1.0 - x - y) / y]
+}
+
+/// RGB to XYZ matrix for the given primaries, normalized so RGB white maps to `white`
+fn rgb_to_xyz(primaries: [(f64, f64); 3], white: (f64, f64)) -> Matrix {
+    let [r, g, b] = primaries.map(xy_to_xyz);
+    let m = [[r[0], g[0], b[0]], [r[1], g[1], b[1]], [r[2], g[2], b[2]]];
+    let s = apply(&invert(&m), xy_to_xyz(white));
+    let mut out = m;
+    for row in &mut out {
+        for (v, scale) in row.iter_mut().zip(s) {
+            *v *= scale;
+        }
+    }
+    out
+}
+
+/// Bradford chromatic adaptation from `src` to `dst` white (both XYZ)
+fn adaptation_matrix(src: [f64; 3], dst: [f64; 3]) -> Matrix {
+    let (src_cone, dst_cone) = (apply(&BRADFORD, src), apply(&BRADFORD, dst));
+    let mut scale = [[0.0; 3]; 3];
+    for i in 0..3 {
+        scale[i][i] = dst_cone[i] / src_cone[i];
+    }
+    multiply(&invert(&BRADFORD), &multiply(&scale, &BRADFORD))
+}
+
+fn apply(m: &Matrix, v: [f64; 3]) -> [f64; 3] {
+    std::array::from_fn(|i| m[i][0] * v[0] + m[i][1] * v[1] + m[i][2] * v[2])
+}
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    std::array::from_fn(|i| std::array::from_fn(|j| (0..3).map(|k| a[i][k] * b[k][j]).sum()))
+}
+
+fn invert(m: &Matrix) -> Matrix {
+    let cofactor = |r: usize, c: usize| {
+        let (r1, r2) = ((r + 1) % 3, (r + 2) % 3);
+        let (c1, c2) = ((c + 1) % 3, (c + 2) % 3);
+        m[r1][c1] * m[r2][c2] - m[r1][c2] * m[r2][c1]
+    };
+    let det: f64 = (0..3).map(|c| m[0][c] * cofactor(0, c)).sum();
+    // Transposed cofactors over the determinant
+    std::array::from_fn(|i| std::array::from_fn(|j| cofactor(j, i) / det))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_xyz(icc: &[u8], signature: &[u8; 4]) -> [f64; 3] {
+        let count = u32::from_be_bytes(icc[128..132].try_into().unwrap()) as usize;
+        let entry = (0..count)
+            .map(|i| 132 + 12 * i)
+            .find(|&e| &icc[e..e + 4] == signature)
+            .unwrap();
+        let offset = u32::from_be_bytes(icc[entry + 4..entry + 8].try_into().unwrap()) as usize;
+        std::array::from_fn(|i| {
+            let at = offset + 8 + 4 * i;
+            i32::from_be_bytes(icc[at..at + 4].try_into().unwrap()) as f64 / 65536.0
+        })
+    }
+
+    #[test]
+    fn test_icc_profile_header() {
+        for profile in [
+            ColorProfile::Srgb,
+            ColorProfile::DisplayP3,
+            ColorProfile::AdobeRgb,
+        ] {
+            let icc = icc_profile(profile);
+            let size = u32::from_be_bytes(icc[0..4].try_into().unwrap()) as usize;
+            assert_eq!(size, icc.len());
+            assert_eq!(&icc[36..40], b"acsp");
+            assert_eq!(&icc[12..20], b"mntrRGB ");
+        }
+    }
+
+    #[test]
+    fn test_icc_description_round_trip() {
+        assert_eq!(
+            icc_description(&icc_profile(ColorProfile::DisplayP3)).as_deref(),
+            Some("Display P3")
+        );
+        assert_eq!(
+            icc_description(&icc_profile(ColorProfile::AdobeRgb)).as_deref(),
+            Some("Adobe RGB (1998)")
+        );
+        assert_eq!(icc_description(b"not a profile"), None);
+    }
+
+    #[test]
+    fn test_srgb_colorants_match_published_values() {
+        let icc = icc_profile(ColorProfile::Srgb);
+        // D50-adapted sRGB red colorant as published in the sRGB ICC profiles
+        let red = read_xyz(&icc, b"rXYZ");
+        for (got, want) in red.iter().zip([0.4361, 0.2225, 0.0139]) {
+            assert!((got - want).abs() < 0.001, "{} vs {}", got, want);
+        }
+
+        // Colorants sum to the D50 white point
+        let (g, b) = (read_xyz(&icc, b"gXYZ"), read_xyz(&icc, b"bXYZ"));
+        for i in 0..3 {
+            assert!((red[i] + g[i] + b[i] - D50_XYZ[i]).abs() < 0.001);
+        }
+    }
+}
@@ -1,6 +1,7 @@
+use crate::color::color_to_hex;
 use crate::error::{ImgEditError, Result};
 use image::ImageReader;
-use image::{ColorType, DynamicImage};
+use image::{ColorType, DynamicImage, GenericImageView};
 use serde::Serialize;
 use std::fs;
 use std::path::Path;
@@ -8,36 +9,104 @@ use std::path::Path;
 #[derive(Debug, Serialize)]
 pub struct ImageInfo {
     pub file: String,
+    /// The format sniffed from the file's content
     pub format: String,
+    /// Whether the content is a different format than the extension names
+    pub format_mismatch: bool,
     pub width: u32,
     pub height: u32,
     pub color_type: String,
     pub bit_depth: u8,
+    /// Whether the decoded color type carries an alpha channel
+    pub has_alpha: bool,
     pub file_size_bytes: u64,
+    /// Horizontal resolution in dots per inch, if the file records one
+    pub dpi_x: Option<f64>,
+    /// Vertical resolution in dots per inch, if the file records one
+    pub dpi_y: Option<f64>,
 }
 
 impl ImageInfo {
     pub fn display(&self) -> String {
         let size_display = format_file_size(self.file_size_bytes);
+        let resolution = match (self.dpi_x, self.dpi_y) {
+            (Some(x), Some(y)) => format!("{}x{} DPI", x, y),
+            _ => "not set".to_string(),
+        };
+        let format = if self.format_mismatch {
+            format!("{} (does not match the file extension)", self.format)
+        } else {
+            self.format.clone()
+        };
         format!(
             "File: {}\n\
              Format: {}\n\
              Dimensions: {}x{}\n\
              Color Type: {}\n\
              Bit Depth: {}\n\
+             Resolution: {}\n\
              File Size: {}",
             self.file,
-            self.format,
+            format,
             self.width,
             self.height,
             self.color_type,
             self.bit_depth,
+            resolution,
             size_display
         )
     }
 }
 
-fn format_file_size(bytes: u64) -> String {
+/// The value of one pixel, converted to 8-bit RGBA
+#[derive(Debug, Serialize)]
+pub struct PixelInfo {
+    pub x: u32,
+    pub y: u32,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+    /// `#RRGGBB`, or `#RRGGBBAA` when not fully opaque
+    pub hex: String,
+    /// Rec.601 luma of the color channels
+    pub luma: u8,
+}
+
+impl PixelInfo {
+    pub fn display(&self) -> String {
+        format!(
+            "Pixel ({}, {}): rgba({}, {}, {}, {}) {} luma {}",
+            self.x, self.y, self.r, self.g, self.b, self.a, self.hex, self.luma
+        )
+    }
+}
+
+/// The mean color of a rectangle, after clamping it to the image
+#[derive(Debug, Serialize)]
+pub struct RegionAverage {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+    /// `#RRGGBB`, or `#RRGGBBAA` when not fully opaque
+    pub hex: String,
+}
+
+impl RegionAverage {
+    pub fn display(&self) -> String {
+        format!(
+            "Region {}x{} at ({}, {}): rgba({}, {}, {}, {}) {}",
+            self.width, self.height, self.x, self.y, self.r, self.g, self.b, self.a, self.hex
+        )
+    }
+}
+
+pub(crate) fn format_file_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -96,6 +165,174 @@ pub fn load_image(path: &Path) -> Result<DynamicImage> {
         })
 }
 
+/// Load an image for `validate`: like [`load_image`], but a format that
+/// cannot be decoded is reported as `UnsupportedFormat`, so it can be told
+/// apart from a damaged file
+pub fn validate_image(path: &Path) -> Result<DynamicImage> {
+    if !path.exists() {
+        return Err(ImgEditError::InputNotFound(path.display().to_string()));
+    }
+
+    ImageReader::open(path)
+        .map_err(|e| ImgEditError::ReadError {
+            path: path.display().to_string(),
+            reason: e.to_string(),
+        })?
+        .decode()
+        .map_err(|e| match e {
+            image::ImageError::Unsupported(_) => ImgEditError::UnsupportedFormat(format!(
+                "Cannot decode '{}': {}",
+                path.display(),
+                e
+            )),
+            _ => ImgEditError::ReadError {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            },
+        })
+}
+
+/// Read the pixel at (x, y)
+pub fn pixel_info(img: &DynamicImage, x: u32, y: u32) -> Result<PixelInfo> {
+    if x >= img.width() || y >= img.height() {
+        return Err(ImgEditError::CropOutOfBounds(format!(
+            "Pixel ({}, {}) is outside image bounds {}x{}",
+            x,
+            y,
+            img.width(),
+            img.height()
+        )));
+    }
+
+    let [r, g, b, a] = img.get_pixel(x, y).0;
+    let hex = color_to_hex(image::Rgba([r, g, b, a]));
+    let luma = (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64).round() as u8;
+
+    Ok(PixelInfo {
+        x,
+        y,
+        r,
+        g,
+        b,
+        a,
+        hex,
+        luma,
+    })
+}
+
+/// Smallest, largest and mean value of one channel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ChannelRange {
+    pub min: u8,
+    pub max: u8,
+    pub mean: f64,
+}
+
+/// Per-channel ranges over every pixel, converted to 8-bit RGBA
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ChannelStats {
+    pub r: ChannelRange,
+    pub g: ChannelRange,
+    pub b: ChannelRange,
+    pub a: ChannelRange,
+}
+
+impl ChannelStats {
+    pub fn display(&self) -> String {
+        let line = |name: &str, c: &ChannelRange| {
+            format!("{}: min {} max {} mean {:.2}", name, c.min, c.max, c.mean)
+        };
+        format!(
+            "Channels:\n  {}\n  {}\n  {}\n  {}",
+            line("Red", &self.r),
+            line("Green", &self.g),
+            line("Blue", &self.b),
+            line("Alpha", &self.a)
+        )
+    }
+}
+
+/// Compute [`ChannelStats`] for `img`. Images without alpha report it as
+/// a constant 255.
+pub fn channel_stats(img: &DynamicImage) -> Result<ChannelStats> {
+    if img.width() == 0 || img.height() == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Image has no pixels".to_string(),
+        ));
+    }
+
+    let rgba = img.to_rgba8();
+    let mut mins = [u8::MAX; 4];
+    let mut maxes = [u8::MIN; 4];
+    let mut sums = [0u64; 4];
+    for pixel in rgba.pixels() {
+        for (c, &value) in pixel.0.iter().enumerate() {
+            mins[c] = mins[c].min(value);
+            maxes[c] = maxes[c].max(value);
+            sums[c] += value as u64;
+        }
+    }
+    let count = rgba.width() as f64 * rgba.height() as f64;
+    let [r, g, b, a] = std::array::from_fn(|c| ChannelRange {
+        min: mins[c],
+        max: maxes[c],
+        mean: sums[c] as f64 / count,
+    });
+
+    Ok(ChannelStats { r, g, b, a })
+}
+
+/// Average the RGBA values over a rectangle. The rectangle is clamped to the
+/// image bounds, and the clamped region is reported back.
+pub fn region_average(
+    img: &DynamicImage,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+) -> Result<RegionAverage> {
+    if width == 0 || height == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Region width and height must be greater than 0".to_string(),
+        ));
+    }
+    if x >= img.width() || y >= img.height() {
+        return Err(ImgEditError::CropOutOfBounds(format!(
+            "Region origin ({}, {}) is outside image bounds {}x{}",
+            x,
+            y,
+            img.width(),
+            img.height()
+        )));
+    }
+
+    let width = width.min(img.width() - x);
+    let height = height.min(img.height() - y);
+
+    let mut sums = [0u64; 4];
+    for py in y..y + height {
+        for px in x..x + width {
+            for (sum, value) in sums.iter_mut().zip(img.get_pixel(px, py).0) {
+                *sum += value as u64;
+            }
+        }
+    }
+    let count = width as u64 * height as u64;
+    let [r, g, b, a] = sums.map(|sum| ((sum + count / 2) / count) as u8);
+
+    Ok(RegionAverage {
+        x,
+        y,
+        width,
+        height,
+        r,
+        g,
+        b,
+        a,
+        hex: color_to_hex(image::Rgba([r, g, b, a])),
+    })
+}
+
 /// Get information about an image file
 pub fn get_image_info(path: &Path) -> Result<ImageInfo> {
     // Get file metadata for size
@@ -107,14 +344,24 @@ pub fn get_image_info(path: &Path) -> Result<ImageInfo> {
         }
     })?;
 
-    // Read image to get dimensions and format
-    let reader = ImageReader::open(path).map_err(|e| ImgEditError::ReadError {
+    // Read image to get dimensions and format, trusting the content over the
+    // extension so mislabeled files are reported as what they are
+    let read_error = |e: std::io::Error| ImgEditError::ReadError {
         path: path.display().to_string(),
         reason: e.to_string(),
-    })?;
+    };
+    let reader = ImageReader::open(path)
+        .map_err(read_error)?
+        .with_guessed_format()
+        .map_err(read_error)?;
 
-    let format = reader
-        .format()
+    let image_format = reader.format();
+    let extension_format = image::ImageFormat::from_path(path).ok();
+    let format_mismatch = matches!(
+        (image_format, extension_format),
+        (Some(content), Some(extension)) if content != extension
+    );
+    let format = image_format
         .map(|f| format!("{:?}", f).to_uppercase())
         .unwrap_or_else(|| "UNKNOWN".to_string());
 
@@ -124,22 +371,174 @@ pub fn get_image_info(path: &Path) -> Result<ImageInfo> {
     })?;
 
     let color_type = img.color();
+    let (dpi_x, dpi_y) = match image_format.and_then(|f| read_resolution(path, f)) {
+        Some((x, y)) => (Some(x), Some(y)),
+        None => (None, None),
+    };
 
     Ok(ImageInfo {
         file: path.display().to_string(),
         format,
+        format_mismatch,
         width: img.width(),
         height: img.height(),
         color_type: color_type_to_string(color_type),
         bit_depth: color_type_bit_depth(color_type),
+        has_alpha: color_type.has_alpha(),
         file_size_bytes: metadata.len(),
+        dpi_x,
+        dpi_y,
     })
 }
 
+/// Read the recorded resolution (x, y) in DPI from PNG pHYs, JPEG JFIF density
+/// or TIFF resolution tags. Returns `None` if the file has no absolute
+/// resolution (a unitless aspect ratio does not count).
+fn read_resolution(path: &Path, format: image::ImageFormat) -> Option<(f64, f64)> {
+    const METERS_PER_INCH: f64 = 0.0254;
+    const CM_PER_INCH: f64 = 2.54;
+
+    // Round to 2 decimals so e.g. 11811 px/m reads as 300 DPI
+    let round = |v: f64| (v * 100.0).round() / 100.0;
+
+    let (x, y) = match format {
+        image::ImageFormat::Png => {
+            let data = fs::read(path).ok()?;
+            let phys = png_chunk(&data, b"pHYs")?;
+            if phys.get(8) != Some(&1) {
+                return None;
+            }
+            let x = u32::from_be_bytes(phys[0..4].try_into().ok()?) as f64;
+            let y = u32::from_be_bytes(phys[4..8].try_into().ok()?) as f64;
+            (x * METERS_PER_INCH, y * METERS_PER_INCH)
+        }
+        image::ImageFormat::Jpeg => {
+            let data = fs::read(path).ok()?;
+            // SOI, then APP0: marker, length, "JFIF\0", version, units, X, Y
+            let app0 = data.get(2..20)?;
+            if app0[0..2] != [0xFF, 0xE0] || &app0[4..9] != b"JFIF\0" {
+                return None;
+            }
+            let x = u16::from_be_bytes([app0[12], app0[13]]) as f64;
+            let y = u16::from_be_bytes([app0[14], app0[15]]) as f64;
+            match app0[11] {
+                1 => (x, y),
+                2 => (x * CM_PER_INCH, y * CM_PER_INCH),
+                _ => return None,
+            }
+        }
+        image::ImageFormat::Tiff => {
+            use tiff::tags::Tag;
+
+            let file = fs::File::open(path).ok()?;
+            let mut decoder = tiff::decoder::Decoder::new(std::io::BufReader::new(file)).ok()?;
+            let mut rational = |tag| -> Option<f64> {
+                let v = decoder.get_tag_u32_vec(tag).ok()?;
+                (v.len() == 2 && v[1] != 0).then(|| v[0] as f64 / v[1] as f64)
+            };
+            let (x, y) = (rational(Tag::XResolution)?, rational(Tag::YResolution)?);
+            // ResolutionUnit defaults to inches; 1 means no absolute unit
+            match decoder.get_tag_u32(Tag::ResolutionUnit).unwrap_or(2) {
+                2 => (x, y),
+                3 => (x * CM_PER_INCH, y * CM_PER_INCH),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    Some((round(x), round(y)))
+}
+
+/// Find a chunk of an encoded PNG by type and return its data.
+/// Only the chunks before the image data are searched.
+pub(crate) fn png_chunk<'a>(png: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    let mut pos = 8;
+    while pos + 8 <= png.len() {
+        let len = u32::from_be_bytes(png[pos..pos + 4].try_into().ok()?) as usize;
+        if &png[pos + 4..pos + 8] == kind {
+            return png.get(pos + 8..pos + 8 + len);
+        }
+        if &png[pos + 4..pos + 8] == b"IDAT" {
+            return None;
+        }
+        pos += 12 + len;
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_pixel_info() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(4, 4, |x, y| {
+            image::Rgba([
+                x as u8 * 60,
+                y as u8 * 60,
+                10,
+                if x == 3 { 128 } else { 255 },
+            ])
+        }));
+
+        let pixel = pixel_info(&img, 2, 1).unwrap();
+        assert_eq!((pixel.r, pixel.g, pixel.b, pixel.a), (120, 60, 10, 255));
+        assert_eq!(pixel.hex, "#783C0A");
+        assert_eq!(pixel.luma, 72);
+
+        assert_eq!(pixel_info(&img, 3, 0).unwrap().hex, "#B4000A80");
+        assert!(pixel_info(&img, 4, 0).is_err());
+    }
+
+    #[test]
+    fn test_region_average_clamps_to_bounds() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            10,
+            10,
+            image::Rgba([10, 20, 30, 255]),
+        ));
+
+        let avg = region_average(&img, 8, 6, 50, 50).unwrap();
+        assert_eq!((avg.width, avg.height), (2, 4));
+        assert_eq!((avg.r, avg.g, avg.b, avg.a), (10, 20, 30, 255));
+        assert_eq!(avg.hex, "#0A141E");
+
+        assert!(region_average(&img, 10, 0, 1, 1).is_err());
+        assert!(region_average(&img, 0, 0, 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_read_resolution() {
+        use crate::ops::convert::{save_with_options, SaveOptions};
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let img = DynamicImage::new_rgb8(4, 4);
+        let with_dpi = SaveOptions {
+            dpi: Some(300),
+            ..SaveOptions::default()
+        };
+
+        for (name, format) in [
+            ("a.png", image::ImageFormat::Png),
+            ("a.jpg", image::ImageFormat::Jpeg),
+            ("a.tiff", image::ImageFormat::Tiff),
+        ] {
+            let path = temp_dir.path().join(name);
+            save_with_options(&img, &path, format, &with_dpi).unwrap();
+            assert_eq!(
+                read_resolution(&path, format),
+                Some((300.0, 300.0)),
+                "{}",
+                name
+            );
+
+            save_with_options(&img, &path, format, &SaveOptions::default()).unwrap();
+            assert_eq!(read_resolution(&path, format), None, "{}", name);
+        }
+    }
+
     #[test]
     fn test_format_file_size_bytes() {
         assert_eq!(format_file_size(500), "500 bytes");
@@ -207,6 +606,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_unsupported_format_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "not an image").unwrap();
+
+        // Only validate separates undecodable formats from damaged files
+        assert_eq!(load_image(&path).unwrap_err().code(), "READ_ERROR");
+        assert_eq!(
+            validate_image(&path).unwrap_err().code(),
+            "UNSUPPORTED_FORMAT"
+        );
+    }
+
+    #[test]
+    fn test_channel_stats_solid_gray() {
+        let img = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(
+            5,
+            4,
+            image::Rgb([100, 100, 100]),
+        ));
+        let stats = channel_stats(&img).unwrap();
+
+        let gray = ChannelRange {
+            min: 100,
+            max: 100,
+            mean: 100.0,
+        };
+        assert_eq!((stats.r, stats.g, stats.b), (gray, gray, gray));
+        assert_eq!(
+            stats.a,
+            ChannelRange {
+                min: 255,
+                max: 255,
+                mean: 255.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_channel_stats_range() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(4, 1, |x, _| {
+            image::Rgba([x as u8 * 10, 0, 255, if x == 0 { 0 } else { 255 }])
+        }));
+        let stats = channel_stats(&img).unwrap();
+
+        assert_eq!((stats.r.min, stats.r.max, stats.r.mean), (0, 30, 15.0));
+        assert_eq!((stats.a.min, stats.a.max), (0, 255));
+        assert!(stats.display().contains("Red: min 0 max 30 mean 15.00"));
+    }
+
+    #[test]
+    fn test_get_info_sniffs_mislabeled_format() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let img = DynamicImage::ImageRgb8(image::RgbImage::new(8, 6));
+        let jpeg = temp_dir.path().join("photo.jpg");
+        img.save(&jpeg).unwrap();
+        let mislabeled = temp_dir.path().join("photo.png");
+        fs::copy(&jpeg, &mislabeled).unwrap();
+
+        let info = get_image_info(&mislabeled).unwrap();
+        assert_eq!(info.format, "JPEG");
+        assert!(info.format_mismatch);
+        assert!(info.display().contains("does not match"));
+
+        let info = get_image_info(&jpeg).unwrap();
+        assert_eq!(info.format, "JPEG");
+        assert!(!info.format_mismatch);
+    }
+
     #[test]
     fn test_get_info_nonexistent() {
         let result = get_image_info(Path::new("nonexistent.png"));
@@ -218,11 +689,15 @@ mod tests {
         let info = ImageInfo {
             file: "test.png".to_string(),
             format: "PNG".to_string(),
+            format_mismatch: false,
             width: 800,
             height: 600,
             color_type: "RGBA".to_string(),
             bit_depth: 8,
+            has_alpha: true,
             file_size_bytes: 1536,
+            dpi_x: None,
+            dpi_y: None,
         };
 
         let display = info.display();
@@ -231,5 +706,13 @@ mod tests {
         assert!(display.contains("PNG"));
         assert!(display.contains("RGBA"));
         assert!(display.contains("1.50 KB"));
+        assert!(display.contains("Resolution: not set"));
+
+        let info = ImageInfo {
+            dpi_x: Some(300.0),
+            dpi_y: Some(72.5),
+            ..info
+        };
+        assert!(info.display().contains("Resolution: 300x72.5 DPI"));
     }
 }
@@ -0,0 +1,105 @@
+use crate::cli::args::BlendMode;
+use crate::error::{ImgEditError, Result};
+use crate::ops::canvas::blend_pixels;
+use image::{DynamicImage, Rgba};
+
+/// Cut the largest circle centered in the image out of it, for avatars: the
+/// area outside becomes transparent, with anti-aliased edges. With `crop`
+/// the result is first cut down to the circle's centered bounding square.
+/// A `border_width` above 0 paints a ring of `border_color` just inside the
+/// edge.
+pub fn circle(
+    img: &DynamicImage,
+    crop: bool,
+    border_width: u32,
+    border_color: Rgba<u8>,
+) -> Result<DynamicImage> {
+    let side = img.width().min(img.height());
+    if side == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Image has no pixels".to_string(),
+        ));
+    }
+    let radius = side as f32 / 2.0;
+    if border_width as f32 > radius {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Border width {} is larger than the circle radius {}",
+            border_width, radius
+        )));
+    }
+
+    let mut rgba = if crop {
+        img.crop_imm(
+            (img.width() - side) / 2,
+            (img.height() - side) / 2,
+            side,
+            side,
+        )
+        .to_rgba8()
+    } else {
+        img.to_rgba8()
+    };
+
+    let (cx, cy) = (rgba.width() as f32 / 2.0, rgba.height() as f32 / 2.0);
+    let ring_start = radius - border_width as f32;
+    for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+        let distance = (x as f32 + 0.5 - cx).hypot(y as f32 + 0.5 - cy);
+
+        // Coverage of a pixel straddling an edge, approximated from the
+        // distance of its center
+        if border_width > 0 {
+            let ring = (distance - ring_start + 0.5).clamp(0.0, 1.0);
+            *pixel = blend_pixels(*pixel, border_color, ring, BlendMode::Normal);
+        }
+        let inside = (radius - distance + 0.5).clamp(0.0, 1.0);
+        pixel[3] = (pixel[3] as f32 * inside).round() as u8;
+    }
+
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+
+    #[test]
+    fn test_circle_crop_square() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(80, 50, RED));
+        let result = circle(&img, true, 0, WHITE).unwrap().to_rgba8();
+
+        assert_eq!(result.dimensions(), (50, 50));
+        for (x, y) in [(0, 0), (49, 0), (0, 49), (49, 49)] {
+            assert_eq!(result.get_pixel(x, y)[3], 0, "corner ({}, {})", x, y);
+        }
+        assert_eq!(result.get_pixel(25, 25), &RED);
+        // Edge pixels are partly covered
+        let edge = (0..50).map(|x| result.get_pixel(x, 40)[3]);
+        assert!(edge.clone().any(|a| a > 0 && a < 255), "no anti-aliasing");
+    }
+
+    #[test]
+    fn test_circle_keeps_size_without_crop() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(80, 50, RED));
+        let result = circle(&img, false, 0, WHITE).unwrap().to_rgba8();
+        assert_eq!(result.dimensions(), (80, 50));
+        assert_eq!(result.get_pixel(5, 25)[3], 0);
+        assert_eq!(result.get_pixel(40, 25), &RED);
+    }
+
+    #[test]
+    fn test_circle_border_ring() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(40, 40, RED));
+        let result = circle(&img, true, 4, WHITE).unwrap().to_rgba8();
+
+        // Just inside the edge is border, the middle is untouched
+        assert_eq!(result.get_pixel(20, 1), &WHITE);
+        assert_eq!(result.get_pixel(20, 20), &RED);
+        assert_eq!(result.get_pixel(20, 8), &RED);
+
+        assert!(circle(&img, true, 30, WHITE).is_err());
+    }
+}
@@ -1,4 +1,6 @@
 pub mod adjust;
+pub mod audit;
+pub mod batch;
 pub mod canvas;
 pub mod color;
 pub mod convert;
@@ -6,18 +8,64 @@ pub mod crop;
 pub mod exif;
 pub mod filter;
 pub mod flip;
+pub mod flood;
+pub mod gradient;
+pub mod icc;
 pub mod info;
+pub mod mask;
+pub mod montage;
+pub mod recipe;
 pub mod resize;
 pub mod rotate;
+pub mod seam;
+pub mod smartcrop;
+pub mod stream;
+pub mod text;
 
-pub use adjust::{brightness, contrast, gamma};
-pub use canvas::{canvas_resize, composite, pad};
-pub use color::{change_depth, grayscale, invert};
-pub use convert::{determine_format, save_with_format};
-pub use crop::crop;
-pub use exif::{read_exif, ExifData, ExifField};
-pub use filter::{blur, sharpen};
+pub use adjust::{
+    adjust, brightness, clip_counts, clip_warning, contrast, curves, gamma, gray_point_gains,
+    parse_curve, saturation, temperature_gains, white_balance, Adjustments, ClipCounts, PointOp,
+};
+pub use audit::{audit, AuditSummary, DimensionStats};
+pub use batch::{load_manifest, parse_manifest, BatchJob};
+pub use canvas::{
+    canvas_resize, canvas_resize_textured, check_canvas_fits, checkerboard, chroma_key,
+    chroma_key_mask, composite, feather_alpha, frame, grid, overlay_visible_fraction, pad,
+    pad_edges, shadow, tile, EdgeColors,
+};
+pub use color::{
+    alpha_threshold, change_depth, grayscale, grayscale_luma_alpha, invert, parse_weights,
+    premultiply, unpremultiply,
+};
+pub use convert::{
+    alpha_warning, check_lossless, determine_format, dpi_warning, encode_to_bytes,
+    encode_with_options, ensure_parent_dir, is_animated, load_frame, optimize_warning,
+    png_alpha_warning, png_color_type_warning, png_palette, profile_warning, save_low_memory,
+    save_with_format, save_with_options, save_with_palette, single_channel, SaveOptions,
+};
+pub use crop::{crop, crop_padded};
+pub use exif::{
+    display_dimensions, expand_name_pattern, read_exif, set_orientation, suggest_name, ExifData,
+    ExifField,
+};
+pub use filter::{bilateral, blur, sharpen};
 pub use flip::flip;
-pub use info::{get_image_info, load_image};
-pub use resize::{fit, resize};
-pub use rotate::rotate;
+pub use flood::flood_remove;
+pub use gradient::{generate, gradient_overlay, linear_gradient, parse_gradient_spec};
+pub use icc::{icc_description, icc_profile};
+pub use info::{
+    channel_stats, get_image_info, load_image, pixel_info, region_average, validate_image,
+    ChannelRange, ChannelStats, PixelInfo, RegionAverage,
+};
+pub use mask::circle;
+pub use montage::{contact_sheet, SheetItem};
+pub use recipe::{apply_recipe, load_recipe, parse_recipe, RecipeStep};
+pub use resize::{
+    auto_filter, fit, in_linear_light, keep_other_dimension, limit, resize, resize_allowed,
+    resize_lanczos, resize_pad, resize_per_axis, scale, upscale_warning,
+};
+pub use rotate::{estimate_skew, rotate, rotate_textured};
+pub use seam::seam_carve;
+pub use smartcrop::{smart_crop, smart_crop_position};
+pub use stream::{stream_point_op, streamable_png};
+pub use text::{draw_text_anchored, Timestamp};
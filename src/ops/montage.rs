@@ -0,0 +1,135 @@
+use crate::cli::args::{FitMode, ResizeFilter};
+use crate::error::{ImgEditError, Result};
+use crate::ops::resize::fit;
+use crate::ops::text::{draw_text, text_size, truncate_text};
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+
+/// Gap in pixels around and between cells
+const SHEET_PADDING: u32 = 8;
+/// Vertical space taken by one label line: the 8px font plus spacing
+const LABEL_LINE_HEIGHT: u32 = 10;
+
+/// One thumbnail on a contact sheet and the lines of text drawn beneath it
+pub struct SheetItem {
+    pub image: DynamicImage,
+    pub label: Vec<String>,
+}
+
+/// Arrange `items` in a grid of `columns` cells, each image shrunk to fit a
+/// `cell_size` square and centered in it, with its label lines centered below.
+///
+/// Every row reserves room for the longest label so cells stay aligned; labels
+/// wider than a cell are truncated.
+pub fn contact_sheet(
+    items: &[SheetItem],
+    columns: u32,
+    cell_size: u32,
+    background: Rgba<u8>,
+    label_color: Rgba<u8>,
+) -> Result<DynamicImage> {
+    if items.is_empty() {
+        return Err(ImgEditError::InvalidParameter(
+            "A contact sheet needs at least one image".to_string(),
+        ));
+    }
+    if columns == 0 || cell_size == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Columns and cell size must be greater than 0".to_string(),
+        ));
+    }
+
+    let count = items.len() as u32;
+    let columns = columns.min(count);
+    let rows = count.div_ceil(columns);
+    let label_lines = items.iter().map(|item| item.label.len()).max().unwrap_or(0) as u32;
+    let label_height = label_lines * LABEL_LINE_HEIGHT;
+
+    let cell_height = cell_size + label_height;
+    let width = columns * cell_size + (columns + 1) * SHEET_PADDING;
+    let height = rows * cell_height + (rows + 1) * SHEET_PADDING;
+    let mut sheet = RgbaImage::from_pixel(width, height, background);
+
+    for (i, item) in items.iter().enumerate() {
+        let (col, row) = (i as u32 % columns, i as u32 / columns);
+        let cell_x = SHEET_PADDING + col * (cell_size + SHEET_PADDING);
+        let cell_y = SHEET_PADDING + row * (cell_height + SHEET_PADDING);
+
+        let thumb = fit(
+            &item.image,
+            Some(cell_size),
+            Some(cell_size),
+            FitMode::Down,
+            ResizeFilter::Lanczos,
+        )?
+        .to_rgba8();
+        let thumb_x = cell_x + (cell_size - thumb.width()) / 2;
+        let thumb_y = cell_y + (cell_size - thumb.height()) / 2;
+        imageops::overlay(&mut sheet, &thumb, thumb_x as i64, thumb_y as i64);
+
+        for (line_index, line) in item.label.iter().enumerate() {
+            let line = truncate_text(line, cell_size, 1);
+            let (text_w, _) = text_size(&line, 1);
+            let x = cell_x + (cell_size - text_w) / 2;
+            let y = cell_y + cell_size + 2 + line_index as u32 * LABEL_LINE_HEIGHT;
+            draw_text(&mut sheet, &line, x as i64, y as i64, 1, label_color);
+        }
+    }
+
+    Ok(DynamicImage::ImageRgba8(sheet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const BLACK: Rgba<u8> = Rgba([0, 0, 0, 255]);
+
+    fn item(width: u32, height: u32, label: &[&str]) -> SheetItem {
+        SheetItem {
+            image: DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+                width,
+                height,
+                Rgba([255, 0, 0, 255]),
+            )),
+            label: label.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_contact_sheet_layout_and_labels() {
+        let items = [item(64, 32, &["a.png"]), item(16, 16, &["b.png"])];
+        let sheet = contact_sheet(&items, 4, 32, WHITE, BLACK)
+            .unwrap()
+            .to_rgba8();
+
+        // Two columns (clamped to the item count), one label line per row
+        assert_eq!(sheet.dimensions(), (2 * 32 + 3 * 8, 32 + 10 + 2 * 8));
+
+        // The wide image is shrunk to 32x16 and centered vertically in its cell
+        assert_eq!(sheet.get_pixel(8 + 16, 8 + 16), &Rgba([255, 0, 0, 255]));
+        assert_eq!(sheet.get_pixel(8 + 16, 8 + 4), &WHITE);
+
+        // Label text lands below each thumbnail
+        for cell_x in [8, 48] {
+            let inked = (cell_x..cell_x + 32)
+                .flat_map(|x| (40..50).map(move |y| (x, y)))
+                .any(|(x, y)| sheet.get_pixel(x, y) == &BLACK);
+            assert!(inked, "no label under cell at x={}", cell_x);
+        }
+    }
+
+    #[test]
+    fn test_contact_sheet_wraps_rows() {
+        let items: Vec<_> = (0..5).map(|_| item(10, 10, &["x", "10x10"])).collect();
+        let sheet = contact_sheet(&items, 2, 20, WHITE, BLACK).unwrap();
+        assert_eq!(sheet.width(), 2 * 20 + 3 * 8);
+        assert_eq!(sheet.height(), 3 * (20 + 20) + 4 * 8);
+    }
+
+    #[test]
+    fn test_contact_sheet_rejects_empty() {
+        assert!(contact_sheet(&[], 2, 20, WHITE, BLACK).is_err());
+        assert!(contact_sheet(&[item(4, 4, &[])], 0, 20, WHITE, BLACK).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+use crate::cli::args::{Anchor, FitMode, ResizeFilter};
+use crate::color::parse_color;
+use crate::error::{ImgEditError, Result};
+use crate::ops::{adjust, canvas, color, crop, filter, flip, resize, rotate};
+use clap::ValueEnum;
+use image::{DynamicImage, Rgba};
+use serde::{Deserialize, Deserializer};
+use std::path::Path;
+
+/// One operation in a recipe file, tagged by its `op` name. Parameters
+/// mirror the command-line options of the command of the same name; colors
+/// and filters use the same spellings.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum RecipeStep {
+    Grayscale {
+        #[serde(default = "default_true")]
+        preserve_alpha: bool,
+    },
+    Invert {
+        #[serde(default)]
+        invert_alpha: bool,
+    },
+    Resize {
+        width: Option<u32>,
+        height: Option<u32>,
+        scale: Option<f64>,
+        #[serde(default, deserialize_with = "value_enum")]
+        filter: Option<ResizeFilter>,
+    },
+    Fit {
+        max_width: Option<u32>,
+        max_height: Option<u32>,
+        #[serde(default, deserialize_with = "value_enum")]
+        mode: Option<FitMode>,
+        #[serde(default, deserialize_with = "value_enum")]
+        filter: Option<ResizeFilter>,
+    },
+    Crop {
+        #[serde(default)]
+        x: u32,
+        #[serde(default)]
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    Rotate {
+        degrees: f64,
+        #[serde(default)]
+        expand: bool,
+        background: Option<String>,
+    },
+    Flip {
+        #[serde(default)]
+        horizontal: bool,
+        #[serde(default)]
+        vertical: bool,
+    },
+    Pad {
+        all: Option<u32>,
+        top: Option<u32>,
+        bottom: Option<u32>,
+        left: Option<u32>,
+        right: Option<u32>,
+        color: Option<String>,
+    },
+    Brightness {
+        value: i32,
+    },
+    Contrast {
+        value: f64,
+    },
+    Gamma {
+        value: f64,
+    },
+    Saturation {
+        value: f64,
+    },
+    Blur {
+        radius: f32,
+    },
+    Sharpen {
+        #[serde(default = "default_sharpen_amount")]
+        amount: f32,
+        #[serde(default = "default_sharpen_radius")]
+        radius: f32,
+    },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_sharpen_amount() -> f32 {
+    1.0
+}
+
+fn default_sharpen_radius() -> f32 {
+    1.0
+}
+
+/// Deserialize a value enum from its command-line spelling, e.g. `lanczos2`
+fn value_enum<'de, D, T>(deserializer: D) -> std::result::Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: ValueEnum,
+{
+    let name = String::deserialize(deserializer)?;
+    T::from_str(&name, true)
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+impl RecipeStep {
+    /// The `op` name this step was written with
+    pub fn name(&self) -> &'static str {
+        match self {
+            RecipeStep::Grayscale { .. } => "grayscale",
+            RecipeStep::Invert { .. } => "invert",
+            RecipeStep::Resize { .. } => "resize",
+            RecipeStep::Fit { .. } => "fit",
+            RecipeStep::Crop { .. } => "crop",
+            RecipeStep::Rotate { .. } => "rotate",
+            RecipeStep::Flip { .. } => "flip",
+            RecipeStep::Pad { .. } => "pad",
+            RecipeStep::Brightness { .. } => "brightness",
+            RecipeStep::Contrast { .. } => "contrast",
+            RecipeStep::Gamma { .. } => "gamma",
+            RecipeStep::Saturation { .. } => "saturation",
+            RecipeStep::Blur { .. } => "blur",
+            RecipeStep::Sharpen { .. } => "sharpen",
+        }
+    }
+
+    /// Apply this step to `img`
+    pub fn apply(&self, img: &DynamicImage) -> Result<DynamicImage> {
+        let color_or = |value: &Option<String>, default: Rgba<u8>| match value {
+            Some(value) => parse_color(value),
+            None => Ok(default),
+        };
+
+        match self {
+            RecipeStep::Grayscale { preserve_alpha } => {
+                color::grayscale(img, *preserve_alpha, None)
+            }
+            RecipeStep::Invert { invert_alpha } => color::invert(img, *invert_alpha),
+            RecipeStep::Resize {
+                width,
+                height,
+                scale,
+                filter,
+            } => resize::resize(
+                img,
+                *width,
+                *height,
+                *scale,
+                filter.unwrap_or(ResizeFilter::Lanczos),
+            ),
+            RecipeStep::Fit {
+                max_width,
+                max_height,
+                mode,
+                filter,
+            } => resize::fit(
+                img,
+                *max_width,
+                *max_height,
+                mode.unwrap_or(FitMode::Down),
+                filter.unwrap_or(ResizeFilter::Lanczos),
+            ),
+            RecipeStep::Crop {
+                x,
+                y,
+                width,
+                height,
+            } => crop::crop(img, *x, *y, *width, *height, Anchor::TopLeft),
+            RecipeStep::Rotate {
+                degrees,
+                expand,
+                background,
+            } => rotate::rotate(
+                img,
+                *degrees,
+                *expand,
+                color_or(background, Rgba([0, 0, 0, 0]))?,
+            ),
+            RecipeStep::Flip {
+                horizontal,
+                vertical,
+            } => flip::flip(img, *horizontal, *vertical),
+            RecipeStep::Pad {
+                all,
+                top,
+                bottom,
+                left,
+                right,
+                color,
+            } => {
+                let side = |value: &Option<u32>| value.or(*all).unwrap_or(0);
+                canvas::pad(
+                    img,
+                    side(top),
+                    side(bottom),
+                    side(left),
+                    side(right),
+                    color_or(color, Rgba([0, 0, 0, 0]))?,
+                )
+            }
+            RecipeStep::Brightness { value } => adjust::brightness(img, *value),
+            RecipeStep::Contrast { value } => adjust::contrast(img, *value),
+            RecipeStep::Gamma { value } => adjust::gamma(img, *value),
+            RecipeStep::Saturation { value } => adjust::saturation(img, *value),
+            RecipeStep::Blur { radius } => filter::blur(img, *radius),
+            RecipeStep::Sharpen { amount, radius } => filter::sharpen(img, *amount, *radius),
+        }
+    }
+}
+
+/// Parse a recipe: a JSON object whose `operations` array lists the steps in
+/// the order they are applied, e.g.
+/// `{"operations": [{"op": "grayscale"}, {"op": "resize", "width": 200}]}`
+pub fn parse_recipe(json: &str) -> Result<Vec<RecipeStep>> {
+    let invalid =
+        |reason: String| ImgEditError::InvalidParameter(format!("Invalid recipe: {}", reason));
+
+    let recipe: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+    let operations = recipe
+        .get("operations")
+        .and_then(|ops| ops.as_array())
+        .ok_or_else(|| invalid("expected an \"operations\" array".to_string()))?;
+    if operations.is_empty() {
+        return Err(invalid("the \"operations\" array is empty".to_string()));
+    }
+
+    // Parse step by step so errors name the offending operation
+    operations
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            RecipeStep::deserialize(step)
+                .map_err(|e| invalid(format!("operation {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+/// Read and parse a recipe file
+pub fn load_recipe(path: &Path) -> Result<Vec<RecipeStep>> {
+    if !path.exists() {
+        return Err(ImgEditError::InputNotFound(path.display().to_string()));
+    }
+    let json = std::fs::read_to_string(path).map_err(|e| ImgEditError::ReadError {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })?;
+    parse_recipe(&json)
+}
+
+/// Apply `steps` to `img` in order
+pub fn apply_recipe(img: &DynamicImage, steps: &[RecipeStep]) -> Result<DynamicImage> {
+    let mut result = img.clone();
+    for step in steps {
+        result = step.apply(&result)?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::GenericImageView;
+
+    #[test]
+    fn test_parse_recipe() {
+        let steps = parse_recipe(
+            r#"{"operations": [
+                {"op": "grayscale"},
+                {"op": "resize", "width": 50, "filter": "nearest"},
+                {"op": "pad", "all": 5, "top": 0, "color": "white"}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(steps.len(), 3);
+        assert_eq!(
+            steps[0],
+            RecipeStep::Grayscale {
+                preserve_alpha: true
+            }
+        );
+        assert_eq!(
+            steps[1],
+            RecipeStep::Resize {
+                width: Some(50),
+                height: None,
+                scale: None,
+                filter: Some(ResizeFilter::Nearest),
+            }
+        );
+        assert_eq!(steps[2].name(), "pad");
+    }
+
+    #[test]
+    fn test_parse_recipe_errors() {
+        let err = parse_recipe(r#"{"operations": [{"op": "grayscale"}, {"op": "emboss"}]}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("operation 2"), "{}", err);
+        assert!(err.contains("emboss"), "{}", err);
+
+        let err = parse_recipe(r#"{"operations": [{"op": "blur", "sigma": 2}]}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("sigma"), "{}", err);
+
+        let err = parse_recipe(r#"{"operations": [{"op": "resize", "filter": "blurry"}]}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("blurry"), "{}", err);
+
+        assert!(parse_recipe(r#"{"operations": []}"#).is_err());
+        assert!(parse_recipe("[]").is_err());
+        assert!(parse_recipe("not json").is_err());
+    }
+
+    #[test]
+    fn test_apply_recipe_in_order() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            100,
+            50,
+            Rgba([255, 0, 0, 255]),
+        ));
+        let steps = parse_recipe(
+            r#"{"operations": [
+                {"op": "grayscale"},
+                {"op": "resize", "width": 50},
+                {"op": "pad", "all": 10, "color": "white"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let result = apply_recipe(&img, &steps).unwrap();
+        assert_eq!(result.dimensions(), (70, 45));
+
+        let rgba = result.to_rgba8();
+        assert_eq!(rgba.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+        let center = rgba.get_pixel(35, 22);
+        assert_eq!(center[0], center[1]);
+        assert_eq!(center[1], center[2]);
+        assert!(center[0] < 255);
+    }
+}
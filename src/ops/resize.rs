@@ -1,6 +1,8 @@
-use crate::cli::args::ResizeFilter;
-use crate::error::{ImgEditError, Result};
-use image::DynamicImage;
+use crate::cli::args::{Anchor, FitMode, ResizeFilter, ScaleMode};
+use crate::error::{ImgEditError, Result, Warning};
+use crate::ops::canvas::canvas_resize;
+use crate::ops::crop::crop;
+use image::{DynamicImage, Rgba};
 
 /// Resize an image to exact dimensions or by a scale factor
 pub fn resize(
@@ -10,11 +12,134 @@ pub fn resize(
     scale: Option<f64>,
     filter: ResizeFilter,
 ) -> Result<DynamicImage> {
+    let (target_width, target_height) = target_dimensions(img, width, height, scale)?;
+    Ok(resize_exact(img, target_width, target_height, filter))
+}
+
+/// Resize to exact dimensions, using a custom kernel for the filters the
+/// `image` crate does not provide
+fn resize_exact(img: &DynamicImage, width: u32, height: u32, filter: ResizeFilter) -> DynamicImage {
+    match filter {
+        ResizeFilter::Auto => resize_exact(
+            img,
+            width,
+            height,
+            auto_filter((img.width(), img.height()), (width, height)),
+        ),
+        ResizeFilter::Lanczos2 => resample(img, width, height, Kernel::LANCZOS2),
+        ResizeFilter::Mitchell => resample(img, width, height, Kernel::MITCHELL),
+        ResizeFilter::Area => resample_with(img, width, height, area_contributions),
+        _ => img.resize_exact(width, height, filter.to_image_filter()),
+    }
+}
+
+/// Whether resizing an axis from `src_len` to `dst_len` repeats each source
+/// pixel a whole number of times
+fn is_integer_upscale(src_len: u32, dst_len: u32) -> bool {
+    src_len > 0 && dst_len >= src_len && dst_len.is_multiple_of(src_len)
+}
+
+/// The filter [`ResizeFilter::Auto`] resolves to: nearest-neighbor when both
+/// axes are whole-number upscales, so hard pixel edges survive, and Lanczos3
+/// for everything else
+pub fn auto_filter(source: (u32, u32), target: (u32, u32)) -> ResizeFilter {
+    if is_integer_upscale(source.0, target.0) && is_integer_upscale(source.1, target.1) {
+        ResizeFilter::Nearest
+    } else {
+        ResizeFilter::Lanczos
+    }
+}
+
+/// Resize like [`resize`], but with separate filters for the horizontal and
+/// vertical passes, e.g. smooth along x and hard-edged along y
+pub fn resize_per_axis(
+    img: &DynamicImage,
+    width: Option<u32>,
+    height: Option<u32>,
+    scale: Option<f64>,
+    horizontal: ResizeFilter,
+    vertical: ResizeFilter,
+) -> Result<DynamicImage> {
+    let (target_width, target_height) = target_dimensions(img, width, height, scale)?;
+    let columns = axis_contributions(horizontal, img.width(), target_width);
+    let rows = axis_contributions(vertical, img.height(), target_height);
+    Ok(resample_separable(
+        img,
+        target_width,
+        target_height,
+        &columns,
+        &rows,
+    ))
+}
+
+/// Resize like [`resize`], but resample with a custom Lanczos kernel of window `a`
+/// (2 or 3) instead of the built-in Lanczos3 filter
+pub fn resize_lanczos(
+    img: &DynamicImage,
+    width: Option<u32>,
+    height: Option<u32>,
+    scale: Option<f64>,
+    a: u32,
+) -> Result<DynamicImage> {
+    let kernel = match a {
+        2 => Kernel::LANCZOS2,
+        3 => Kernel::LANCZOS3,
+        _ => {
+            return Err(ImgEditError::InvalidParameter(format!(
+                "Lanczos window must be 2 or 3, got {}",
+                a
+            )));
+        }
+    };
+
+    let (target_width, target_height) = target_dimensions(img, width, height, scale)?;
+    Ok(resample(img, target_width, target_height, kernel))
+}
+
+/// Whether resizing to the requested target goes in a direction `mode` allows:
+/// `Down` rejects enlarging either dimension, `Up` rejects shrinking either one.
+/// Validates the request the same way as [`resize`].
+pub fn resize_allowed(
+    img: &DynamicImage,
+    width: Option<u32>,
+    height: Option<u32>,
+    scale: Option<f64>,
+    mode: FitMode,
+) -> Result<bool> {
+    let (target_width, target_height) = target_dimensions(img, width, height, scale)?;
+    Ok(match mode {
+        FitMode::Both => true,
+        FitMode::Down => target_width <= img.width() && target_height <= img.height(),
+        FitMode::Up => target_width >= img.width() && target_height >= img.height(),
+    })
+}
+
+/// The dimensions to resize to when only one side may change: a missing
+/// `width` or `height` is filled in with the image's own, so giving just one
+/// stretches that axis instead of keeping the aspect ratio
+pub fn keep_other_dimension(
+    img: &DynamicImage,
+    width: Option<u32>,
+    height: Option<u32>,
+) -> (Option<u32>, Option<u32>) {
+    match (width, height) {
+        (Some(w), None) => (Some(w), Some(img.height())),
+        (None, Some(h)) => (Some(img.width()), Some(h)),
+        other => other,
+    }
+}
+
+/// Compute the target dimensions for `resize` from explicit dimensions or a scale factor
+fn target_dimensions(
+    img: &DynamicImage,
+    width: Option<u32>,
+    height: Option<u32>,
+    scale: Option<f64>,
+) -> Result<(u32, u32)> {
     let img_width = img.width();
     let img_height = img.height();
 
-    // Determine target dimensions
-    let (target_width, target_height) = if let Some(s) = scale {
+    let dimensions = if let Some(s) = scale {
         if s <= 0.0 {
             return Err(ImgEditError::InvalidParameter(
                 "Scale must be positive".to_string(),
@@ -68,7 +193,7 @@ pub fn resize(
         }
     };
 
-    Ok(img.resize_exact(target_width, target_height, filter.to_image_filter()))
+    Ok(dimensions)
 }
 
 /// Resize an image to fit within maximum bounds while preserving aspect ratio
@@ -76,7 +201,7 @@ pub fn fit(
     img: &DynamicImage,
     max_width: Option<u32>,
     max_height: Option<u32>,
-    upscale: bool,
+    mode: FitMode,
     filter: ResizeFilter,
 ) -> Result<DynamicImage> {
     if max_width.is_none() && max_height.is_none() {
@@ -100,8 +225,12 @@ pub fn fit(
         (None, None) => unreachable!(),
     };
 
-    // Don't upscale unless requested
-    let final_scale = if !upscale && scale > 1.0 { 1.0 } else { scale };
+    // Only scale in the direction(s) the mode allows
+    let final_scale = match mode {
+        FitMode::Down if scale > 1.0 => 1.0,
+        FitMode::Up if scale < 1.0 => 1.0,
+        _ => scale,
+    };
 
     if (final_scale - 1.0).abs() < 0.0001 {
         // No change needed
@@ -117,19 +246,430 @@ pub fn fit(
         ));
     }
 
-    Ok(img.resize_exact(target_width, target_height, filter.to_image_filter()))
+    Ok(resize_exact(img, target_width, target_height, filter))
+}
+
+/// Scale an image down so its longest side is at most `max`, preserving aspect
+/// ratio. Never enlarges; an image that already fits is returned unchanged.
+pub fn limit(img: &DynamicImage, max: u32, filter: ResizeFilter) -> Result<DynamicImage> {
+    fit(img, Some(max), Some(max), FitMode::Down, filter)
+}
+
+/// Run a resampling `op` in linear light: the sRGB-encoded image is decoded
+/// to a linear float buffer, `op` resamples that, and the result is encoded
+/// back to sRGB. Averaging encoded values darkens fine high-contrast detail;
+/// averaging light does not. Alpha is not gamma-encoded and passes through
+/// as is. The result is 16-bit for sources deeper than 8 bits, else 8-bit.
+pub fn in_linear_light(
+    img: &DynamicImage,
+    op: impl FnOnce(&DynamicImage) -> Result<DynamicImage>,
+) -> Result<DynamicImage> {
+    let mut linear = img.to_rgba32f();
+    for pixel in linear.pixels_mut() {
+        for c in 0..3 {
+            pixel[c] = srgb_to_linear(pixel[c]);
+        }
+    }
+
+    let mut resampled = op(&DynamicImage::ImageRgba32F(linear))?.to_rgba32f();
+    for pixel in resampled.pixels_mut() {
+        for c in 0..3 {
+            pixel[c] = linear_to_srgb(pixel[c]);
+        }
+    }
+
+    let resampled = DynamicImage::ImageRgba32F(resampled);
+    let deep = img.color().bits_per_pixel() / img.color().channel_count() as u16 > 8;
+    Ok(if deep {
+        DynamicImage::ImageRgba16(resampled.to_rgba16())
+    } else {
+        DynamicImage::ImageRgba8(resampled.to_rgba8())
+    })
+}
+
+/// Decode an sRGB-encoded channel value (0.0 to 1.0) to linear light
+fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear-light channel value (0.0 to 1.0) as sRGB
+fn linear_to_srgb(v: f32) -> f32 {
+    let v = v.clamp(0.0, 1.0);
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// A separable resampling kernel: a weight function and its support radius in
+/// source pixels (at 1:1 scale)
+#[derive(Clone, Copy)]
+struct Kernel {
+    weight: fn(f32) -> f32,
+    support: f32,
+}
+
+impl Kernel {
+    const LINEAR: Kernel = Kernel {
+        weight: triangle,
+        support: 1.0,
+    };
+    const CATMULL_ROM: Kernel = Kernel {
+        weight: catmull_rom,
+        support: 2.0,
+    };
+    const LANCZOS2: Kernel = Kernel {
+        weight: lanczos2,
+        support: 2.0,
+    };
+    const LANCZOS3: Kernel = Kernel {
+        weight: lanczos3,
+        support: 3.0,
+    };
+    const MITCHELL: Kernel = Kernel {
+        weight: mitchell,
+        support: 2.0,
+    };
+}
+
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-6 {
+        1.0
+    } else {
+        let px = std::f32::consts::PI * x;
+        px.sin() / px
+    }
+}
+
+fn lanczos(x: f32, a: f32) -> f32 {
+    if x.abs() < a {
+        sinc(x) * sinc(x / a)
+    } else {
+        0.0
+    }
+}
+
+fn triangle(x: f32) -> f32 {
+    (1.0 - x.abs()).max(0.0)
+}
+
+/// Catmull-Rom cubic: the Keys cubic with a = -0.5
+fn catmull_rom(x: f32) -> f32 {
+    let x = x.abs();
+    if x < 1.0 {
+        1.5 * x.powi(3) - 2.5 * x.powi(2) + 1.0
+    } else if x < 2.0 {
+        -0.5 * x.powi(3) + 2.5 * x.powi(2) - 4.0 * x + 2.0
+    } else {
+        0.0
+    }
+}
+
+fn lanczos2(x: f32) -> f32 {
+    lanczos(x, 2.0)
+}
+
+fn lanczos3(x: f32) -> f32 {
+    lanczos(x, 3.0)
+}
+
+/// Mitchell-Netravali cubic with B = C = 1/3
+fn mitchell(x: f32) -> f32 {
+    const B: f32 = 1.0 / 3.0;
+    const C: f32 = 1.0 / 3.0;
+    let x = x.abs();
+    let w = if x < 1.0 {
+        (12.0 - 9.0 * B - 6.0 * C) * x.powi(3)
+            + (-18.0 + 12.0 * B + 6.0 * C) * x.powi(2)
+            + (6.0 - 2.0 * B)
+    } else if x < 2.0 {
+        (-B - 6.0 * C) * x.powi(3)
+            + (6.0 * B + 30.0 * C) * x.powi(2)
+            + (-12.0 * B - 48.0 * C) * x
+            + (8.0 * B + 24.0 * C)
+    } else {
+        0.0
+    };
+    w / 6.0
+}
+
+/// Contributing source pixels for one output pixel along an axis
+struct Contribution {
+    start: usize,
+    weights: Vec<f32>,
+}
+
+/// Precompute the normalized kernel weights mapping `src_len` pixels onto `dst_len`
+fn contributions(src_len: u32, dst_len: u32, kernel: Kernel) -> Vec<Contribution> {
+    let ratio = src_len as f32 / dst_len as f32;
+    // Widen the kernel when downscaling so it acts as a low-pass filter
+    let filter_scale = ratio.max(1.0);
+    let support = kernel.support * filter_scale;
+
+    (0..dst_len)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * ratio;
+            let start = (center - support).floor().max(0.0) as usize;
+            let end = ((center + support).ceil() as usize).min(src_len as usize);
+
+            let mut weights: Vec<f32> = (start..end)
+                .map(|j| (kernel.weight)((j as f32 + 0.5 - center) / filter_scale))
+                .collect();
+
+            let sum: f32 = weights.iter().sum();
+            if sum.abs() > f32::EPSILON {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            } else {
+                // Degenerate kernel: fall back to the nearest source pixel
+                let nearest = (center as usize).min(src_len as usize - 1);
+                return Contribution {
+                    start: nearest,
+                    weights: vec![1.0],
+                };
+            }
+
+            Contribution { start, weights }
+        })
+        .collect()
+}
+
+/// Nearest-neighbor weights: each output pixel copies the source pixel under
+/// its center
+fn nearest_contributions(src_len: u32, dst_len: u32) -> Vec<Contribution> {
+    let ratio = src_len as f32 / dst_len as f32;
+    (0..dst_len)
+        .map(|i| Contribution {
+            start: (((i as f32 + 0.5) * ratio) as usize).min(src_len as usize - 1),
+            weights: vec![1.0],
+        })
+        .collect()
+}
+
+/// Weights for resampling one axis of `src_len` pixels onto `dst_len` with `filter`
+fn axis_contributions(filter: ResizeFilter, src_len: u32, dst_len: u32) -> Vec<Contribution> {
+    match filter {
+        ResizeFilter::Nearest => nearest_contributions(src_len, dst_len),
+        ResizeFilter::Linear => contributions(src_len, dst_len, Kernel::LINEAR),
+        ResizeFilter::Cubic => contributions(src_len, dst_len, Kernel::CATMULL_ROM),
+        ResizeFilter::Lanczos => contributions(src_len, dst_len, Kernel::LANCZOS3),
+        ResizeFilter::Lanczos2 => contributions(src_len, dst_len, Kernel::LANCZOS2),
+        ResizeFilter::Mitchell => contributions(src_len, dst_len, Kernel::MITCHELL),
+        ResizeFilter::Area => area_contributions(src_len, dst_len),
+        // Decided per axis when the filters differ
+        ResizeFilter::Auto if is_integer_upscale(src_len, dst_len) => {
+            nearest_contributions(src_len, dst_len)
+        }
+        ResizeFilter::Auto => contributions(src_len, dst_len, Kernel::LANCZOS3),
+    }
+}
+
+/// Area-averaging weights mapping `src_len` pixels onto `dst_len`: each output
+/// pixel spans `src_len / dst_len` source pixels, each weighted by how much of
+/// it falls inside that span
+fn area_contributions(src_len: u32, dst_len: u32) -> Vec<Contribution> {
+    let ratio = src_len as f64 / dst_len as f64;
+
+    (0..dst_len)
+        .map(|i| {
+            let lo = i as f64 * ratio;
+            let hi = ((i + 1) as f64 * ratio).min(src_len as f64);
+            let start = lo.floor() as usize;
+            let end = (hi.ceil() as usize).clamp(start + 1, src_len as usize);
+
+            let weights = (start..end)
+                .map(|j| {
+                    let overlap = hi.min(j as f64 + 1.0) - lo.max(j as f64);
+                    (overlap / (hi - lo)) as f32
+                })
+                .collect();
+
+            Contribution { start, weights }
+        })
+        .collect()
+}
+
+/// Resample an image to `width`x`height` with separable horizontal and vertical
+/// passes of `kernel`, working in floating point to avoid intermediate rounding
+fn resample(img: &DynamicImage, width: u32, height: u32, kernel: Kernel) -> DynamicImage {
+    resample_with(img, width, height, |src_len, dst_len| {
+        contributions(src_len, dst_len, kernel)
+    })
+}
+
+/// Separable resampling with per-axis weights from `weights(src_len, dst_len)`
+fn resample_with(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    weights: impl Fn(u32, u32) -> Vec<Contribution>,
+) -> DynamicImage {
+    let columns = weights(img.width(), width);
+    let rows = weights(img.height(), height);
+    resample_separable(img, width, height, &columns, &rows)
+}
+
+/// Separable resampling with precomputed `columns` weights for the horizontal
+/// pass and `rows` weights for the vertical one
+fn resample_separable(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    columns: &[Contribution],
+    rows: &[Contribution],
+) -> DynamicImage {
+    let src = img.to_rgba32f();
+    let src_h = src.height();
+
+    // Horizontal pass: src_w x src_h -> width x src_h
+    let horizontal = image::Rgba32FImage::from_fn(width, src_h, |x, y| {
+        let contrib = &columns[x as usize];
+        let mut acc = [0.0f32; 4];
+        for (k, w) in contrib.weights.iter().enumerate() {
+            let p = src.get_pixel((contrib.start + k) as u32, y);
+            for c in 0..4 {
+                acc[c] += p[c] * w;
+            }
+        }
+        Rgba(acc)
+    });
+
+    // Vertical pass: width x src_h -> width x height
+    let vertical = image::Rgba32FImage::from_fn(width, height, |x, y| {
+        let contrib = &rows[y as usize];
+        let mut acc = [0.0f32; 4];
+        for (k, w) in contrib.weights.iter().enumerate() {
+            let p = horizontal.get_pixel(x, (contrib.start + k) as u32);
+            for c in 0..4 {
+                acc[c] += p[c] * w;
+            }
+        }
+        Rgba(acc.map(|v| v.clamp(0.0, 1.0)))
+    });
+
+    // Float sources (such as linear-light buffers) keep their precision, and
+    // deep sources their 16 bits
+    let resampled = DynamicImage::ImageRgba32F(vertical);
+    match img {
+        DynamicImage::ImageRgba32F(_) => resampled,
+        _ if img.color().bits_per_pixel() / img.color().channel_count() as u16 > 8 => {
+            DynamicImage::ImageRgba16(resampled.to_rgba16())
+        }
+        _ => DynamicImage::ImageRgba8(resampled.to_rgba8()),
+    }
+}
+
+/// Scale factor above which built-in resampling filters produce visibly poor results
+pub const UPSCALE_WARNING_THRESHOLD: f64 = 4.0;
+
+/// Check whether a resize from `source` to `target` dimensions upscales beyond
+/// [`UPSCALE_WARNING_THRESHOLD`]. Purely advisory; never blocks the operation.
+pub fn upscale_warning(source: (u32, u32), target: (u32, u32)) -> Option<Warning> {
+    let scale_x = target.0 as f64 / source.0.max(1) as f64;
+    let scale_y = target.1 as f64 / source.1.max(1) as f64;
+    let scale = scale_x.max(scale_y);
+
+    if scale > UPSCALE_WARNING_THRESHOLD {
+        Some(Warning::new(
+            "LARGE_UPSCALE",
+            format!(
+                "Upscaling by {:.1}x exceeds {:.0}x; built-in filters will look soft or blocky. \
+                 Consider a dedicated upscaler.",
+                scale, UPSCALE_WARNING_THRESHOLD
+            ),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Resize an image to exact dimensions without distortion by fitting it within
+/// the target box and padding the remainder with a background color
+pub fn resize_pad(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    filter: ResizeFilter,
+    background: Rgba<u8>,
+) -> Result<DynamicImage> {
+    if width == 0 || height == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Width and height must be positive".to_string(),
+        ));
+    }
+
+    let fitted = fit(img, Some(width), Some(height), FitMode::Both, filter)?;
+    canvas_resize(&fitted, width, height, Anchor::Center, background)
+}
+
+/// Scale an image to a `width` x `height` box, handling a differing aspect
+/// ratio as `mode` says: stretch ([`resize`]), fit inside ([`fit`]), fill
+/// and crop the overflow around the center, or fit and pad with
+/// `background` ([`resize_pad`])
+pub fn scale(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    mode: ScaleMode,
+    filter: ResizeFilter,
+    background: Rgba<u8>,
+) -> Result<DynamicImage> {
+    if width == 0 || height == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Width and height must be positive".to_string(),
+        ));
+    }
+
+    match mode {
+        ScaleMode::Exact => resize(img, Some(width), Some(height), None, filter),
+        ScaleMode::Fit => fit(img, Some(width), Some(height), FitMode::Both, filter),
+        ScaleMode::Pad => resize_pad(img, width, height, filter, background),
+        ScaleMode::Cover => {
+            let factor =
+                (width as f64 / img.width() as f64).max(height as f64 / img.height() as f64);
+            // Never round below the box, or the crop would come up short
+            let scaled_width = ((img.width() as f64 * factor).round() as u32).max(width);
+            let scaled_height = ((img.height() as f64 * factor).round() as u32).max(height);
+            let scaled = if (scaled_width, scaled_height) == (img.width(), img.height()) {
+                img.clone()
+            } else {
+                resize_exact(img, scaled_width, scaled_height, filter)
+            };
+            crop(&scaled, 0, 0, width, height, Anchor::Center)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use image::{ImageBuffer, Rgba};
+    use image::{GenericImageView, ImageBuffer, Rgba};
 
     fn create_test_image(width: u32, height: u32) -> DynamicImage {
         let img = ImageBuffer::from_fn(width, height, |_, _| Rgba([128, 128, 128, 255]));
         DynamicImage::ImageRgba8(img)
     }
 
+    #[test]
+    fn test_resize_allowed() {
+        let img = create_test_image(100, 50);
+        let allowed = |w, mode| resize_allowed(&img, Some(w), None, None, mode).unwrap();
+
+        assert!(!allowed(200, FitMode::Down));
+        assert!(allowed(50, FitMode::Down));
+        assert!(allowed(200, FitMode::Up));
+        assert!(!allowed(50, FitMode::Up));
+        assert!(allowed(100, FitMode::Down) && allowed(100, FitMode::Up));
+        assert!(allowed(50, FitMode::Both) && allowed(200, FitMode::Both));
+        // Either dimension going the wrong way is enough to refuse
+        assert!(!resize_allowed(&img, Some(50), Some(80), None, FitMode::Down).unwrap());
+        assert!(resize_allowed(&img, None, None, None, FitMode::Down).is_err());
+    }
+
     #[test]
     fn test_resize_exact_dimensions() {
         let img = create_test_image(100, 100);
@@ -146,6 +686,23 @@ mod tests {
         assert_eq!(result.height(), 25); // Preserves 2:1 aspect ratio
     }
 
+    #[test]
+    fn test_keep_other_dimension_stretches() {
+        let img = create_test_image(100, 50);
+        let (width, height) = keep_other_dimension(&img, Some(200), None);
+        let result = resize(&img, width, height, None, ResizeFilter::Lanczos).unwrap();
+        assert_eq!((result.width(), result.height()), (200, 50));
+
+        assert_eq!(
+            keep_other_dimension(&img, None, Some(20)),
+            (Some(100), Some(20))
+        );
+        assert_eq!(
+            keep_other_dimension(&img, Some(5), Some(6)),
+            (Some(5), Some(6))
+        );
+    }
+
     #[test]
     fn test_resize_height_only() {
         let img = create_test_image(100, 50);
@@ -201,15 +758,30 @@ mod tests {
     #[test]
     fn test_fit_within_width() {
         let img = create_test_image(200, 100);
-        let result = fit(&img, Some(100), None, false, ResizeFilter::Lanczos).unwrap();
+        let result = fit(&img, Some(100), None, FitMode::Down, ResizeFilter::Lanczos).unwrap();
         assert_eq!(result.width(), 100);
         assert_eq!(result.height(), 50);
     }
 
+    #[test]
+    fn test_limit_longest_side() {
+        let img = create_test_image(400, 200);
+        let result = limit(&img, 100, ResizeFilter::Lanczos).unwrap();
+        assert_eq!((result.width(), result.height()), (100, 50));
+
+        let portrait = create_test_image(200, 400);
+        let result = limit(&portrait, 100, ResizeFilter::Lanczos).unwrap();
+        assert_eq!((result.width(), result.height()), (50, 100));
+
+        // Already within the limit: never upscaled
+        let result = limit(&img, 1000, ResizeFilter::Lanczos).unwrap();
+        assert_eq!((result.width(), result.height()), (400, 200));
+    }
+
     #[test]
     fn test_fit_within_height() {
         let img = create_test_image(200, 100);
-        let result = fit(&img, None, Some(50), false, ResizeFilter::Lanczos).unwrap();
+        let result = fit(&img, None, Some(50), FitMode::Down, ResizeFilter::Lanczos).unwrap();
         assert_eq!(result.width(), 100);
         assert_eq!(result.height(), 50);
     }
@@ -217,7 +789,14 @@ mod tests {
     #[test]
     fn test_fit_within_both_width_limited() {
         let img = create_test_image(200, 100);
-        let result = fit(&img, Some(100), Some(100), false, ResizeFilter::Lanczos).unwrap();
+        let result = fit(
+            &img,
+            Some(100),
+            Some(100),
+            FitMode::Down,
+            ResizeFilter::Lanczos,
+        )
+        .unwrap();
         // Width is the limiting factor
         assert_eq!(result.width(), 100);
         assert_eq!(result.height(), 50);
@@ -226,7 +805,14 @@ mod tests {
     #[test]
     fn test_fit_within_both_height_limited() {
         let img = create_test_image(100, 200);
-        let result = fit(&img, Some(100), Some(100), false, ResizeFilter::Lanczos).unwrap();
+        let result = fit(
+            &img,
+            Some(100),
+            Some(100),
+            FitMode::Down,
+            ResizeFilter::Lanczos,
+        )
+        .unwrap();
         // Height is the limiting factor
         assert_eq!(result.width(), 50);
         assert_eq!(result.height(), 100);
@@ -235,7 +821,14 @@ mod tests {
     #[test]
     fn test_fit_no_upscale() {
         let img = create_test_image(50, 50);
-        let result = fit(&img, Some(100), Some(100), false, ResizeFilter::Lanczos).unwrap();
+        let result = fit(
+            &img,
+            Some(100),
+            Some(100),
+            FitMode::Down,
+            ResizeFilter::Lanczos,
+        )
+        .unwrap();
         // Should not upscale
         assert_eq!(result.width(), 50);
         assert_eq!(result.height(), 50);
@@ -244,19 +837,227 @@ mod tests {
     #[test]
     fn test_fit_with_upscale() {
         let img = create_test_image(50, 50);
-        let result = fit(&img, Some(100), Some(100), true, ResizeFilter::Lanczos).unwrap();
+        let result = fit(
+            &img,
+            Some(100),
+            Some(100),
+            FitMode::Both,
+            ResizeFilter::Lanczos,
+        )
+        .unwrap();
         // Should upscale
         assert_eq!(result.width(), 100);
         assert_eq!(result.height(), 100);
     }
 
+    #[test]
+    fn test_fit_modes_small_image() {
+        let img = create_test_image(50, 50);
+        for (mode, expected) in [
+            (FitMode::Both, 100),
+            (FitMode::Down, 50),
+            (FitMode::Up, 100),
+        ] {
+            let result = fit(&img, Some(100), Some(100), mode, ResizeFilter::Lanczos).unwrap();
+            assert_eq!(result.dimensions(), (expected, expected), "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_fit_modes_large_image() {
+        let img = create_test_image(200, 200);
+        for (mode, expected) in [
+            (FitMode::Both, 100),
+            (FitMode::Down, 100),
+            (FitMode::Up, 200),
+        ] {
+            let result = fit(&img, Some(100), Some(100), mode, ResizeFilter::Lanczos).unwrap();
+            assert_eq!(result.dimensions(), (expected, expected), "{:?}", mode);
+        }
+    }
+
     #[test]
     fn test_fit_no_params() {
         let img = create_test_image(100, 100);
-        let result = fit(&img, None, None, false, ResizeFilter::Lanczos);
+        let result = fit(&img, None, None, FitMode::Down, ResizeFilter::Lanczos);
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_resize_pad_letterbox() {
+        let img = create_test_image(200, 100);
+        let result =
+            resize_pad(&img, 100, 100, ResizeFilter::Lanczos, Rgba([0, 0, 0, 255])).unwrap();
+        assert_eq!(result.width(), 100);
+        assert_eq!(result.height(), 100);
+
+        let rgba = result.to_rgba8();
+        // Bars above and below the fitted 100x50 content
+        assert_eq!(rgba.get_pixel(50, 0), &Rgba([0, 0, 0, 255]));
+        assert_eq!(rgba.get_pixel(50, 99), &Rgba([0, 0, 0, 255]));
+        assert_eq!(rgba.get_pixel(50, 50), &Rgba([128, 128, 128, 255]));
+    }
+
+    #[test]
+    fn test_custom_kernels_keep_16_bits() {
+        // 12345 has no exact 8-bit equivalent
+        let img = DynamicImage::ImageLuma16(ImageBuffer::from_pixel(8, 8, image::Luma([12345])));
+        let results = [
+            resize(&img, Some(4), None, None, ResizeFilter::Lanczos2).unwrap(),
+            resize(&img, Some(4), None, None, ResizeFilter::Mitchell).unwrap(),
+            resize(&img, Some(4), None, None, ResizeFilter::Area).unwrap(),
+            resize_per_axis(
+                &img,
+                Some(4),
+                None,
+                None,
+                ResizeFilter::Mitchell,
+                ResizeFilter::Nearest,
+            )
+            .unwrap(),
+            resize_lanczos(&img, Some(4), None, None, 2).unwrap(),
+        ];
+
+        for (i, result) in results.iter().enumerate() {
+            let DynamicImage::ImageRgba16(rgba) = result else {
+                panic!("result {} is {:?}", i, result.color());
+            };
+            assert_eq!(rgba.dimensions(), (4, 4));
+            for pixel in rgba.pixels() {
+                assert!(
+                    (pixel[0] as i32 - 12345).abs() <= 1,
+                    "result {}: {:?}",
+                    i,
+                    pixel
+                );
+                assert_eq!(pixel[3], u16::MAX);
+            }
+        }
+
+        // 8-bit sources stay 8-bit
+        let img = create_test_image(8, 8);
+        let result = resize(&img, Some(4), None, None, ResizeFilter::Mitchell).unwrap();
+        assert!(matches!(result, DynamicImage::ImageRgba8(_)));
+    }
+
+    #[test]
+    fn test_scale_modes() {
+        // A green strip down the left quarter, red elsewhere
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(200, 100, |x, _| {
+            if x < 50 {
+                Rgba([0, 255, 0, 255])
+            } else {
+                Rgba([255, 0, 0, 255])
+            }
+        }));
+        let black = Rgba([0, 0, 0, 255]);
+        let scaled = |mode| {
+            scale(&img, 100, 100, mode, ResizeFilter::Nearest, black)
+                .unwrap()
+                .to_rgba8()
+        };
+
+        // Stretched: the strip is squeezed but kept, and spans the full height
+        let exact = scaled(ScaleMode::Exact);
+        assert_eq!(exact.dimensions(), (100, 100));
+        assert_eq!(exact.get_pixel(5, 5), &Rgba([0, 255, 0, 255]));
+
+        let fitted = scaled(ScaleMode::Fit);
+        assert_eq!(fitted.dimensions(), (100, 50));
+        assert_eq!(fitted.get_pixel(5, 25), &Rgba([0, 255, 0, 255]));
+
+        // The centered crop drops the strip
+        let cover = scaled(ScaleMode::Cover);
+        assert_eq!(cover.dimensions(), (100, 100));
+        assert!(cover.pixels().all(|p| p == &Rgba([255, 0, 0, 255])));
+
+        let padded = scaled(ScaleMode::Pad);
+        assert_eq!(padded.dimensions(), (100, 100));
+        assert_eq!(padded.get_pixel(5, 5), &black);
+        assert_eq!(padded.get_pixel(5, 50), &Rgba([0, 255, 0, 255]));
+    }
+
+    #[test]
+    fn test_scale_cover_resamples() {
+        let img = create_test_image(30, 20);
+        let result = scale(
+            &img,
+            50,
+            50,
+            ScaleMode::Cover,
+            ResizeFilter::Lanczos,
+            Rgba([0, 0, 0, 0]),
+        )
+        .unwrap();
+        assert_eq!(result.dimensions(), (50, 50));
+        assert!(scale(
+            &img,
+            0,
+            50,
+            ScaleMode::Cover,
+            ResizeFilter::Lanczos,
+            Rgba([0; 4])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_resize_pad_zero_dimension() {
+        let img = create_test_image(100, 100);
+        let result = resize_pad(&img, 0, 100, ResizeFilter::Lanczos, Rgba([0, 0, 0, 255]));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_upscale_warning_large_scale() {
+        let warning = upscale_warning((10, 10), (100, 100)).unwrap();
+        assert_eq!(warning.code, "LARGE_UPSCALE");
+        assert!(warning.message.contains("10.0x"));
+    }
+
+    #[test]
+    fn test_upscale_warning_moderate_scale() {
+        assert!(upscale_warning((10, 10), (20, 20)).is_none());
+        assert!(upscale_warning((100, 100), (10, 10)).is_none());
+    }
+
+    #[test]
+    fn test_resize_lanczos_windows() {
+        // Mid-gray step edge so ringing overshoot isn't hidden by clamping
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(100, 10, |x, _| {
+            let v = if x < 50 { 64 } else { 192 };
+            Rgba([v, v, v, 255])
+        }));
+
+        let a2 = resize_lanczos(&img, Some(30), Some(10), None, 2).unwrap();
+        let a3 = resize_lanczos(&img, Some(30), Some(10), None, 3).unwrap();
+        assert_eq!((a2.width(), a2.height()), (30, 10));
+        assert_eq!((a3.width(), a3.height()), (30, 10));
+
+        let row = |img: &DynamicImage| -> Vec<u8> {
+            let rgba = img.to_rgba8();
+            (0..30).map(|x| rgba.get_pixel(x, 5)[0]).collect()
+        };
+        let (row2, row3) = (row(&a2), row(&a3));
+        assert_ne!(row2, row3);
+
+        // Both ring past the original levels near the edge, by different amounts
+        let overshoot = |row: &[u8]| row.iter().map(|&v| v.saturating_sub(192)).max().unwrap();
+        assert!(overshoot(&row2) > 0);
+        assert!(overshoot(&row3) > 0);
+        assert_ne!(overshoot(&row2), overshoot(&row3));
+
+        // Flat regions away from the edge are preserved
+        assert!((row2[0] as i32 - 64).abs() <= 1);
+        assert!((row3[29] as i32 - 192).abs() <= 1);
+    }
+
+    #[test]
+    fn test_resize_lanczos_invalid_window() {
+        let img = create_test_image(100, 100);
+        assert!(resize_lanczos(&img, Some(50), None, None, 4).is_err());
+    }
+
     #[test]
     fn test_resize_all_filters() {
         let img = create_test_image(100, 100);
@@ -266,6 +1067,9 @@ mod tests {
             ResizeFilter::Linear,
             ResizeFilter::Cubic,
             ResizeFilter::Lanczos,
+            ResizeFilter::Lanczos2,
+            ResizeFilter::Mitchell,
+            ResizeFilter::Area,
         ];
 
         for filter in filters {
@@ -274,4 +1078,201 @@ mod tests {
             assert_eq!(result.height(), 50);
         }
     }
+
+    #[test]
+    fn test_custom_filters_differ_from_lanczos3() {
+        // One-pixel vertical stripes: the worst case for resampling filters
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(64, 8, |x, _| {
+            let v = if x % 2 == 0 { 0 } else { 255 };
+            Rgba([v, v, v, 255])
+        }));
+        let resized = |filter| {
+            resize(&img, Some(27), Some(8), None, filter)
+                .unwrap()
+                .to_rgba8()
+        };
+
+        let lanczos3 = resized(ResizeFilter::Lanczos);
+        for filter in [ResizeFilter::Lanczos2, ResizeFilter::Mitchell] {
+            let result = resized(filter);
+            assert_eq!(result.dimensions(), (27, 8));
+            assert_ne!(result, lanczos3, "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn test_area_downscale_averages_checkerboard() {
+        // One-pixel checkerboard: every 10x10 block holds equal black and white
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(100, 100, |x, y| {
+            let v = if (x + y) % 2 == 0 { 0 } else { 255 };
+            Rgba([v, v, v, 255])
+        }));
+
+        let result = resize(&img, Some(10), Some(10), None, ResizeFilter::Area)
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(result.dimensions(), (10, 10));
+        for pixel in result.pixels() {
+            assert!(pixel[0].abs_diff(128) <= 1, "{:?}", pixel);
+            assert_eq!(pixel[3], 255);
+        }
+
+        // Point sampling aliases the same pattern to solid black or white
+        let nearest = resize(&img, Some(10), Some(10), None, ResizeFilter::Nearest)
+            .unwrap()
+            .to_rgba8();
+        assert!(nearest.pixels().all(|p| p[0] == 0 || p[0] == 255));
+    }
+
+    #[test]
+    fn test_area_contributions_cover_source() {
+        // A fractional ratio still gives weights that sum to 1 over the span
+        for contrib in area_contributions(10, 3) {
+            let sum: f32 = contrib.weights.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-5);
+        }
+        let upscaled = area_contributions(2, 4);
+        assert_eq!(upscaled[1].start, 0);
+        assert_eq!(upscaled[2].start, 1);
+    }
+
+    #[test]
+    fn test_auto_filter_policy() {
+        assert_eq!(auto_filter((10, 10), (20, 30)), ResizeFilter::Nearest);
+        assert_eq!(auto_filter((10, 10), (10, 10)), ResizeFilter::Nearest);
+        assert_eq!(auto_filter((10, 10), (25, 20)), ResizeFilter::Lanczos);
+        assert_eq!(auto_filter((10, 10), (5, 5)), ResizeFilter::Lanczos);
+    }
+
+    #[test]
+    fn test_auto_filter_keeps_pixel_art_crisp() {
+        let black = Rgba([0, 0, 0, 255]);
+        let white = Rgba([255, 255, 255, 255]);
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(8, 8, |x, y| {
+            if (x + y) % 2 == 0 {
+                black
+            } else {
+                white
+            }
+        }));
+
+        // A 2x upscale keeps only the two colors
+        let up = resize(&img, None, None, Some(2.0), ResizeFilter::Auto)
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(up.dimensions(), (16, 16));
+        assert!(up.pixels().all(|p| *p == black || *p == white));
+
+        // A downscale interpolates
+        let down = resize(&img, Some(5), Some(5), None, ResizeFilter::Auto)
+            .unwrap()
+            .to_rgba8();
+        assert!(down.pixels().any(|p| p[0] > 10 && p[0] < 245));
+    }
+
+    #[test]
+    fn test_resize_per_axis_filters() {
+        // Alternating black and white columns and rows
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_fn(4, 4, |x, y| {
+            let v = if (x + y) % 2 == 0 { 0 } else { 255 };
+            Rgba([v, v, v, 255])
+        }));
+        let result = resize_per_axis(
+            &img,
+            Some(32),
+            Some(32),
+            None,
+            ResizeFilter::Lanczos,
+            ResizeFilter::Nearest,
+        )
+        .unwrap()
+        .to_rgba8();
+        assert_eq!(result.dimensions(), (32, 32));
+
+        // Along x the values ramp through intermediate grays
+        let row: Vec<u8> = (0..32).map(|x| result.get_pixel(x, 12)[0]).collect();
+        assert!(row.iter().any(|&v| v > 40 && v < 215), "{:?}", row);
+
+        // Along y each source row is repeated 8 times, then steps sharply
+        for x in [4, 12, 20] {
+            for y in 0..32 {
+                let band_start = y / 8 * 8;
+                assert_eq!(result.get_pixel(x, y), result.get_pixel(x, band_start));
+            }
+            assert_ne!(result.get_pixel(x, 7), result.get_pixel(x, 8));
+        }
+    }
+
+    #[test]
+    fn test_per_axis_matches_single_filter() {
+        let img = create_test_image(40, 30);
+        let both = resize_per_axis(
+            &img,
+            Some(20),
+            Some(15),
+            None,
+            ResizeFilter::Mitchell,
+            ResizeFilter::Mitchell,
+        )
+        .unwrap();
+        let single = resize(&img, Some(20), Some(15), None, ResizeFilter::Mitchell).unwrap();
+        assert_eq!(both.to_rgba8(), single.to_rgba8());
+    }
+
+    #[test]
+    fn test_mitchell_kernel() {
+        // Interpolating at the center, zero beyond the support, weights sum to 1
+        assert!((mitchell(0.0) - 8.0 / 9.0).abs() < 1e-6);
+        assert_eq!(mitchell(2.0), 0.0);
+        let sum: f32 = (-2..=2).map(|i| mitchell(i as f32 + 0.25)).sum();
+        assert!((sum - 1.0).abs() < 1e-5);
+    }
+
+    /// Alternating 1px black and white columns
+    fn create_stripes(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(ImageBuffer::from_fn(width, height, |x, _| {
+            let v = if x % 2 == 0 { 0 } else { 255 };
+            Rgba([v, v, v, 255])
+        }))
+    }
+
+    #[test]
+    fn test_in_linear_light_keeps_brightness() {
+        let img = create_stripes(32, 8);
+        let halve = |img: &DynamicImage| resize(img, Some(16), Some(4), None, ResizeFilter::Area);
+
+        let naive = halve(&img).unwrap().to_rgba8();
+        assert_eq!(naive.get_pixel(8, 2)[0].abs_diff(128), 0);
+
+        for filter in [
+            ResizeFilter::Area,
+            ResizeFilter::Lanczos,
+            ResizeFilter::Mitchell,
+        ] {
+            let linear = in_linear_light(&img, |img| resize(img, Some(16), Some(4), None, filter))
+                .unwrap()
+                .to_rgba8();
+            let center = linear.get_pixel(8, 2);
+            assert!(center[0].abs_diff(188) <= 1, "{:?}: {:?}", filter, center);
+            assert_eq!(center[3], 255);
+        }
+    }
+
+    #[test]
+    fn test_in_linear_light_round_trips_and_keeps_depth() {
+        let img = create_test_image(8, 8);
+        let same = in_linear_light(&img, |img| Ok(img.clone())).unwrap();
+        assert_eq!(same.to_rgba8(), img.to_rgba8());
+
+        let deep =
+            DynamicImage::ImageRgb16(ImageBuffer::from_pixel(8, 8, image::Rgb([0, 30000, 65535])));
+        let result = in_linear_light(&deep, |img| {
+            resize(img, Some(4), None, None, ResizeFilter::Lanczos)
+        })
+        .unwrap();
+        assert_eq!(result.color(), image::ColorType::Rgba16);
+        assert_eq!(result.dimensions(), (4, 4));
+        let pixel = result.to_rgba16().get_pixel(2, 2).0;
+        assert!(pixel[1].abs_diff(30000) <= 4, "{:?}", pixel);
+    }
 }
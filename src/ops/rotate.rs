@@ -1,5 +1,7 @@
-use crate::error::Result;
-use image::{DynamicImage, Rgba};
+use crate::cli::args::BlendMode;
+use crate::error::{ImgEditError, Result};
+use crate::ops::canvas::{composite, tile};
+use image::{DynamicImage, ImageBuffer, Rgba, Rgba32FImage, RgbaImage};
 
 /// Rotate an image by the specified degrees (counter-clockwise)
 pub fn rotate(
@@ -53,40 +55,168 @@ pub fn rotate(
             }
         }
 
-        // Rotate around new center
-        let rotated = imageproc::geometric_transformations::rotate_about_center(
-            &canvas,
-            -radians as f32, // Negative because we want counter-clockwise
-            imageproc::geometric_transformations::Interpolation::Bilinear,
-            background,
-        );
+        // Rotate around new center; negative because we want counter-clockwise
+        let rotated = rotate_premultiplied(&canvas, -radians as f32, background);
 
         Ok(DynamicImage::ImageRgba8(rotated))
     } else {
         // Rotate without expanding - clips to original size
         let radians = normalized.to_radians();
 
-        let rotated = imageproc::geometric_transformations::rotate_about_center(
-            &rgba_img,
-            -radians as f32,
-            imageproc::geometric_transformations::Interpolation::Bilinear,
-            background,
-        );
+        let rotated = rotate_premultiplied(&rgba_img, -radians as f32, background);
 
         Ok(DynamicImage::ImageRgba8(rotated))
     }
 }
 
+/// Bilinear rotation about the center in premultiplied alpha, so transparent
+/// neighbors (including a transparent `background`) only fade a pixel's
+/// coverage and never bleed their RGB into its color
+fn rotate_premultiplied(img: &RgbaImage, theta: f32, background: Rgba<u8>) -> RgbaImage {
+    let premultiply = |p: &Rgba<u8>| {
+        let a = p[3] as f32 / 255.0;
+        Rgba([p[0] as f32 * a, p[1] as f32 * a, p[2] as f32 * a, a])
+    };
+
+    let premultiplied: Rgba32FImage = ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+        premultiply(img.get_pixel(x, y))
+    });
+    let rotated = imageproc::geometric_transformations::rotate_about_center(
+        &premultiplied,
+        theta,
+        imageproc::geometric_transformations::Interpolation::Bilinear,
+        premultiply(&background),
+    );
+
+    ImageBuffer::from_fn(rotated.width(), rotated.height(), |x, y| {
+        let p = rotated.get_pixel(x, y);
+        let alpha = (p[3] * 255.0).round();
+        if alpha <= 0.0 {
+            return Rgba([0, 0, 0, 0]);
+        }
+        let channel = |c: f32| (c / p[3]).round().clamp(0.0, 255.0) as u8;
+        Rgba([
+            channel(p[0]),
+            channel(p[1]),
+            channel(p[2]),
+            alpha.min(255.0) as u8,
+        ])
+    })
+}
+
+/// Rotate like [`rotate`], filling revealed areas with `texture` tiled across
+/// the result instead of a solid color
+pub fn rotate_textured(
+    img: &DynamicImage,
+    degrees: f64,
+    expand: bool,
+    texture: &DynamicImage,
+) -> Result<DynamicImage> {
+    let rotated = rotate(img, degrees, expand, Rgba([0, 0, 0, 0]))?;
+    let background = tile(texture, rotated.width(), rotated.height())?;
+    composite(&background, &rotated, 0, 0, None, 1.0, BlendMode::Normal)
+}
+
+/// Largest dimension the skew estimator works at; bigger images are downscaled first
+const SKEW_ANALYSIS_SIZE: u32 = 800;
+
+/// Angular resolution of the skew search in degrees
+const SKEW_STEP_DEGREES: f64 = 0.1;
+
+/// Estimate the skew of an image's dominant near-horizontal lines, in degrees
+/// counter-clockwise (the same convention as [`rotate`]), searching within
+/// `±max_angle`. Rotating by the negated result straightens the image.
+///
+/// Edges are detected with Canny, then a Hough-style accumulator is built for
+/// each candidate angle by projecting every edge point onto the line normal.
+/// The angle whose accumulator is most concentrated (largest sum of squared
+/// bin counts) is the one where the detected lines are straight.
+pub fn estimate_skew(img: &DynamicImage, max_angle: f64) -> Result<f64> {
+    if max_angle <= 0.0 || max_angle > 45.0 {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Max angle must be greater than 0 and at most 45 degrees, got {}",
+            max_angle
+        )));
+    }
+
+    let gray = if img.width().max(img.height()) > SKEW_ANALYSIS_SIZE {
+        img.thumbnail(SKEW_ANALYSIS_SIZE, SKEW_ANALYSIS_SIZE)
+            .to_luma8()
+    } else {
+        img.to_luma8()
+    };
+
+    let edges = imageproc::edges::canny(&gray, 50.0, 100.0);
+    let (cx, cy) = (edges.width() as f64 / 2.0, edges.height() as f64 / 2.0);
+    let points: Vec<(f64, f64)> = edges
+        .enumerate_pixels()
+        .filter(|(_, _, p)| p[0] > 0)
+        .map(|(x, y, _)| (x as f64 - cx, y as f64 - cy))
+        .collect();
+
+    if points.is_empty() {
+        return Ok(0.0);
+    }
+
+    let steps = (max_angle / SKEW_STEP_DEGREES).round() as i64;
+    let mut best_angle = 0.0f64;
+    let mut best_score = 0u64;
+
+    for step in -steps..=steps {
+        let angle = step as f64 * SKEW_STEP_DEGREES;
+        let (sin, cos) = angle.to_radians().sin_cos();
+
+        // Distance of each edge point from the center along the normal of a
+        // line tilted by `angle` (image y axis points down)
+        let mut bins = std::collections::HashMap::new();
+        for &(x, y) in &points {
+            let rho = (y * cos + x * sin).round() as i64;
+            *bins.entry(rho).or_insert(0u64) += 1;
+        }
+        let score: u64 = bins.values().map(|c| c * c).sum();
+
+        // Prefer the smallest correction on ties
+        if score > best_score || (score == best_score && angle.abs() < best_angle.abs()) {
+            best_score = score;
+            best_angle = angle;
+        }
+    }
+
+    Ok(best_angle)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use image::ImageBuffer;
 
     fn create_test_image(width: u32, height: u32) -> DynamicImage {
         let img = ImageBuffer::from_fn(width, height, |x, y| Rgba([x as u8, y as u8, 128, 255]));
         DynamicImage::ImageRgba8(img)
     }
 
+    #[test]
+    fn test_rotate_transparent_background_does_not_bleed() {
+        let img =
+            DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(40, 40, Rgba([255, 0, 0, 255])));
+        let result = rotate(&img, 30.0, true, Rgba([0, 0, 0, 0]))
+            .unwrap()
+            .to_rgba8();
+
+        // Edge pixels fade out in alpha only; none pick up the black background
+        let mut partial = 0;
+        for p in result.pixels() {
+            match p[3] {
+                0 => {}
+                255 => assert_eq!(p.0, [255, 0, 0, 255]),
+                _ => {
+                    partial += 1;
+                    assert_eq!(&p.0[..3], &[255, 0, 0], "{:?}", p);
+                }
+            }
+        }
+        assert!(partial > 0, "expected antialiased edges");
+    }
+
     #[test]
     fn test_rotate_0_degrees() {
         let img = create_test_image(100, 100);
@@ -146,6 +276,82 @@ mod tests {
         assert_eq!(result.height(), 100);
     }
 
+    fn create_grid_image(size: u32, spacing: u32) -> DynamicImage {
+        let img = ImageBuffer::from_fn(size, size, |x, y| {
+            if x % spacing < 2 || y % spacing < 2 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        DynamicImage::ImageRgba8(img)
+    }
+
+    #[test]
+    fn test_estimate_skew_straight_grid() {
+        let img = create_grid_image(200, 20);
+        let skew = estimate_skew(&img, 10.0).unwrap();
+        assert!(skew.abs() < 0.3, "skew was {}", skew);
+    }
+
+    #[test]
+    fn test_estimate_skew_and_straighten() {
+        let white = Rgba([255, 255, 255, 255]);
+        let skewed = rotate(&create_grid_image(200, 20), 4.0, false, white).unwrap();
+
+        let skew = estimate_skew(&skewed, 10.0).unwrap();
+        assert!((skew - 4.0).abs() < 0.3, "skew was {}", skew);
+
+        let straightened = rotate(&skewed, -skew, false, white).unwrap();
+        let residual = estimate_skew(&straightened, 10.0).unwrap();
+        assert!(residual.abs() < 0.3, "residual skew was {}", residual);
+    }
+
+    #[test]
+    fn test_estimate_skew_negative_angle() {
+        let white = Rgba([255, 255, 255, 255]);
+        let skewed = rotate(&create_grid_image(200, 20), -2.5, false, white).unwrap();
+
+        let skew = estimate_skew(&skewed, 10.0).unwrap();
+        assert!((skew + 2.5).abs() < 0.3, "skew was {}", skew);
+    }
+
+    #[test]
+    fn test_estimate_skew_blank_image() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_pixel(50, 50, Rgba([255; 4])));
+        assert_eq!(estimate_skew(&img, 10.0).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_estimate_skew_invalid_max_angle() {
+        let img = create_grid_image(50, 10);
+        assert!(estimate_skew(&img, 0.0).is_err());
+        assert!(estimate_skew(&img, 60.0).is_err());
+    }
+
+    #[test]
+    fn test_rotate_textured_fills_corners() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_pixel(20, 20, Rgba([255, 0, 0, 255])));
+        let texture = DynamicImage::ImageRgba8(ImageBuffer::from_fn(2, 2, |x, y| {
+            if (x + y) % 2 == 0 {
+                Rgba([0, 0, 255, 255])
+            } else {
+                Rgba([0, 255, 0, 255])
+            }
+        }));
+
+        let result = rotate_textured(&img, 45.0, true, &texture)
+            .unwrap()
+            .to_rgba8();
+
+        // Corners are revealed areas and show the checkered texture
+        assert_eq!(result.get_pixel(0, 0), &Rgba([0, 0, 255, 255]));
+        assert_eq!(result.get_pixel(1, 0), &Rgba([0, 255, 0, 255]));
+        // Center is still the rotated image
+        let (w, h) = result.dimensions();
+        assert_eq!(result.get_pixel(w / 2, h / 2), &Rgba([255, 0, 0, 255]));
+    }
+
     #[test]
     fn test_rotate_45_degrees_with_expand() {
         let img = create_test_image(100, 100);
@@ -0,0 +1,284 @@
+use crate::error::{ImgEditError, Result};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+/// A mutable pixel grid that vertical seams are carved from.
+/// Horizontal seams are handled by transposing, carving and transposing back.
+#[derive(Clone)]
+struct Grid {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgba<u8>>,
+}
+
+impl Grid {
+    fn from_image(img: &RgbaImage) -> Self {
+        Self {
+            width: img.width() as usize,
+            height: img.height() as usize,
+            pixels: img.pixels().copied().collect(),
+        }
+    }
+
+    fn into_image(self) -> RgbaImage {
+        let mut img = RgbaImage::new(self.width as u32, self.height as u32);
+        for (dst, src) in img.pixels_mut().zip(self.pixels) {
+            *dst = src;
+        }
+        img
+    }
+
+    fn get(&self, x: usize, y: usize) -> Rgba<u8> {
+        self.pixels[y * self.width + x]
+    }
+
+    fn transpose(&self) -> Self {
+        let mut pixels = Vec::with_capacity(self.pixels.len());
+        for x in 0..self.width {
+            for y in 0..self.height {
+                pixels.push(self.get(x, y));
+            }
+        }
+        Self {
+            width: self.height,
+            height: self.width,
+            pixels,
+        }
+    }
+
+    fn energy(&self) -> Vec<f32> {
+        gradient_energy(self.width, self.height, &self.pixels)
+    }
+
+    /// Find the lowest-energy 8-connected vertical seam by dynamic programming.
+    /// Returns the seam's x coordinate for each row.
+    fn find_vertical_seam(&self) -> Vec<usize> {
+        let (w, h) = (self.width, self.height);
+        let mut cost = self.energy();
+
+        for y in 1..h {
+            for x in 0..w {
+                let prev = &cost[(y - 1) * w..y * w];
+                let lo = x.saturating_sub(1);
+                let hi = (x + 1).min(w - 1);
+                let best = prev[lo..=hi].iter().copied().fold(f32::INFINITY, f32::min);
+                cost[y * w + x] += best;
+            }
+        }
+
+        let mut seam = vec![0; h];
+        seam[h - 1] = argmin(&cost[(h - 1) * w..h * w], 0);
+        for y in (0..h - 1).rev() {
+            let x = seam[y + 1];
+            let lo = x.saturating_sub(1);
+            let hi = (x + 1).min(w - 1);
+            seam[y] = argmin(&cost[y * w + lo..=y * w + hi], lo);
+        }
+        seam
+    }
+
+    fn remove_vertical_seam(&mut self, seam: &[usize]) {
+        let mut pixels = Vec::with_capacity((self.width - 1) * self.height);
+        for (y, &skip) in seam.iter().enumerate() {
+            let row = &self.pixels[y * self.width..(y + 1) * self.width];
+            pixels.extend_from_slice(&row[..skip]);
+            pixels.extend_from_slice(&row[skip + 1..]);
+        }
+        self.pixels = pixels;
+        self.width -= 1;
+    }
+
+    /// Insert `count` vertical seams (at most the current width).
+    ///
+    /// The seams are found by successively removing them from a copy so that
+    /// each insertion goes through a distinct low-energy path; each chosen
+    /// pixel is then duplicated as the average of itself and its right neighbor.
+    fn insert_vertical_seams(&mut self, count: usize) {
+        let mut work = self.clone();
+        // Original column of each pixel in the working copy
+        let mut origin: Vec<usize> = (0..self.height).flat_map(|_| 0..self.width).collect();
+        let mut duplicate = vec![false; self.pixels.len()];
+
+        for _ in 0..count {
+            let seam = work.find_vertical_seam();
+            let mut next_origin = Vec::with_capacity(origin.len() - work.height);
+            for (y, &skip) in seam.iter().enumerate() {
+                let row = &origin[y * work.width..(y + 1) * work.width];
+                duplicate[y * self.width + row[skip]] = true;
+                next_origin.extend_from_slice(&row[..skip]);
+                next_origin.extend_from_slice(&row[skip + 1..]);
+            }
+            origin = next_origin;
+            work.remove_vertical_seam(&seam);
+        }
+
+        let mut pixels = Vec::with_capacity((self.width + count) * self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let pixel = self.get(x, y);
+                pixels.push(pixel);
+                if duplicate[y * self.width + x] {
+                    let right = self.get((x + 1).min(self.width - 1), y);
+                    pixels.push(average(pixel, right));
+                }
+            }
+        }
+        self.pixels = pixels;
+        self.width += count;
+    }
+
+    /// Carve or grow the grid horizontally to exactly `target` columns
+    fn retarget_width(&mut self, target: usize) {
+        while self.width > target {
+            let seam = self.find_vertical_seam();
+            self.remove_vertical_seam(&seam);
+        }
+        while self.width < target {
+            let count = (target - self.width).min(self.width);
+            self.insert_vertical_seams(count);
+        }
+    }
+}
+
+/// Gradient magnitude of luma for a row-major pixel buffer, using central
+/// differences clamped at the borders
+pub(crate) fn gradient_energy(width: usize, height: usize, pixels: &[Rgba<u8>]) -> Vec<f32> {
+    let luma: Vec<f32> = pixels
+        .iter()
+        .map(|p| 0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32)
+        .collect();
+    let at = |x: usize, y: usize| luma[y * width + x];
+
+    let mut energy = vec![0.0; luma.len()];
+    for y in 0..height {
+        let (up, down) = (y.saturating_sub(1), (y + 1).min(height - 1));
+        for x in 0..width {
+            let (left, right) = (x.saturating_sub(1), (x + 1).min(width - 1));
+            let dx = at(right, y) - at(left, y);
+            let dy = at(x, down) - at(x, up);
+            energy[y * width + x] = (dx * dx + dy * dy).sqrt();
+        }
+    }
+    energy
+}
+
+fn argmin(values: &[f32], offset: usize) -> usize {
+    let mut best = 0;
+    for (i, &v) in values.iter().enumerate() {
+        if v < values[best] {
+            best = i;
+        }
+    }
+    offset + best
+}
+
+fn average(a: Rgba<u8>, b: Rgba<u8>) -> Rgba<u8> {
+    Rgba(std::array::from_fn(|i| {
+        (a[i] as u16 + b[i] as u16).div_ceil(2) as u8
+    }))
+}
+
+/// Content-aware resize by removing or inserting low-energy seams.
+///
+/// Vertical seams are carved first to reach `width`, then horizontal seams to
+/// reach `height`. Regions with strong gradients are preserved at the expense
+/// of flat areas, so the aspect of important content is kept without cropping.
+pub fn seam_carve(img: &DynamicImage, width: u32, height: u32) -> Result<DynamicImage> {
+    if width == 0 || height == 0 {
+        return Err(ImgEditError::InvalidParameter(
+            "Width and height must be greater than 0".to_string(),
+        ));
+    }
+
+    let mut grid = Grid::from_image(&img.to_rgba8());
+    grid.retarget_width(width as usize);
+
+    if grid.height != height as usize {
+        let mut transposed = grid.transpose();
+        transposed.retarget_width(height as usize);
+        grid = transposed.transpose();
+    }
+
+    Ok(DynamicImage::ImageRgba8(grid.into_image()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, ImageBuffer};
+
+    fn create_flat_with_square(width: u32, height: u32) -> DynamicImage {
+        // White background with a black 20x20 square in the middle
+        let img = ImageBuffer::from_fn(width, height, |x, y| {
+            let cx = width / 2;
+            let cy = height / 2;
+            if x.abs_diff(cx) < 10 && y.abs_diff(cy) < 10 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([255, 255, 255, 255])
+            }
+        });
+        DynamicImage::ImageRgba8(img)
+    }
+
+    fn count_dark(img: &DynamicImage) -> usize {
+        img.pixels().filter(|(_, _, p)| p[0] < 128).count()
+    }
+
+    #[test]
+    fn test_seam_carve_reduce_width() {
+        let img = create_flat_with_square(100, 100);
+        let result = seam_carve(&img, 80, 100).unwrap();
+        assert_eq!(result.dimensions(), (80, 100));
+        // The low-energy background is removed; the square is untouched
+        assert_eq!(count_dark(&result), count_dark(&img));
+    }
+
+    #[test]
+    fn test_seam_carve_reduce_height() {
+        let img = create_flat_with_square(100, 100);
+        let result = seam_carve(&img, 100, 70).unwrap();
+        assert_eq!(result.dimensions(), (100, 70));
+        assert_eq!(count_dark(&result), count_dark(&img));
+    }
+
+    #[test]
+    fn test_seam_carve_flat_image_stays_flat() {
+        let img =
+            DynamicImage::ImageRgba8(ImageBuffer::from_pixel(100, 100, Rgba([40, 120, 200, 255])));
+        let result = seam_carve(&img, 80, 90).unwrap();
+        assert_eq!(result.dimensions(), (80, 90));
+        for (_, _, p) in result.pixels() {
+            assert_eq!(p, Rgba([40, 120, 200, 255]));
+        }
+    }
+
+    #[test]
+    fn test_seam_carve_enlarge() {
+        let img = create_flat_with_square(50, 40);
+        let result = seam_carve(&img, 120, 60).unwrap();
+        assert_eq!(result.dimensions(), (120, 60));
+    }
+
+    #[test]
+    fn test_seam_carve_zero_dimension() {
+        let img = create_flat_with_square(20, 20);
+        assert!(seam_carve(&img, 0, 20).is_err());
+        assert!(seam_carve(&img, 20, 0).is_err());
+    }
+
+    #[test]
+    fn test_find_vertical_seam_follows_low_energy() {
+        // A horizontal ramp with a flat band at x = 5..=7; the band center has
+        // zero gradient and should attract the whole seam
+        let img = RgbaImage::from_fn(12, 6, |x, _| {
+            let v = if (5..=7).contains(&x) {
+                200
+            } else {
+                (x * 20) as u8
+            };
+            Rgba([v, v, v, 255])
+        });
+        let seam = Grid::from_image(&img).find_vertical_seam();
+        assert_eq!(seam, vec![6; 6]);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::error::{ImgEditError, Result};
+use crate::ops::seam::gradient_energy;
+use image::{DynamicImage, GenericImageView};
+
+/// Top-left corner for a `width` x `height` crop over the most detailed region.
+///
+/// Detail is measured as luma gradient energy. The window holding the most
+/// energy is found, then recentered on that energy's centroid; an image with
+/// no detail keeps the centered window.
+pub fn smart_crop_position(img: &DynamicImage, width: u32, height: u32) -> Result<(u32, u32)> {
+    let (img_width, img_height) = img.dimensions();
+
+    if width == 0 || height == 0 {
+        return Err(ImgEditError::InvalidDimensions(
+            "Crop width and height must be greater than 0".to_string(),
+        ));
+    }
+    if width > img_width || height > img_height {
+        return Err(ImgEditError::CropOutOfBounds(format!(
+            "Crop size {}x{} exceeds image bounds {}x{}",
+            width, height, img_width, img_height
+        )));
+    }
+
+    let rgba = img.to_rgba8();
+    let (w, h) = (img_width as usize, img_height as usize);
+    let pixels: Vec<_> = rgba.pixels().copied().collect();
+    let energy = gradient_energy(w, h, &pixels);
+
+    // Summed-area table with a zero row and column, so any window sum is four lookups
+    let stride = w + 1;
+    let mut table = vec![0.0f64; stride * (h + 1)];
+    for y in 0..h {
+        let mut row = 0.0;
+        for x in 0..w {
+            row += energy[y * w + x] as f64;
+            table[(y + 1) * stride + x + 1] = table[y * stride + x + 1] + row;
+        }
+    }
+
+    let (cw, ch) = (width as usize, height as usize);
+    let window_sum = |x: usize, y: usize| {
+        table[(y + ch) * stride + x + cw]
+            - table[y * stride + x + cw]
+            - table[(y + ch) * stride + x]
+            + table[y * stride + x]
+    };
+
+    // Start from the centered window so a flat image keeps a plain center crop
+    let mut best = ((w - cw) / 2, (h - ch) / 2);
+    let mut best_sum = window_sum(best.0, best.1);
+    for y in 0..=(h - ch) {
+        for x in 0..=(w - cw) {
+            let sum = window_sum(x, y);
+            if sum > best_sum {
+                best_sum = sum;
+                best = (x, y);
+            }
+        }
+    }
+
+    // Many windows can hold the same detail; recenter on the energy centroid
+    // of the chosen one so the detail sits in the middle where the bounds allow
+    if best_sum > 0.0 {
+        let (mut sum_x, mut sum_y) = (0.0, 0.0);
+        for y in best.1..best.1 + ch {
+            for x in best.0..best.0 + cw {
+                let e = energy[y * w + x] as f64;
+                sum_x += e * x as f64;
+                sum_y += e * y as f64;
+            }
+        }
+        let centroid_x = (sum_x / best_sum).round() as usize;
+        let centroid_y = (sum_y / best_sum).round() as usize;
+        best = (
+            centroid_x.saturating_sub(cw / 2).min(w - cw),
+            centroid_y.saturating_sub(ch / 2).min(h - ch),
+        );
+    }
+
+    Ok((best.0 as u32, best.1 as u32))
+}
+
+/// Crop to `width` x `height` around the most detailed region of the image
+/// rather than its geometric center
+pub fn smart_crop(img: &DynamicImage, width: u32, height: u32) -> Result<DynamicImage> {
+    let (x, y) = smart_crop_position(img, width, height)?;
+    Ok(img.crop_imm(x, y, width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgba};
+
+    /// Flat gray image with a checkerboard blob centered at (`cx`, `cy`)
+    fn create_image_with_blob(width: u32, height: u32, cx: u32, cy: u32) -> DynamicImage {
+        DynamicImage::ImageRgba8(ImageBuffer::from_fn(width, height, |x, y| {
+            if x.abs_diff(cx) < 10 && y.abs_diff(cy) < 10 && (x / 2 + y / 2) % 2 == 0 {
+                Rgba([0, 0, 0, 255])
+            } else {
+                Rgba([128, 128, 128, 255])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_smart_crop_follows_detail() {
+        let img = create_image_with_blob(200, 150, 170, 25);
+        let (x, y) = smart_crop_position(&img, 60, 60).unwrap();
+
+        // The window center lands near the blob, not the image center (100, 75)
+        let (center_x, center_y) = (x + 30, y + 30);
+        assert!(center_x.abs_diff(170) <= 12, "center x {}", center_x);
+        assert!(center_y.abs_diff(25) <= 12, "center y {}", center_y);
+
+        let cropped = smart_crop(&img, 60, 60).unwrap();
+        assert_eq!(cropped.dimensions(), (60, 60));
+    }
+
+    #[test]
+    fn test_smart_crop_flat_image_uses_center() {
+        let img = DynamicImage::ImageRgba8(ImageBuffer::from_pixel(100, 80, Rgba([50; 4])));
+        assert_eq!(smart_crop_position(&img, 40, 40).unwrap(), (30, 20));
+    }
+
+    #[test]
+    fn test_smart_crop_invalid_size() {
+        let img = create_image_with_blob(50, 50, 25, 25);
+        assert!(smart_crop(&img, 0, 10).is_err());
+        assert!(smart_crop(&img, 60, 10).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use crate::error::{ImgEditError, Result};
+use crate::ops::adjust::PointOp;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Rows decoded, adjusted and written at a time
+const STRIP_ROWS: usize = 64;
+
+fn png_decoder(path: &Path) -> Option<png::Decoder<BufReader<File>>> {
+    let file = File::open(path).ok()?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    // Palettes, low bit depths and tRNS become plain 8-bit channels, as when
+    // the `image` crate decodes the file
+    decoder.set_transformations(png::Transformations::EXPAND);
+    Some(decoder)
+}
+
+/// The dimensions of `path` if [`stream_point_op`] can apply `op` to it: a
+/// non-interlaced, non-animated PNG with 8 bits per channel once expanded,
+/// with an alpha channel when `op` changes alpha (the whole-image path adds
+/// one). Anything else needs the whole image in memory.
+pub fn streamable_png(path: &Path, op: PointOp) -> Option<(u32, u32)> {
+    let reader = png_decoder(path)?.read_info().ok()?;
+    let info = reader.info();
+    let (color_type, bit_depth) = reader.output_color_type();
+    let alpha_ok = !op.affects_alpha()
+        || matches!(
+            color_type,
+            png::ColorType::GrayscaleAlpha | png::ColorType::Rgba
+        );
+    (bit_depth == png::BitDepth::Eight
+        && alpha_ok
+        && !info.interlaced
+        && info.animation_control.is_none())
+    .then_some((info.width, info.height))
+}
+
+/// Apply `op` to the PNG at `input` and write the result to the PNG at
+/// `output`, holding only a strip of rows in memory at a time.
+///
+/// The pixels written are the same as [`PointOp::apply`] followed by a save;
+/// the channel layout of the input is kept. Check the input with
+/// [`streamable_png`] first.
+pub fn stream_point_op(input: &Path, output: &Path, op: PointOp) -> Result<()> {
+    let lut = op.lut()?;
+    let read_error = |reason: String| ImgEditError::ReadError {
+        path: input.display().to_string(),
+        reason,
+    };
+    let write_error = |reason: String| ImgEditError::WriteError {
+        path: output.display().to_string(),
+        reason,
+    };
+
+    let decoder = png_decoder(input)
+        .ok_or_else(|| ImgEditError::InputNotFound(input.display().to_string()))?;
+    let mut reader = decoder.read_info().map_err(|e| read_error(e.to_string()))?;
+    let (color_type, bit_depth) = reader.output_color_type();
+    let (width, height) = (reader.info().width, reader.info().height);
+    if bit_depth != png::BitDepth::Eight || reader.info().interlaced {
+        return Err(ImgEditError::UnsupportedFormat(format!(
+            "{} cannot be processed in strips; it must be 8-bit and not interlaced",
+            input.display()
+        )));
+    }
+
+    let channels = color_type.samples();
+    let has_alpha = matches!(
+        color_type,
+        png::ColorType::GrayscaleAlpha | png::ColorType::Rgba
+    );
+    let adjusted =
+        |channel: usize| !has_alpha || channel % channels != channels - 1 || op.affects_alpha();
+
+    let file = File::create(output).map_err(|e| write_error(e.to_string()))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(color_type);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| write_error(e.to_string()))?;
+    let mut stream = writer
+        .stream_writer()
+        .map_err(|e| write_error(e.to_string()))?;
+
+    let row_len = width as usize * channels;
+    let mut strip = Vec::with_capacity(row_len * STRIP_ROWS);
+    let mut rows_left = height as usize;
+    while rows_left > 0 {
+        strip.clear();
+        for _ in 0..rows_left.min(STRIP_ROWS) {
+            let row = reader
+                .next_row()
+                .map_err(|e| read_error(e.to_string()))?
+                .ok_or_else(|| read_error("image data ended early".to_string()))?;
+            strip.extend_from_slice(row.data());
+        }
+        rows_left -= strip.len() / row_len;
+
+        for (i, value) in strip.iter_mut().enumerate() {
+            if adjusted(i) {
+                *value = lut[*value as usize];
+            }
+        }
+        stream
+            .write_all(&strip)
+            .map_err(|e| write_error(e.to_string()))?;
+    }
+
+    stream.finish().map_err(|e| write_error(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, ImageBuffer, Rgba};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stream_matches_whole_image() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = temp_dir.path().join("input.png");
+        // Taller than one strip, with a partial strip at the end
+        let rgba = DynamicImage::ImageRgba8(ImageBuffer::from_fn(37, 150, |x, y| {
+            Rgba([(x * 7) as u8, (y * 3) as u8, (x * y) as u8, (y + 64) as u8])
+        }));
+        let images = [
+            DynamicImage::ImageLuma8(rgba.to_luma8()),
+            DynamicImage::ImageRgb8(rgba.to_rgb8()),
+            rgba,
+        ];
+
+        let ops = [
+            PointOp::Brightness(-40),
+            PointOp::Contrast(1.8),
+            PointOp::Gamma(0.6),
+            PointOp::Invert {
+                invert_alpha: false,
+            },
+            PointOp::Invert { invert_alpha: true },
+        ];
+        for img in &images {
+            img.save(&input).unwrap();
+            for op in ops {
+                // Inverting alpha on an image without it adds an alpha
+                // channel, which only the whole-image path does
+                let Some(dims) = streamable_png(&input, op) else {
+                    assert!(op.affects_alpha() && !img.color().has_alpha());
+                    continue;
+                };
+                assert_eq!(dims, (37, 150));
+
+                let output = temp_dir.path().join("streamed.png");
+                stream_point_op(&input, &output, op).unwrap();
+                let streamed = image::open(&output).unwrap().to_rgba8();
+                let whole = op.apply(img).unwrap().to_rgba8();
+                assert_eq!(
+                    streamed.as_raw(),
+                    whole.as_raw(),
+                    "{:?} {:?}",
+                    img.color(),
+                    op
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_keeps_gray_layout() {
+        let temp_dir = TempDir::new().unwrap();
+        let input = temp_dir.path().join("gray.png");
+        let output = temp_dir.path().join("output.png");
+        let img = DynamicImage::ImageLuma8(ImageBuffer::from_fn(5, 3, |x, _| {
+            image::Luma([(x * 50) as u8])
+        }));
+        img.save(&input).unwrap();
+
+        stream_point_op(&input, &output, PointOp::Brightness(10)).unwrap();
+        let streamed = image::open(&output).unwrap();
+        assert_eq!(streamed.color(), image::ColorType::L8);
+        assert_eq!(
+            streamed.to_rgba8().as_raw(),
+            PointOp::Brightness(10)
+                .apply(&img)
+                .unwrap()
+                .to_rgba8()
+                .as_raw()
+        );
+    }
+
+    #[test]
+    fn test_streamable_png_rejects_other_inputs() {
+        let temp_dir = TempDir::new().unwrap();
+        let deep = temp_dir.path().join("deep.png");
+        DynamicImage::ImageRgb16(ImageBuffer::new(4, 4))
+            .save(&deep)
+            .unwrap();
+        let jpeg = temp_dir.path().join("photo.jpg");
+        DynamicImage::ImageRgb8(ImageBuffer::new(4, 4))
+            .save(&jpeg)
+            .unwrap();
+
+        let op = PointOp::Brightness(10);
+        assert_eq!(streamable_png(&deep, op), None);
+        assert_eq!(streamable_png(&jpeg, op), None);
+        assert_eq!(
+            streamable_png(&temp_dir.path().join("missing.png"), op),
+            None
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use crate::cli::args::Anchor;
+use crate::error::{ImgEditError, Result};
+use crate::ops::canvas::calculate_anchor_offset;
+use font8x8::UnicodeFonts;
+use image::{DynamicImage, Rgba, RgbaImage};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Width and height of one glyph in the built-in bitmap font, before scaling
+const GLYPH_SIZE: u32 = 8;
+
+/// Size in pixels of `text` rendered with the built-in 8x8 font at `scale`
+pub fn text_size(text: &str, scale: u32) -> (u32, u32) {
+    let chars = text.chars().count() as u32;
+    (chars * GLYPH_SIZE * scale, GLYPH_SIZE * scale)
+}
+
+/// Shorten `text` so it renders no wider than `max_width` pixels at `scale`,
+/// ending it with `~` when characters had to be dropped
+pub fn truncate_text(text: &str, max_width: u32, scale: u32) -> String {
+    let max_chars = (max_width / (GLYPH_SIZE * scale.max(1))) as usize;
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut shortened: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    if max_chars > 0 {
+        shortened.push('~');
+    }
+    shortened
+}
+
+/// Draw `text` with its top-left corner at (x, y) using the built-in 8x8 bitmap
+/// font, each font pixel drawn as a `scale` x `scale` block. Glyphs are
+/// alpha-blended over the image; characters without a glyph are left blank.
+pub fn draw_text(img: &mut RgbaImage, text: &str, x: i64, y: i64, scale: u32, color: Rgba<u8>) {
+    let (width, height) = (img.width() as i64, img.height() as i64);
+    let alpha = color[3] as u32;
+
+    for (i, c) in text.chars().enumerate() {
+        let Some(glyph) = font8x8::BASIC_FONTS
+            .get(c)
+            .or_else(|| font8x8::LATIN_FONTS.get(c))
+        else {
+            continue;
+        };
+        let glyph_x = x + (i as u32 * GLYPH_SIZE * scale) as i64;
+
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..GLYPH_SIZE {
+                // Bit 0 is the leftmost pixel of the row
+                if bits & (1 << col) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let px = glyph_x + (col * scale + dx) as i64;
+                        let py = y + (row as u32 * scale + dy) as i64;
+                        if px < 0 || py < 0 || px >= width || py >= height {
+                            continue;
+                        }
+                        let dst = img.get_pixel_mut(px as u32, py as u32);
+                        for ch in 0..3 {
+                            dst[ch] = ((color[ch] as u32 * alpha + dst[ch] as u32 * (255 - alpha))
+                                / 255) as u8;
+                        }
+                        dst[3] = dst[3].max(color[3]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draw `text` at an anchor position, inset from the edges by `margin` pixels
+pub fn draw_text_anchored(
+    img: &DynamicImage,
+    text: &str,
+    anchor: Anchor,
+    margin: u32,
+    scale: u32,
+    color: Rgba<u8>,
+) -> Result<DynamicImage> {
+    if !(1..=32).contains(&scale) {
+        return Err(ImgEditError::InvalidParameter(format!(
+            "Text scale must be between 1 and 32, got {}",
+            scale
+        )));
+    }
+
+    let mut rgba = img.to_rgba8();
+    let (width, height) = rgba.dimensions();
+    let (text_w, text_h) = text_size(text, scale);
+
+    let (ax, ay) = calculate_anchor_offset(width, height, text_w, text_h, anchor);
+    // Keep the text at least `margin` away from every edge it is anchored to;
+    // if it does not fit, it starts at the margin and is clipped
+    let x = ax
+        .min(width as i64 - text_w as i64 - margin as i64)
+        .max(margin as i64);
+    let y = ay
+        .min(height as i64 - text_h as i64 - margin as i64)
+        .max(margin as i64);
+
+    draw_text(&mut rgba, text, x, y, scale, color);
+    Ok(DynamicImage::ImageRgba8(rgba))
+}
+
+/// A calendar date and time of day, without time zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timestamp {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl Timestamp {
+    /// Parse an EXIF date-time such as `2023:01:01 12:00:00`.
+    /// Surrounding quotes and whitespace are ignored.
+    pub fn from_exif(value: &str) -> Option<Self> {
+        let value = value.trim().trim_matches('"').trim();
+        let (date, time) = value.split_once(' ')?;
+
+        let mut date = date.split(':').map(|p| p.trim().parse::<u32>().ok());
+        let mut time = time.split(':').map(|p| p.trim().parse::<u32>().ok());
+
+        let ts = Timestamp {
+            year: date.next()?? as i64,
+            month: date.next()??,
+            day: date.next()??,
+            hour: time.next()??,
+            minute: time.next()??,
+            second: time.next()??,
+        };
+
+        let valid = (1..=12).contains(&ts.month)
+            && (1..=31).contains(&ts.day)
+            && ts.hour < 24
+            && ts.minute < 60
+            && ts.second < 61;
+        valid.then_some(ts)
+    }
+
+    /// Convert seconds since the Unix epoch to a UTC timestamp
+    pub fn from_unix(secs: i64) -> Self {
+        let days = secs.div_euclid(86_400);
+        let rem = secs.rem_euclid(86_400) as u32;
+
+        // Civil-from-days (Howard Hinnant's algorithm)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+        Timestamp {
+            year,
+            month,
+            day,
+            hour: rem / 3600,
+            minute: rem / 60 % 60,
+            second: rem % 60,
+        }
+    }
+
+    /// The current time in UTC
+    pub fn now() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        Self::from_unix(secs)
+    }
+
+    /// Format using strftime-style specifiers:
+    /// `%Y` year, `%m` month, `%d` day, `%H` hour, `%M` minute, `%S` second, `%%` a literal `%`.
+    /// Unknown specifiers are kept as written.
+    pub fn format(&self, pattern: &str) -> String {
+        let mut out = String::new();
+        let mut chars = pattern.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('Y') => out.push_str(&format!("{:04}", self.year)),
+                Some('m') => out.push_str(&format!("{:02}", self.month)),
+                Some('d') => out.push_str(&format!("{:02}", self.day)),
+                Some('H') => out.push_str(&format!("{:02}", self.hour)),
+                Some('M') => out.push_str(&format!("{:02}", self.minute)),
+                Some('S') => out.push_str(&format!("{:02}", self.second)),
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_size() {
+        assert_eq!(text_size("abc", 1), (24, 8));
+        assert_eq!(text_size("abc", 2), (48, 16));
+        assert_eq!(text_size("", 3), (0, 24));
+    }
+
+    #[test]
+    fn test_truncate_text() {
+        assert_eq!(truncate_text("abc", 24, 1), "abc");
+        assert_eq!(truncate_text("abcdef", 32, 1), "abc~");
+        assert_eq!(truncate_text("abcdef", 32, 2), "a~");
+        assert_eq!(truncate_text("abc", 4, 1), "");
+    }
+
+    #[test]
+    fn test_draw_text_marks_pixels() {
+        let mut img = RgbaImage::from_pixel(20, 10, Rgba([0, 0, 0, 255]));
+        draw_text(&mut img, "H", 1, 1, 1, Rgba([255, 255, 255, 255]));
+
+        let lit = img.pixels().filter(|p| p[0] == 255).count();
+        assert!(lit > 0);
+        // Nothing is drawn outside the glyph cell
+        assert!(img
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p[0] == 255)
+            .all(|(x, y, _)| (1..9).contains(&x) && (1..9).contains(&y)));
+    }
+
+    #[test]
+    fn test_draw_text_clips_at_edges() {
+        let mut img = RgbaImage::from_pixel(4, 4, Rgba([0, 0, 0, 255]));
+        draw_text(&mut img, "WW", -3, -3, 2, Rgba([255, 255, 255, 255]));
+        assert_eq!(img.dimensions(), (4, 4));
+    }
+
+    #[test]
+    fn test_draw_text_anchored_bottom_right() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::from_pixel(100, 50, Rgba([0, 0, 0, 255])));
+        let result = draw_text_anchored(&img, "88", Anchor::BottomRight, 5, 1, Rgba([255; 4]))
+            .unwrap()
+            .to_rgba8();
+
+        // Text box is 16x8 inset by 5: x in 79..95, y in 37..45
+        for (x, y, p) in result.enumerate_pixels() {
+            if p[0] == 255 {
+                assert!(
+                    (79..95).contains(&x) && (37..45).contains(&y),
+                    "({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+        assert!(result.pixels().any(|p| p[0] == 255));
+    }
+
+    #[test]
+    fn test_draw_text_anchored_invalid_scale() {
+        let img = DynamicImage::ImageRgba8(RgbaImage::new(10, 10));
+        assert!(draw_text_anchored(&img, "x", Anchor::Center, 0, 0, Rgba([255; 4])).is_err());
+    }
+
+    #[test]
+    fn test_timestamp_from_exif() {
+        let ts = Timestamp::from_exif("2023:01:02 13:04:05").unwrap();
+        assert_eq!(
+            ts,
+            Timestamp {
+                year: 2023,
+                month: 1,
+                day: 2,
+                hour: 13,
+                minute: 4,
+                second: 5
+            }
+        );
+        assert_eq!(Timestamp::from_exif("\"2023:01:02 13:04:05\""), Some(ts));
+        assert!(Timestamp::from_exif("not a date").is_none());
+        assert!(Timestamp::from_exif("2023:13:02 13:04:05").is_none());
+    }
+
+    #[test]
+    fn test_timestamp_from_unix() {
+        assert_eq!(
+            Timestamp::from_unix(0).format("%Y-%m-%d %H:%M:%S"),
+            "1970-01-01 00:00:00"
+        );
+        assert_eq!(
+            Timestamp::from_unix(951_825_600).format("%Y-%m-%d %H:%M:%S"),
+            "2000-02-29 12:00:00"
+        );
+        assert_eq!(
+            Timestamp::from_unix(-1).format("%Y-%m-%d %H:%M:%S"),
+            "1969-12-31 23:59:59"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_format() {
+        let ts = Timestamp::from_exif("2023:07:04 09:08:07").unwrap();
+        assert_eq!(ts.format("%d/%m/%Y"), "04/07/2023");
+        assert_eq!(ts.format("%H%M%S 100%% %q"), "090807 100% %q");
+    }
+}
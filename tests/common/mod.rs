@@ -37,3 +37,32 @@ pub fn create_test_gray_image(width: u32, height: u32) -> image::GrayImage {
 pub fn save_test_image<P: AsRef<Path>>(img: &RgbaImage, path: P) -> Result<(), image::ImageError> {
     img.save(path)
 }
+
+/// Save an RGB JPEG with the given EXIF fields embedded in an APP1 segment
+#[allow(dead_code)]
+pub fn save_jpeg_with_exif<P: AsRef<Path>>(img: &RgbaImage, path: P, fields: &[exif::Field]) {
+    use std::io::Cursor;
+
+    let mut jpeg = Vec::new();
+    image::DynamicImage::ImageRgba8(img.clone())
+        .to_rgb8()
+        .write_to(&mut Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+        .unwrap();
+
+    let mut writer = exif::experimental::Writer::new();
+    for field in fields {
+        writer.push_field(field);
+    }
+    let mut tiff = Cursor::new(Vec::new());
+    writer.write(&mut tiff, false).unwrap();
+    let tiff = tiff.into_inner();
+
+    // SOI, then APP1 "Exif\0\0" + TIFF, then the rest of the encoded stream
+    let mut out = jpeg[..2].to_vec();
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    out.extend_from_slice(b"Exif\0\0");
+    out.extend_from_slice(&tiff);
+    out.extend_from_slice(&jpeg[2..]);
+    std::fs::write(path, out).unwrap();
+}
@@ -233,6 +233,64 @@ fn test_sharpen_with_params() {
     assert!(output.exists());
 }
 
+#[test]
+fn test_bilateral_with_params() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(50, 50);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "bilateral",
+            "--spatial-sigma",
+            "1.5",
+            "--range-sigma",
+            "25",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(result.status.success());
+    let out_img = image::open(&output).unwrap();
+    assert_eq!((out_img.width(), out_img.height()), (50, 50));
+}
+
+#[test]
+fn test_brightness_low_memory_matches_whole_image() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let whole = temp_dir.path().join("whole.png");
+    let streamed = temp_dir.path().join("streamed.png");
+
+    let img = common::create_test_rgba_image(64, 200);
+    img.save(&input).unwrap();
+
+    let run = |output: &std::path::Path, extra: &[&str]| {
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["--json", "brightness", "--value", "30"])
+            .args(extra)
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success());
+        serde_json::from_slice::<serde_json::Value>(&result.stdout).unwrap()
+    };
+
+    assert!(run(&whole, &[])["details"].get("low_memory").is_none());
+    let json = run(&streamed, &["--low-memory"]);
+    assert_eq!(json["details"]["low_memory"], true);
+    assert_eq!(json["details"]["result_height"], 200);
+
+    let whole = image::open(&whole).unwrap().to_rgba8();
+    let streamed = image::open(&streamed).unwrap().to_rgba8();
+    assert_eq!(whole.as_raw(), streamed.as_raw());
+}
+
 #[test]
 fn test_brightness_json_output() {
     let temp_dir = TempDir::new().unwrap();
@@ -264,3 +322,212 @@ fn test_brightness_json_output() {
     assert_eq!(json["details"]["original_width"], 64);
     assert_eq!(json["details"]["original_height"], 48);
 }
+
+#[test]
+fn test_adjust_matches_chained_commands() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let combined = temp_dir.path().join("combined.png");
+    let step1 = temp_dir.path().join("step1.png");
+    let step2 = temp_dir.path().join("step2.png");
+
+    common::create_test_rgba_image(32, 32).save(&input).unwrap();
+
+    let run = |args: &[&str]| {
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            result.status.success(),
+            "{:?}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+    };
+
+    run(&[
+        "adjust",
+        "--brightness",
+        "-20",
+        "--contrast",
+        "1.5",
+        input.to_str().unwrap(),
+        combined.to_str().unwrap(),
+    ]);
+    run(&[
+        "brightness",
+        "--value",
+        "-20",
+        input.to_str().unwrap(),
+        step1.to_str().unwrap(),
+    ]);
+    run(&[
+        "contrast",
+        "--value",
+        "1.5",
+        step1.to_str().unwrap(),
+        step2.to_str().unwrap(),
+    ]);
+
+    assert_eq!(
+        image::open(&combined).unwrap().to_rgba8(),
+        image::open(&step2).unwrap().to_rgba8()
+    );
+}
+
+#[test]
+fn test_adjust_requires_an_adjustment() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    common::create_test_rgba_image(8, 8).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["adjust", input.to_str().unwrap(), output.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!result.status.success());
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_contrast_clip_warn_reports_highlights() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = image::RgbaImage::from_fn(10, 10, |x, _| {
+        let v = 180 + x as u8 * 5;
+        image::Rgba([v, v, v, 255])
+    });
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "contrast",
+            "--value",
+            "4",
+            "--clip-warn",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    let clipped = &json["details"]["clipped"];
+    assert_eq!(clipped["highlights"]["r"], 100);
+    assert_eq!(clipped["shadows"]["g"], 0);
+    assert_eq!(json["warnings"][0]["code"], "CHANNELS_CLIPPED");
+}
+
+#[test]
+fn test_brightness_clip_warn_text_mode_notes_on_stderr() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = image::RgbaImage::from_fn(4, 4, |_, _| image::Rgba([240, 240, 240, 255]));
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "brightness",
+            "--value",
+            "50",
+            "--clip-warn",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(stderr.contains("48 to white"), "{}", stderr);
+}
+
+#[test]
+fn test_curves_per_channel() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(8, 8, image::Rgba([100, 128, 128, 255]))
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "curves",
+            "--blue",
+            "0,0;128,170;255,255",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["parameters"]["blue"], "0,0;128,170;255,255");
+
+    let pixel = *image::open(&output).unwrap().to_rgba8().get_pixel(4, 4);
+    assert_eq!(pixel, image::Rgba([100, 128, 170, 255]));
+
+    // A malformed curve is attributed to its option
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "curves",
+            "--green",
+            "0,0;0,255",
+            input.to_str().unwrap(),
+            temp_dir.path().join("bad.png").to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["field"], "green");
+}
+
+#[test]
+fn test_white_balance_gray_point() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    // A bluish cast over everything
+    image::RgbaImage::from_pixel(10, 10, image::Rgba([90, 100, 160, 255]))
+        .save(&input)
+        .unwrap();
+
+    let run = |args: &[&str], output: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["--json", "white-balance"])
+            .args(args)
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let result = run(&["--gray-x", "3", "--gray-y", "7"], &output);
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["gains"].as_array().unwrap().len(), 3);
+
+    let pixel = *image::open(&output).unwrap().to_rgba8().get_pixel(0, 0);
+    let spread = pixel.0[..3].iter().max().unwrap() - pixel.0[..3].iter().min().unwrap();
+    assert!(spread <= 1, "{:?}", pixel);
+
+    // The gray point must lie inside the image
+    let result = run(
+        &["--gray-x", "10", "--gray-y", "0"],
+        &temp_dir.path().join("bad.png"),
+    );
+    assert_eq!(result.status.code(), Some(5));
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["field"], "gray-x");
+}
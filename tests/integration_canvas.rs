@@ -132,6 +132,47 @@ fn test_pad_with_color() {
     assert_eq!(center[2], 0); // B
 }
 
+#[test]
+fn test_pad_with_edge_colors() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = image::RgbaImage::from_fn(10, 10, |_, _| image::Rgba([255, 0, 0, 255]));
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "pad",
+            "--all",
+            "5",
+            "--color",
+            "black",
+            "--top-color",
+            "blue",
+            "--left-color",
+            "green",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    // Top border (its corner included) is blue, left border is green,
+    // the edges without an override fall back to --color
+    assert_eq!(out_img.get_pixel(0, 0), &image::Rgba([0, 0, 255, 255]));
+    assert_eq!(out_img.get_pixel(10, 2), &image::Rgba([0, 0, 255, 255]));
+    assert_eq!(out_img.get_pixel(2, 10), &image::Rgba([0, 255, 0, 255]));
+    assert_eq!(out_img.get_pixel(17, 10), &image::Rgba([0, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(10, 17), &image::Rgba([0, 0, 0, 255]));
+}
+
 #[test]
 fn test_pad_with_hex_color() {
     let temp_dir = TempDir::new().unwrap();
@@ -244,6 +285,60 @@ fn test_canvas_expand() {
     assert_eq!(out_img.height(), 100);
 }
 
+#[test]
+fn test_canvas_background_image_tiles_texture() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let texture = temp_dir.path().join("texture.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = image::RgbaImage::from_pixel(10, 10, image::Rgba([255, 0, 0, 255]));
+    img.save(&input).unwrap();
+
+    // 2x2 checker texture
+    let texture_img = image::RgbaImage::from_fn(2, 2, |x, y| {
+        if (x + y) % 2 == 0 {
+            image::Rgba([0, 0, 0, 255])
+        } else {
+            image::Rgba([255, 255, 255, 255])
+        }
+    });
+    texture_img.save(&texture).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "canvas",
+            "--width",
+            "30",
+            "--height",
+            "30",
+            "--background-image",
+            texture.to_str().unwrap(),
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    // Expanded area follows the checker pattern, not a flat color
+    assert_eq!(out_img.get_pixel(0, 0), &image::Rgba([0, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(1, 0), &image::Rgba([255, 255, 255, 255]));
+    assert_eq!(
+        out_img.get_pixel(29, 28),
+        &image::Rgba([255, 255, 255, 255])
+    );
+    assert_eq!(out_img.get_pixel(29, 29), &image::Rgba([0, 0, 0, 255]));
+    // Original stays centered
+    assert_eq!(out_img.get_pixel(15, 15), &image::Rgba([255, 0, 0, 255]));
+}
+
 #[test]
 fn test_canvas_shrink() {
     let temp_dir = TempDir::new().unwrap();
@@ -273,6 +368,34 @@ fn test_canvas_shrink() {
     assert_eq!(out_img.height(), 50);
 }
 
+#[test]
+fn test_canvas_shrink_no_crop_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(100, 100);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "canvas",
+            "--width",
+            "150",
+            "--height",
+            "50",
+            "--no-crop",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(result.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&result.stderr).contains("resize or fit"));
+    assert!(!output.exists());
+}
+
 #[test]
 fn test_canvas_with_anchor_top_left() {
     let temp_dir = TempDir::new().unwrap();
@@ -437,6 +560,323 @@ fn test_composite_basic() {
     assert_eq!(center[1], 255);
 }
 
+#[test]
+fn test_composite_guides_overlay_grid() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().join("base.png");
+    let overlay = temp_dir.path().join("overlay.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(40, 40, image::Rgba([0, 0, 255, 255]))
+        .save(&base)
+        .unwrap();
+    image::RgbaImage::from_pixel(20, 20, image::Rgba([0, 255, 0, 255]))
+        .save(&overlay)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "composite",
+            "--x",
+            "10",
+            "--y",
+            "10",
+            "--guides",
+            "--grid-spacing",
+            "10",
+            "--grid-color",
+            "#FF0000",
+            base.to_str().unwrap(),
+            overlay.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let img = image::open(&output).unwrap().to_rgba8();
+    for line in [0, 10, 20, 30] {
+        assert_eq!(img.get_pixel(line, 15), &image::Rgba([255, 0, 0, 255]));
+        assert_eq!(img.get_pixel(15, line), &image::Rgba([255, 0, 0, 255]));
+    }
+    // Cell interiors keep the composited content
+    assert_eq!(img.get_pixel(15, 15), &image::Rgba([0, 255, 0, 255]));
+    assert_eq!(img.get_pixel(35, 35), &image::Rgba([0, 0, 255, 255]));
+}
+
+#[test]
+fn test_gradient_overlay_fades_top_to_bottom() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(10, 30, image::Rgba([220, 200, 180, 255]))
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "gradient-overlay",
+            "--start-color",
+            "black",
+            "--end-color",
+            "transparent",
+            "--angle",
+            "90",
+            "--opacity",
+            "1.0",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let img = image::open(&output).unwrap().to_rgba8();
+    let top = img.get_pixel(5, 1);
+    assert!(top[0] < 30, "{:?}", top);
+    let bottom = img.get_pixel(5, 29);
+    assert!(bottom[0].abs_diff(220) <= 1, "{:?}", bottom);
+    // Darkening falls off down the image
+    assert!(img.get_pixel(5, 10)[0] < img.get_pixel(5, 20)[0]);
+}
+
+#[test]
+fn test_composite_chroma_key_border() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().join("base.png");
+    let overlay = temp_dir.path().join("overlay.png");
+    let output = temp_dir.path().join("output.png");
+
+    let base_img = image::RgbaImage::from_fn(40, 40, |_, _| image::Rgba([255, 0, 0, 255]));
+    base_img.save(&base).unwrap();
+
+    // Blue square with a 5px slightly noisy green border
+    let overlay_img = image::RgbaImage::from_fn(40, 40, |x, y| {
+        if !(5..35).contains(&x) || !(5..35).contains(&y) {
+            image::Rgba([((x + y) % 10) as u8, 250, 8, 255])
+        } else {
+            image::Rgba([0, 0, 255, 255])
+        }
+    });
+    overlay_img.save(&overlay).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "composite",
+            "--chroma-key",
+            "#00FF00",
+            "--chroma-tolerance",
+            "20",
+            base.to_str().unwrap(),
+            overlay.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+
+    // Keyed-out border shows the red base
+    for (x, y) in [(0, 0), (2, 20), (37, 37), (20, 39)] {
+        assert_eq!(out_img.get_pixel(x, y), &image::Rgba([255, 0, 0, 255]));
+    }
+    // Interior keeps the blue overlay
+    assert_eq!(out_img.get_pixel(20, 20), &image::Rgba([0, 0, 255, 255]));
+}
+
+#[test]
+fn test_composite_chroma_key_mask_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().join("base.png");
+    let overlay = temp_dir.path().join("overlay.png");
+    let output = temp_dir.path().join("output.png");
+    let mask = temp_dir.path().join("mask.png");
+
+    let base_img = image::RgbaImage::from_fn(20, 20, |_, _| image::Rgba([255, 0, 0, 255]));
+    base_img.save(&base).unwrap();
+
+    // Left half green key, right half blue
+    let overlay_img = image::RgbaImage::from_fn(20, 20, |x, _| {
+        if x < 10 {
+            image::Rgba([0, 255, 0, 255])
+        } else {
+            image::Rgba([0, 0, 255, 255])
+        }
+    });
+    overlay_img.save(&overlay).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "composite",
+            "--chroma-key",
+            "#00FF00",
+            "--mask-output",
+            mask.to_str().unwrap(),
+            base.to_str().unwrap(),
+            overlay.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let mask_img = image::open(&mask).unwrap();
+    assert_eq!(mask_img.color(), image::ColorType::L8);
+    let mask_img = mask_img.to_luma8();
+    assert_eq!(mask_img.get_pixel(0, 0)[0], 255);
+    assert_eq!(mask_img.get_pixel(9, 19)[0], 255);
+    assert_eq!(mask_img.get_pixel(10, 0)[0], 0);
+    assert_eq!(mask_img.get_pixel(19, 19)[0], 0);
+}
+
+#[test]
+fn test_composite_mask_output_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().join("base.png");
+    let overlay = temp_dir.path().join("overlay.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(8, 8, image::Rgba([255, 0, 0, 255]))
+        .save(&base)
+        .unwrap();
+    image::RgbaImage::from_pixel(8, 8, image::Rgba([0, 255, 0, 255]))
+        .save(&overlay)
+        .unwrap();
+
+    for mask in [&base, &overlay, &output] {
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args([
+                "--overwrite",
+                "composite",
+                "--chroma-key",
+                "#00FF00",
+                "--mask-output",
+                mask.to_str().unwrap(),
+                base.to_str().unwrap(),
+                overlay.to_str().unwrap(),
+                output.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute command");
+
+        assert!(!result.status.success(), "{:?}", mask);
+        assert!(String::from_utf8_lossy(&result.stderr).contains("--mask-output"));
+    }
+    assert!(!output.exists());
+    assert_eq!(
+        image::open(&base).unwrap().to_rgba8().get_pixel(0, 0)[0],
+        255
+    );
+}
+
+#[test]
+fn test_composite_chroma_key_feather() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().join("base.png");
+    let overlay = temp_dir.path().join("overlay.png");
+    let output = temp_dir.path().join("output.png");
+
+    let base_img = image::RgbaImage::from_fn(40, 20, |_, _| image::Rgba([255, 0, 0, 255]));
+    base_img.save(&base).unwrap();
+
+    // Green key on the left half, blue subject on the right
+    let overlay_img = image::RgbaImage::from_fn(40, 20, |x, _| {
+        if x < 20 {
+            image::Rgba([0, 255, 0, 255])
+        } else {
+            image::Rgba([0, 0, 255, 255])
+        }
+    });
+    overlay_img.save(&overlay).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "composite",
+            "--chroma-key",
+            "#00FF00",
+            "--feather",
+            "3",
+            base.to_str().unwrap(),
+            overlay.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+
+    // Far from the edge: pure base and pure overlay
+    assert_eq!(out_img.get_pixel(2, 10)[0], 255);
+    assert_eq!(out_img.get_pixel(37, 10)[2], 255);
+
+    // Across the edge the red base fades out gradually rather than in one step
+    let reds: Vec<u8> = (15..25).map(|x| out_img.get_pixel(x, 10)[0]).collect();
+    assert!(reds.windows(2).all(|w| w[0] >= w[1]), "{:?}", reds);
+    let intermediate = reds.iter().filter(|&&r| r > 0 && r < 255).count();
+    assert!(intermediate >= 2, "{:?}", reds);
+}
+
+#[test]
+fn test_composite_with_position_offset() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().join("base.png");
+    let overlay = temp_dir.path().join("overlay.png");
+    let output = temp_dir.path().join("output.png");
+
+    let base_img = image::RgbaImage::from_pixel(100, 100, image::Rgba([255, 0, 0, 255]));
+    base_img.save(&base).unwrap();
+    let overlay_img = image::RgbaImage::from_pixel(20, 20, image::Rgba([0, 0, 255, 255]));
+    overlay_img.save(&overlay).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "composite",
+            "--position",
+            "bottom-right-10-10",
+            base.to_str().unwrap(),
+            overlay.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    // Overlay occupies 70..90 on both axes
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out_img.get_pixel(70, 70), &image::Rgba([0, 0, 255, 255]));
+    assert_eq!(out_img.get_pixel(89, 89), &image::Rgba([0, 0, 255, 255]));
+    assert_eq!(out_img.get_pixel(90, 90), &image::Rgba([255, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(69, 69), &image::Rgba([255, 0, 0, 255]));
+}
+
 #[test]
 fn test_composite_with_anchor() {
     let temp_dir = TempDir::new().unwrap();
@@ -684,3 +1124,501 @@ fn test_composite_overlay_completely_outside() {
     assert!(result.status.success());
     // Should succeed but overlay won't be visible
 }
+
+#[test]
+fn test_composite_reports_visible_fraction() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().join("base.png");
+    let overlay = temp_dir.path().join("overlay.png");
+
+    common::create_test_rgba_image(50, 50).save(&base).unwrap();
+    common::create_test_rgba_image(20, 20)
+        .save(&overlay)
+        .unwrap();
+
+    let fraction = |x: &str, output: &str| {
+        let output = temp_dir.path().join(output);
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["--json", "composite", "--x", x, "--y", "10"])
+            .args([
+                base.to_str().unwrap(),
+                overlay.to_str().unwrap(),
+                output.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+        json["details"]["overlay_visible_fraction"]
+            .as_f64()
+            .unwrap()
+    };
+
+    // Half the overlay hangs off the right edge
+    assert!((fraction("40", "half.png") - 0.5).abs() < 1e-9);
+    assert_eq!(fraction("200", "none.png"), 0.0);
+    assert_eq!(fraction("5", "all.png"), 1.0);
+}
+
+#[test]
+fn test_frame_two_tone() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = image::RgbaImage::from_fn(10, 10, |_, _| image::Rgba([0, 0, 255, 255]));
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "frame",
+            "--width",
+            "5",
+            "--color",
+            "red",
+            "--inner-width",
+            "2",
+            "--inner-color",
+            "white",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["result_width"], 24);
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out_img.get_pixel(0, 0), &image::Rgba([255, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(5, 12), &image::Rgba([255, 255, 255, 255]));
+    assert_eq!(out_img.get_pixel(12, 12), &image::Rgba([0, 0, 255, 255]));
+}
+
+#[test]
+fn test_frame_zero_width_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    common::create_test_rgba_image(10, 10).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "frame",
+            "--width",
+            "0",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+}
+
+#[test]
+fn test_shadow_behind_opaque_square() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.jpg");
+    let output = temp_dir.path().join("output.png");
+
+    // No alpha channel in the input; the output still gets a transparent surround
+    let img = image::RgbImage::from_pixel(20, 20, image::Rgb([255, 255, 255]));
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "shadow",
+            "--offset-x",
+            "10",
+            "--offset-y",
+            "10",
+            "--blur",
+            "5",
+            "--color",
+            "black",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["result_width"], 40);
+    assert_eq!(json["details"]["result_height"], 40);
+
+    let out_img = image::open(&output).unwrap();
+    assert!(out_img.color().has_alpha());
+    let out_img = out_img.to_rgba8();
+    // The square is unchanged at the blur margin
+    assert_eq!(
+        out_img.get_pixel(10, 10),
+        &image::Rgba([255, 255, 255, 255])
+    );
+    // A darker region sits below-right of the square
+    let shade = out_img.get_pixel(30, 30);
+    assert!(shade[3] > 200 && shade[0] < 20, "{:?}", shade);
+    assert_eq!(out_img.get_pixel(0, 39)[3], 0);
+}
+
+#[test]
+fn test_flood_remove_clears_connected_background() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    // White background, blue square with a white hole in the middle
+    let img = image::RgbImage::from_fn(30, 30, |x, y| {
+        let in_square = (10..20).contains(&x) && (10..20).contains(&y);
+        let in_hole = (13..17).contains(&x) && (13..17).contains(&y);
+        if in_square && !in_hole {
+            image::Rgb([0, 0, 255])
+        } else {
+            image::Rgb([255, 255, 255])
+        }
+    });
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "flood-remove",
+            "--x",
+            "29",
+            "--y",
+            "0",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out_img.get_pixel(0, 0)[3], 0);
+    assert_eq!(out_img.get_pixel(12, 12), &image::Rgba([0, 0, 255, 255]));
+    assert_eq!(
+        out_img.get_pixel(15, 15),
+        &image::Rgba([255, 255, 255, 255])
+    );
+}
+
+#[test]
+fn test_canvas_scale_content_letterboxes() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = image::RgbaImage::from_pixel(100, 100, image::Rgba([255, 0, 0, 255]));
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "canvas",
+            "--width",
+            "50",
+            "--height",
+            "200",
+            "--scale-content",
+            "--color",
+            "black",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["content_width"], 50);
+    assert_eq!(json["details"]["content_height"], 50);
+
+    // 50x50 content centered vertically: rows 75..125
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out_img.dimensions(), (50, 200));
+    assert_eq!(out_img.get_pixel(25, 74), &image::Rgba([0, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(25, 75), &image::Rgba([255, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(0, 124), &image::Rgba([255, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(25, 125), &image::Rgba([0, 0, 0, 255]));
+}
+
+#[test]
+fn test_contact_sheet_with_labels() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.png");
+    let second = temp_dir.path().join("second.png");
+    let output = temp_dir.path().join("sheet.png");
+
+    let red = image::Rgba([255, 0, 0, 255]);
+    image::RgbaImage::from_pixel(80, 80, red)
+        .save(&first)
+        .unwrap();
+    image::RgbaImage::from_pixel(40, 80, red)
+        .save(&second)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "contact-sheet",
+            "--cell-size",
+            "64",
+            "--label",
+            "name-size",
+            "-o",
+            output.to_str().unwrap(),
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    // Two 64px cells with 8px padding, plus two 10px label lines below them
+    let sheet = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(sheet.dimensions(), (2 * 64 + 3 * 8, 64 + 2 * 10 + 2 * 8));
+
+    let black = image::Rgba([0, 0, 0, 255]);
+    for cell_x in [8, 80] {
+        assert_eq!(sheet.get_pixel(cell_x + 32, 40), &red);
+        let inked = (cell_x..cell_x + 64)
+            .flat_map(|x| (72..92).map(move |y| (x, y)))
+            .any(|(x, y)| sheet.get_pixel(x, y) == &black);
+        assert!(inked, "no label under cell at x={}", cell_x);
+    }
+}
+
+#[test]
+fn test_generate_solid_color() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = temp_dir.path().join("red.png");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "generate",
+            "--width",
+            "100",
+            "--height",
+            "50",
+            "--color",
+            "red",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let out = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out.dimensions(), (100, 50));
+    assert!(out.pixels().all(|p| *p == image::Rgba([255, 0, 0, 255])));
+}
+
+#[test]
+fn test_generate_horizontal_gradient() {
+    let temp_dir = TempDir::new().unwrap();
+    let output = temp_dir.path().join("gradient.png");
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "generate",
+            "--width",
+            "64",
+            "--height",
+            "16",
+            "--gradient",
+            "black,white,0",
+            "-o",
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["parameters"]["gradient"], "black,white,0");
+
+    let out = image::open(&output).unwrap().to_rgba8();
+    let (left, right) = (out.get_pixel(0, 8), out.get_pixel(63, 8));
+    assert_ne!(left, right);
+    assert_eq!(left, &image::Rgba([0, 0, 0, 255]));
+    assert_eq!(right, &image::Rgba([255, 255, 255, 255]));
+
+    // A malformed spec is attributed to --gradient
+    let bad = temp_dir.path().join("bad.png");
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "generate",
+            "--width",
+            "8",
+            "--height",
+            "8",
+            "--gradient",
+            "black,white",
+            "-o",
+            bad.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["field"], "gradient");
+}
+
+#[test]
+fn test_circle_avatar() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("photo.png");
+    let output = temp_dir.path().join("avatar.png");
+    common::create_test_rgba_image(120, 80)
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "circle",
+            "--crop",
+            "--border-width",
+            "3",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let out = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out.dimensions(), (80, 80));
+    for (x, y) in [(0, 0), (79, 0), (0, 79), (79, 79)] {
+        assert_eq!(out.get_pixel(x, y)[3], 0);
+    }
+    assert_eq!(out.get_pixel(40, 40)[3], 255);
+    assert_eq!(out.get_pixel(40, 1), &image::Rgba([255, 255, 255, 255]));
+}
+
+#[test]
+fn test_checkerboard_preview_is_opaque() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(16, 16, image::Rgba([255, 0, 0, 128]))
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "checkerboard",
+            "--size",
+            "4",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let out = image::open(&output).unwrap().to_rgba8();
+    assert!(out.pixels().all(|p| p[3] == 255));
+    // Red blended over white keeps more green than over gray
+    assert!(out.get_pixel(0, 0)[1] > out.get_pixel(4, 0)[1]);
+    assert_eq!(out.get_pixel(0, 0), out.get_pixel(4, 4));
+}
+
+#[test]
+fn test_apply_recipe() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    let recipe = temp_dir.path().join("recipe.json");
+    image::RgbaImage::from_pixel(80, 40, image::Rgba([0, 0, 255, 255]))
+        .save(&input)
+        .unwrap();
+    std::fs::write(
+        &recipe,
+        r#"{"operations": [
+            {"op": "grayscale"},
+            {"op": "resize", "width": 40},
+            {"op": "pad", "all": 5, "color": "white"}
+        ]}"#,
+    )
+    .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "apply",
+            "--recipe",
+            recipe.to_str().unwrap(),
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(
+        json["details"]["operations"],
+        serde_json::json!(["grayscale", "resize", "pad"])
+    );
+
+    let out = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out.dimensions(), (50, 30));
+    assert_eq!(out.get_pixel(0, 0), &image::Rgba([255, 255, 255, 255]));
+    let center = out.get_pixel(25, 15);
+    assert!(center[0] == center[1] && center[1] == center[2]);
+    assert!(center[0] < 100);
+}
+
+#[test]
+fn test_apply_recipe_unknown_operation() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    let recipe = temp_dir.path().join("recipe.json");
+    common::create_test_rgba_image(10, 10).save(&input).unwrap();
+    std::fs::write(&recipe, r#"{"operations": [{"op": "posterize"}]}"#).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "apply",
+            "--recipe",
+            recipe.to_str().unwrap(),
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["code"], "INVALID_PARAMETER");
+    assert_eq!(json["field"], "recipe");
+    assert!(json["error"].as_str().unwrap().contains("posterize"));
+    assert!(!output.exists());
+}
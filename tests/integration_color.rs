@@ -64,6 +64,183 @@ fn test_grayscale_json_output() {
     assert_eq!(json["command"], "grayscale");
 }
 
+#[test]
+fn test_grayscale_weights_must_sum_to_one() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    common::create_test_rgba_image(10, 10).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "grayscale",
+            "--weights",
+            "0.5,0.5,0.5",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(result.status.code(), Some(5));
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_grayscale_in_place() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("img.png");
+
+    let original = common::create_test_rgba_image(20, 20);
+    original.save(&path).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["grayscale", "--in-place", path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let edited = image::open(&path).unwrap().to_rgba8();
+    assert_ne!(edited, original);
+    for pixel in edited.pixels() {
+        assert_eq!(pixel[0], pixel[1]);
+        assert_eq!(pixel[1], pixel[2]);
+    }
+    // No backup unless asked for
+    assert!(!temp_dir.path().join("img.png.bak").exists());
+}
+
+#[test]
+fn test_in_place_before_subcommand() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("img.png");
+    common::create_test_rgba_image(20, 20).save(&path).unwrap();
+
+    // Given with the other global options, ahead of the subcommand
+    for args in [
+        &["--in-place", "grayscale"][..],
+        &["--in-place", "resize", "--width", "10"][..],
+    ] {
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(args)
+            .arg(&path)
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            result.status.success(),
+            "{:?}: {}",
+            args,
+            String::from_utf8_lossy(&result.stderr)
+        );
+    }
+    assert_eq!(image::open(&path).unwrap().width(), 10);
+
+    // --backup may sit on either side of the subcommand from --in-place
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--backup", "grayscale", "--in-place"])
+        .arg(&path)
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+    assert!(temp_dir.path().join("img.png.bak").exists());
+
+    // Neither OUTPUT nor --in-place is still an error
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .arg("grayscale")
+        .arg(&path)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&result.stderr).contains("--in-place is required"));
+
+    let output = temp_dir.path().join("out.png");
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--backup", "grayscale"])
+        .args([&path, &output])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_in_place_with_backup() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("img.png");
+    let backup = temp_dir.path().join("img.png.bak");
+
+    common::create_test_rgba_image(20, 20).save(&path).unwrap();
+    let original = std::fs::read(&path).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["invert", "--in-place", "--backup", path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    assert_eq!(std::fs::read(&backup).unwrap(), original);
+    assert_ne!(std::fs::read(&path).unwrap(), original);
+}
+
+#[test]
+fn test_in_place_backup_only_after_success() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("img.png");
+    let backup = temp_dir.path().join("img.png.bak");
+    common::create_test_rgba_image(20, 20).save(&path).unwrap();
+    let original = std::fs::read(&path).unwrap();
+
+    let brighten = |value: &str, extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(extra)
+            .args(["--in-place", "--backup", "brightness", "--value", value])
+            .arg(&path)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    // A run that fails leaves neither a backup nor a changed input
+    assert!(!brighten("500", &[]).status.success());
+    assert!(!backup.exists());
+    assert_eq!(std::fs::read(&path).unwrap(), original);
+
+    // An existing backup is not replaced without --overwrite
+    std::fs::write(&backup, "older backup").unwrap();
+    assert!(!brighten("20", &[]).status.success());
+    assert_eq!(std::fs::read(&backup).unwrap(), b"older backup");
+    assert_eq!(std::fs::read(&path).unwrap(), original);
+
+    assert!(brighten("20", &["--overwrite"]).status.success());
+    assert_eq!(std::fs::read(&backup).unwrap(), original);
+    assert_ne!(std::fs::read(&path).unwrap(), original);
+}
+
+#[test]
+fn test_in_place_rejects_output_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("img.png");
+    let output = temp_dir.path().join("out.png");
+
+    common::create_test_rgba_image(20, 20).save(&path).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "grayscale",
+            "--in-place",
+            path.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+    assert!(!output.exists());
+}
+
 #[test]
 fn test_invert_basic() {
     let temp_dir = TempDir::new().unwrap();
@@ -90,6 +267,67 @@ fn test_invert_basic() {
     assert_eq!(pixel[3], 255); // Alpha preserved
 }
 
+#[test]
+fn test_alpha_threshold_hardens_mask() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = image::RgbaImage::from_fn(2, 1, |x, _| {
+        image::Rgba([90, 120, 150, if x == 0 { 200 } else { 50 }])
+    });
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "alpha-threshold",
+            "--value",
+            "128",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out_img.get_pixel(0, 0), &image::Rgba([90, 120, 150, 255]));
+    assert_eq!(out_img.get_pixel(1, 0), &image::Rgba([90, 120, 150, 0]));
+}
+
+#[test]
+fn test_premultiply_and_back() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let premultiplied = temp_dir.path().join("pma.png");
+    let restored = temp_dir.path().join("restored.png");
+
+    image::RgbaImage::from_pixel(4, 4, image::Rgba([200, 100, 40, 128]))
+        .save(&input)
+        .unwrap();
+
+    for (command, from, to) in [
+        ("premultiply", &input, &premultiplied),
+        ("unpremultiply", &premultiplied, &restored),
+    ] {
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args([command, from.to_str().unwrap(), to.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success(), "{} failed", command);
+    }
+
+    let pixel = *image::open(&premultiplied)
+        .unwrap()
+        .to_rgba8()
+        .get_pixel(0, 0);
+    assert_eq!(pixel, image::Rgba([100, 50, 20, 128]));
+    let pixel = *image::open(&restored).unwrap().to_rgba8().get_pixel(0, 0);
+    for (got, want) in pixel.0.iter().zip([200u8, 100, 40, 128]) {
+        assert!(got.abs_diff(want) <= 1, "{:?}", pixel);
+    }
+}
+
 #[test]
 fn test_depth_1bit() {
     let temp_dir = TempDir::new().unwrap();
@@ -144,6 +382,290 @@ fn test_depth_1bit_with_dither() {
     assert!(output.exists());
 }
 
+#[test]
+fn test_depth_1bit_with_dither_algo() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(100, 100);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "depth",
+            "--bits",
+            "1",
+            "--dither",
+            "--dither-algo",
+            "atkinson",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(result.status.success());
+    let out = image::open(&output).unwrap().to_luma8();
+    assert!(out.pixels().all(|p| p[0] == 0 || p[0] == 255));
+
+    // The algorithm only applies with --dither
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "depth",
+            "--bits",
+            "1",
+            "--dither-algo",
+            "sierra",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!result.status.success());
+}
+
+#[test]
+fn test_depth_dither_strength_zero_thresholds() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let plain = temp_dir.path().join("plain.png");
+    let partial = temp_dir.path().join("partial.png");
+
+    common::create_test_rgba_image(64, 64).save(&input).unwrap();
+
+    let depth = |extra: &[&str], output: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["depth", "--bits", "1"])
+            .args(extra)
+            .arg(&input)
+            .arg(output)
+            .output()
+            .expect("Failed to execute command")
+    };
+    assert!(depth(&[], &plain).status.success());
+    assert!(depth(&["--dither", "--dither-strength", "0"], &partial)
+        .status
+        .success());
+    assert_eq!(
+        image::open(&plain).unwrap().to_luma8(),
+        image::open(&partial).unwrap().to_luma8()
+    );
+
+    let result = depth(&["--dither", "--dither-strength", "2"], &partial);
+    assert!(!result.status.success());
+}
+
+#[test]
+fn test_convert_only_if_smaller_skips_without_gain() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    common::create_test_rgba_image(4, 4).save(&input).unwrap();
+
+    // Re-encoding a tiny PNG as PNG gains nothing, so nothing is written
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "convert",
+            "--only-if-smaller",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["skipped_no_gain"], true);
+    assert!(!output.exists());
+
+    // A smaller result is written
+    let noisy_input = temp_dir.path().join("big.png");
+    let noisy = image::RgbImage::from_fn(64, 64, |x, y| {
+        image::Rgb([
+            (x * 37 % 256) as u8,
+            (y * 91 % 256) as u8,
+            ((x * y) % 256) as u8,
+        ])
+    });
+    noisy.save(&noisy_input).unwrap();
+    let jpeg_output = temp_dir.path().join("small.jpg");
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "convert",
+            "--only-if-smaller",
+            "--quality",
+            "50",
+            noisy_input.to_str().unwrap(),
+            jpeg_output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["skipped_no_gain"], false);
+    assert!(jpeg_output.exists());
+}
+
+#[test]
+fn test_convert_low_memory_matches_normal_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    image::RgbImage::from_fn(1200, 900, |x, y| {
+        image::Rgb([(x % 256) as u8, (y % 256) as u8, ((x * y) % 256) as u8])
+    })
+    .save(&input)
+    .unwrap();
+
+    for extension in ["jpg", "png", "tiff"] {
+        let normal = temp_dir.path().join(format!("normal.{}", extension));
+        let streamed = temp_dir.path().join(format!("streamed.{}", extension));
+        for (output, extra) in [(&normal, None), (&streamed, Some("--low-memory"))] {
+            let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+                .arg("convert")
+                .args(extra)
+                .args([input.to_str().unwrap(), output.to_str().unwrap()])
+                .output()
+                .expect("Failed to execute command");
+            assert!(
+                result.status.success(),
+                "{}",
+                String::from_utf8_lossy(&result.stderr)
+            );
+        }
+        assert_eq!(
+            std::fs::read(&normal).unwrap(),
+            std::fs::read(&streamed).unwrap(),
+            "{} output differs",
+            extension
+        );
+    }
+
+    // Recompression needs the whole encoded file in memory
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["convert", "--low-memory", "--optimize"])
+        .args([
+            input.to_str().unwrap(),
+            temp_dir.path().join("bad.png").to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!result.status.success());
+}
+
+#[test]
+fn test_convert_png_color_type() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    image::RgbaImage::from_fn(8, 8, |x, y| {
+        image::Rgba([(x * 30) as u8, (y * 30) as u8, 90, 255])
+    })
+    .save(&input)
+    .unwrap();
+
+    let color_type = |extra: &[&str], name: &str| {
+        let output = temp_dir.path().join(name);
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .arg("convert")
+            .args(extra)
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            result.status.success(),
+            "{}",
+            String::from_utf8_lossy(&result.stderr)
+        );
+        let info = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["--json", "info", output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        let json: serde_json::Value = serde_json::from_slice(&info.stdout).unwrap();
+        json["details"]["color_type"].as_str().unwrap().to_string()
+    };
+
+    // The opaque alpha channel is kept by default and dropped when forced
+    assert_eq!(color_type(&[], "auto.png"), "RGBA");
+    assert_eq!(color_type(&["--png-color-type", "rgb"], "rgb.png"), "RGB");
+    assert_eq!(
+        color_type(&["--png-color-type", "graya"], "graya.png"),
+        "Grayscale+Alpha"
+    );
+}
+
+#[test]
+fn test_convert_png_color_type_without_alpha_warns() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(8, 8, image::Rgba([200, 50, 50, 128]))
+        .save(&input)
+        .unwrap();
+
+    let convert = |strict: bool| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_mdimgedit"));
+        cmd.args(["--json", "-y"]);
+        if strict {
+            cmd.arg("--fail-on-warning");
+        }
+        cmd.args(["convert", "--png-color-type", "gray"])
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let result = convert(true);
+    assert_eq!(result.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["code"], "WARNING_AS_ERROR");
+    assert!(!output.exists());
+
+    let result = convert(false);
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["warnings"][0]["code"], "ALPHA_DISCARDED");
+    assert_eq!(image::open(&output).unwrap().color(), image::ColorType::L8);
+}
+
+#[test]
+fn test_convert_lossless() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let webp = temp_dir.path().join("output.webp");
+    let jpeg = temp_dir.path().join("output.jpg");
+    let source = image::RgbaImage::from_fn(32, 32, |x, y| {
+        image::Rgba([(x * 8) as u8, (y * 8) as u8, ((x ^ y) * 8) as u8, 255])
+    });
+    source.save(&input).unwrap();
+
+    let convert = |output: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args([
+                "--json",
+                "convert",
+                "--lossless",
+                input.to_str().unwrap(),
+                output.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let result = convert(&webp);
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["lossless"], true);
+    assert_eq!(image::open(&webp).unwrap().to_rgba8(), source);
+
+    // JPEG has no lossless mode
+    let result = convert(&jpeg);
+    assert_eq!(result.status.code(), Some(4));
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert!(json["error"].as_str().unwrap().contains("PNG or WebP"));
+    assert!(!jpeg.exists());
+}
+
 #[test]
 fn test_convert_png_to_jpeg() {
     let temp_dir = TempDir::new().unwrap();
@@ -171,6 +693,186 @@ fn test_convert_png_to_jpeg() {
     assert_eq!(out_img.height(), 100);
 }
 
+#[test]
+fn test_convert_alpha_to_jpeg_fail_on_warning() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.jpg");
+
+    image::RgbaImage::from_pixel(16, 16, image::Rgba([200, 50, 50, 128]))
+        .save(&input)
+        .unwrap();
+    let convert = |strict: bool| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_mdimgedit"));
+        cmd.args(["--json", "-y", "convert"]);
+        if strict {
+            cmd.arg("--fail-on-warning");
+        }
+        cmd.args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    // Strict mode fails before writing anything
+    let result = convert(true);
+    assert_eq!(result.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["code"], "WARNING_AS_ERROR");
+    assert!(!output.exists());
+
+    // Without it the conversion succeeds and reports the warning
+    let result = convert(false);
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["warnings"][0]["code"], "ALPHA_DISCARDED");
+    assert!(output.exists());
+}
+
+#[test]
+fn test_edit_alpha_to_jpeg_fail_on_warning() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.jpg");
+
+    image::RgbaImage::from_pixel(16, 16, image::Rgba([200, 50, 50, 128]))
+        .save(&input)
+        .unwrap();
+    let flip = |strict: bool| {
+        let mut cmd = Command::new(env!("CARGO_BIN_EXE_mdimgedit"));
+        cmd.args(["--json", "-y"]);
+        if strict {
+            cmd.arg("--fail-on-warning");
+        }
+        cmd.args(["flip", "--horizontal"])
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    // Any command saving to JPEG warns as convert does
+    let result = flip(true);
+    assert_eq!(result.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["code"], "WARNING_AS_ERROR");
+    assert!(!output.exists());
+
+    let result = flip(false);
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["warnings"][0]["code"], "ALPHA_DISCARDED");
+    assert!(output.exists());
+}
+
+fn write_animated_gif(path: &std::path::Path) {
+    use image::codecs::gif::GifEncoder;
+    use image::{Frame, Rgba, RgbaImage};
+
+    let file = std::fs::File::create(path).unwrap();
+    let mut encoder = GifEncoder::new(file);
+    encoder
+        .encode_frames([
+            Frame::new(RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]))),
+            Frame::new(RgbaImage::from_pixel(8, 8, Rgba([0, 255, 0, 255]))),
+            Frame::new(RgbaImage::from_pixel(8, 8, Rgba([0, 0, 255, 255]))),
+        ])
+        .unwrap();
+}
+
+#[test]
+fn test_convert_animated_gif_frame() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("anim.gif");
+    let output = temp_dir.path().join("frame.png");
+    write_animated_gif(&input);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "convert",
+            "--frame",
+            "1",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out_img.get_pixel(4, 4), &image::Rgba([0, 255, 0, 255]));
+}
+
+#[test]
+fn test_convert_animated_gif_without_frame_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("anim.gif");
+    let output = temp_dir.path().join("frame.png");
+    write_animated_gif(&input);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["convert", input.to_str().unwrap(), output.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(result.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(stderr.contains("--frame"), "{}", stderr);
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_convert_assign_profile_display_p3() {
+    use image::ImageDecoder;
+
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(32, 32);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "convert",
+            "--assign-profile",
+            "display-p3",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["profile"], "Display P3");
+
+    // Walk the PNG chunks: the profile is stored in an iCCP chunk
+    let bytes = std::fs::read(&output).unwrap();
+    let mut pos = 8;
+    let mut chunks = Vec::new();
+    while pos + 8 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[pos..pos + 4].try_into().unwrap()) as usize;
+        chunks.push(String::from_utf8_lossy(&bytes[pos + 4..pos + 8]).to_string());
+        pos += 12 + len;
+    }
+    assert!(chunks.iter().any(|c| c == "iCCP"), "chunks: {:?}", chunks);
+
+    let file = std::io::BufReader::new(std::fs::File::open(&output).unwrap());
+    let mut decoder = image::codecs::png::PngDecoder::new(file).unwrap();
+    let icc = decoder.icc_profile().unwrap().expect("profile embedded");
+    assert_eq!(
+        mdimgedit::ops::icc_description(&icc).as_deref(),
+        Some("Display P3")
+    );
+
+    // Pixels are unchanged
+    assert_eq!(image::open(&output).unwrap().to_rgba8(), img);
+}
+
 #[test]
 fn test_convert_with_quality() {
     let temp_dir = TempDir::new().unwrap();
@@ -458,6 +1160,106 @@ fn test_grayscale_no_preserve_alpha() {
     assert!(output.exists());
 }
 
+#[test]
+fn test_grayscale_output_is_single_channel() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let flat = temp_dir.path().join("flat.png");
+    let with_alpha = temp_dir.path().join("alpha.png");
+
+    image::RgbaImage::from_fn(10, 10, |x, _| {
+        image::Rgba([255, 128, 64, (x * 25).min(255) as u8])
+    })
+    .save(&input)
+    .unwrap();
+
+    let grayscale = |extra: &[&str], output: &std::path::Path| {
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .arg("grayscale")
+            .args(extra)
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success());
+    };
+    grayscale(&["--no-preserve-alpha"], &flat);
+    grayscale(&["--method", "luma-alpha"], &with_alpha);
+
+    // Gray results are written as one gray channel (plus alpha when kept),
+    // not promoted to RGBA
+    assert_eq!(image::open(&flat).unwrap().color(), image::ColorType::L8);
+    assert_eq!(
+        image::open(&with_alpha).unwrap().color(),
+        image::ColorType::La8
+    );
+
+    // The default method returns RGBA and is written as such
+    let rgba = temp_dir.path().join("rgba.png");
+    grayscale(&[], &rgba);
+    assert_eq!(image::open(&rgba).unwrap().color(), image::ColorType::Rgba8);
+}
+
+#[test]
+fn test_flip_keeps_opaque_gray_rgba() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(4, 4, image::Rgba([90, 90, 90, 255]))
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["flip", "--horizontal"])
+        .args([input.to_str().unwrap(), output.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+    assert_eq!(
+        image::open(&output).unwrap().color(),
+        image::ColorType::Rgba8
+    );
+}
+
+#[test]
+fn test_grayscale_luma_alpha_method() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    image::RgbaImage::from_pixel(6, 6, image::Rgba([200, 100, 50, 128]))
+        .save(&input)
+        .unwrap();
+
+    for name in ["output.png", "output.tif"] {
+        let output = temp_dir.path().join(name);
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["grayscale", "--method", "luma-alpha"])
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(
+            result.status.success(),
+            "{}: {}",
+            name,
+            String::from_utf8_lossy(&result.stderr)
+        );
+
+        let pixel = image::open(&output).unwrap().to_rgba8().get_pixel(0, 0).0;
+        assert_eq!(pixel[0], pixel[1]);
+        assert_eq!(pixel[1], pixel[2]);
+        assert_eq!(pixel[3], 128);
+    }
+
+    // Dropping alpha and keeping it in a gray+alpha image contradict each other
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["grayscale", "--method", "luma-alpha", "--no-preserve-alpha"])
+        .args([
+            input.to_str().unwrap(),
+            temp_dir.path().join("bad.png").to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!result.status.success());
+}
+
 #[test]
 fn test_invert_with_alpha() {
     let temp_dir = TempDir::new().unwrap();
@@ -516,6 +1318,38 @@ fn test_depth_16bit() {
     assert!(output.exists());
 }
 
+#[test]
+fn test_depth_16bit_png_reported_by_info() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input16.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img: image::ImageBuffer<image::Rgb<u16>, Vec<u16>> =
+        image::ImageBuffer::from_fn(16, 16, |x, y| {
+            image::Rgb([(x * 4000) as u16, (y * 4000) as u16, 40000])
+        });
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "depth",
+            "--bits",
+            "16",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "info", output.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["bit_depth"], 16);
+}
+
 #[test]
 fn test_convert_json_output() {
     let temp_dir = TempDir::new().unwrap();
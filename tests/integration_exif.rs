@@ -191,3 +191,311 @@ fn test_exif_subcommand_help() {
     assert!(stdout.contains("--verbose"));
     assert!(stdout.contains("--tag"));
 }
+
+fn count_non_background(path: &std::path::Path, background: [u8; 3]) -> usize {
+    image::open(path)
+        .unwrap()
+        .to_rgb8()
+        .pixels()
+        .filter(|p| p.0.iter().zip(background).any(|(a, b)| a.abs_diff(b) > 60))
+        .count()
+}
+
+#[test]
+fn test_annotate_timestamp_from_exif() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("photo.jpg");
+    let output = temp_dir.path().join("stamped.png");
+
+    let img = image::RgbaImage::from_pixel(200, 60, image::Rgba([0, 0, 0, 255]));
+    let date = exif::Field {
+        tag: exif::Tag::DateTime,
+        ifd_num: exif::In::PRIMARY,
+        value: exif::Value::Ascii(vec![b"2021:06:15 08:30:00".to_vec()]),
+    };
+    common::save_jpeg_with_exif(&img, &input, &[date]);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "annotate",
+            "--annotate-timestamp",
+            "--time-format",
+            "%Y-%m-%d %H:%M",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&result.stdout)).unwrap();
+    assert_eq!(json["details"]["text"], "2021-06-15 08:30");
+    assert_eq!(json["details"]["time_source"], "exif");
+
+    // Text was drawn in the bottom-right corner
+    assert!(count_non_background(&output, [0, 0, 0]) > 50);
+    let out_img = image::open(&output).unwrap().to_rgb8();
+    for y in 0..20 {
+        for x in 0..100 {
+            assert_eq!(out_img.get_pixel(x, y).0, [0, 0, 0]);
+        }
+    }
+}
+
+#[test]
+fn test_annotate_timestamp_falls_back_to_now() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = image::RgbaImage::from_pixel(200, 60, image::Rgba([0, 0, 0, 255]));
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "annotate",
+            "--annotate-timestamp",
+            "--time-format",
+            "%Y",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(result.status.success());
+
+    let json: serde_json::Value =
+        serde_json::from_str(&String::from_utf8_lossy(&result.stdout)).unwrap();
+    assert_eq!(json["details"]["time_source"], "now");
+    let year: i64 = json["details"]["text"].as_str().unwrap().parse().unwrap();
+    assert!(year >= 2024, "year was {}", year);
+    assert!(count_non_background(&output, [0, 0, 0]) > 10);
+}
+
+#[test]
+fn test_set_orientation_on_jpeg() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.jpg");
+    let output = temp_dir.path().join("output.jpg");
+
+    let img = common::create_test_rgb_image(64, 32);
+    img.save(&input).unwrap();
+    let original = image::open(&input).unwrap().to_rgb8();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "set-orientation",
+            "--orientation",
+            "6",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let exif = mdimgedit::ops::read_exif(&output).unwrap();
+    assert_eq!(exif.orientation, Some(6));
+
+    // Pixels are not re-encoded
+    let out_img = image::open(&output).unwrap().to_rgb8();
+    assert_eq!(out_img.dimensions(), (64, 32));
+    assert_eq!(out_img, original);
+}
+
+#[test]
+fn test_set_orientation_rejects_png() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    common::create_test_rgba_image(8, 8).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "set-orientation",
+            "--orientation",
+            "6",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(result.status.code(), Some(4));
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_suggest_name_from_capture_date() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("DSC0001.jpg");
+
+    let img = image::RgbaImage::from_pixel(8, 8, image::Rgba([0, 0, 0, 255]));
+    let date = exif::Field {
+        tag: exif::Tag::DateTimeOriginal,
+        ifd_num: exif::In::PRIMARY,
+        value: exif::Value::Ascii(vec![b"2022:11:05 17:42:09".to_vec()]),
+    };
+    common::save_jpeg_with_exif(&img, &input, &[date]);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["suggest-name", input.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&result.stdout).trim(),
+        "20221105_174209.jpg"
+    );
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "suggest-name",
+            "--pattern",
+            "{year}-{month}-{day}",
+            input.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["name"], "2022-11-05.jpg");
+    // Nothing was renamed
+    assert!(input.exists());
+}
+
+#[test]
+fn test_suggest_name_date_and_model_tokens() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("DSC0002.jpg");
+
+    let img = image::RgbaImage::from_pixel(8, 8, image::Rgba([0, 0, 0, 255]));
+    let fields = [
+        exif::Field {
+            tag: exif::Tag::DateTimeOriginal,
+            ifd_num: exif::In::PRIMARY,
+            value: exif::Value::Ascii(vec![b"2019:02:28 23:59:58".to_vec()]),
+        },
+        exif::Field {
+            tag: exif::Tag::Model,
+            ifd_num: exif::In::PRIMARY,
+            value: exif::Value::Ascii(vec![b"X100V".to_vec()]),
+        },
+    ];
+    common::save_jpeg_with_exif(&img, &input, &fields);
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "suggest-name",
+            "--require-exif",
+            "--pattern",
+            "{date:%Y%m%d}_{exif:Model}",
+            input.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&result.stdout).trim(),
+        "20190228_X100V.jpg"
+    );
+}
+
+#[test]
+fn test_suggest_name_without_exif_fails() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    common::create_test_rgba_image(8, 8).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["suggest-name", "--require-exif", input.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&result.stderr).contains("DateTimeOriginal"));
+}
+
+#[test]
+fn test_exif_raw_reports_unknown_tag_bytes() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("photo.jpg");
+
+    let img = image::RgbaImage::from_pixel(16, 16, image::Rgba([0, 0, 0, 255]));
+    let unknown = exif::Field {
+        tag: exif::Tag(exif::Context::Tiff, 0xC0DE),
+        ifd_num: exif::In::PRIMARY,
+        value: exif::Value::Undefined(vec![0xDE, 0xAD, 0xBE, 0xEF], 0),
+    };
+    common::save_jpeg_with_exif(&img, &input, &[unknown]);
+
+    let run = |raw: bool| {
+        let mut args = vec!["--json", "exif"];
+        if raw {
+            args.push("--raw");
+        }
+        args.push(input.to_str().unwrap());
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(&args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+        json["details"]["fields"].as_array().unwrap().clone()
+    };
+
+    let fields = run(true);
+    let field = fields
+        .iter()
+        .find(|f| f["raw_hex"].is_string())
+        .expect("unknown tag should carry raw bytes");
+    assert_eq!(field["raw_hex"], "de ad be ef");
+    assert_eq!(field["value_type"], "UNDEFINED");
+
+    // Without --raw only the decoded fields are reported
+    let fields = run(false);
+    assert!(fields
+        .iter()
+        .all(|f| f.get("raw_hex").is_none() && f.get("value_type").is_none()));
+}
+
+#[test]
+fn test_suggest_name_rejects_unknown_date_specifier() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    common::create_test_rgba_image(8, 8).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "suggest-name",
+            "--pattern",
+            "{date:%Y-%Q}",
+            input.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&result.stderr).contains("'%Q'"));
+}
@@ -55,6 +55,58 @@ fn test_info_command_json_output() {
     assert_eq!(json["details"]["bit_depth"], 8);
 }
 
+#[test]
+fn test_info_reports_content_format_of_mislabeled_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let jpeg = temp_dir.path().join("photo.jpg");
+    let img_path = temp_dir.path().join("photo.png");
+    image::DynamicImage::ImageRgba8(common::create_test_rgba_image(32, 16))
+        .to_rgb8()
+        .save(&jpeg)
+        .unwrap();
+    std::fs::rename(&jpeg, &img_path).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["info", "--json", img_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["details"]["format"], "JPEG");
+    assert_eq!(json["details"]["format_mismatch"], true);
+    assert_eq!(json["details"]["width"], 32);
+}
+
+#[test]
+fn test_info_assume_orientation_swaps_display_dimensions() {
+    let temp_dir = TempDir::new().unwrap();
+    let img_path = temp_dir.path().join("test.png");
+    common::create_test_rgba_image(100, 50)
+        .save(&img_path)
+        .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "info",
+            "--assume-orientation",
+            "6",
+            img_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    // Stored dimensions are unchanged; only the display ones swap
+    assert_eq!(json["details"]["width"], 100);
+    assert_eq!(json["details"]["height"], 50);
+    assert_eq!(json["details"]["assumed_orientation"], 6);
+    assert_eq!(json["details"]["display_width"], 50);
+    assert_eq!(json["details"]["display_height"], 100);
+}
+
 #[test]
 fn test_info_command_quiet_mode() {
     let temp_dir = TempDir::new().unwrap();
@@ -107,6 +159,69 @@ fn test_info_command_nonexistent_file_json() {
     assert_eq!(json["code"], "INPUT_NOT_FOUND");
 }
 
+#[test]
+fn test_validate_valid_png() {
+    let temp_dir = TempDir::new().unwrap();
+    let img_path = temp_dir.path().join("test.png");
+
+    let img = common::create_test_rgba_image(64, 48);
+    img.save(&img_path).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "validate", img_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Should be valid JSON");
+    assert_eq!(json["command"], "validate");
+    assert_eq!(json["details"]["valid"], true);
+    assert_eq!(json["details"]["width"], 64);
+}
+
+#[test]
+fn test_validate_truncated_png() {
+    let temp_dir = TempDir::new().unwrap();
+    let full = temp_dir.path().join("full.png");
+    let truncated = temp_dir.path().join("truncated.png");
+
+    let img = common::create_test_rgba_image(64, 48);
+    img.save(&full).unwrap();
+    let bytes = std::fs::read(&full).unwrap();
+    std::fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "validate", truncated.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(2)); // READ_ERROR exit code
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Should be valid JSON");
+    assert_eq!(json["success"], false);
+    assert_eq!(json["details"]["valid"], false);
+    assert_eq!(json["details"]["code"], "READ_ERROR");
+    assert!(!json["details"]["error"].as_str().unwrap().is_empty());
+}
+
+#[test]
+fn test_validate_unsupported_format() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("notes.txt");
+    std::fs::write(&path, "not an image").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["validate", path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(4)); // UNSUPPORTED_FORMAT exit code
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid"));
+}
+
 #[test]
 fn test_help_output() {
     let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
@@ -150,3 +265,341 @@ fn test_version_output() {
     assert!(stdout.contains("mdimgedit"));
     assert!(stdout.contains("1.0.0"));
 }
+
+#[test]
+fn test_info_reports_dpi() {
+    let temp_dir = TempDir::new().unwrap();
+    let plain = temp_dir.path().join("plain.png");
+    let print = temp_dir.path().join("print.png");
+
+    common::create_test_rgba_image(20, 20).save(&plain).unwrap();
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "convert",
+            "--dpi",
+            "300",
+            plain.to_str().unwrap(),
+            print.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let info = |path: &std::path::Path| {
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["--json", "info", path.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        serde_json::from_slice::<serde_json::Value>(&result.stdout).unwrap()
+    };
+
+    let json = info(&print);
+    assert_eq!(json["details"]["dpi_x"], 300.0);
+    assert_eq!(json["details"]["dpi_y"], 300.0);
+
+    let json = info(&plain);
+    assert!(json["details"]["dpi_x"].is_null());
+    assert!(json["details"]["dpi_y"].is_null());
+}
+
+#[test]
+fn test_pixel_reports_gradient_value() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("gradient.png");
+
+    // r = x * 255 / 100, g = y * 255 / 50, b = 128
+    common::create_test_rgba_image(100, 50)
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "pixel",
+            "--x",
+            "40",
+            "--y",
+            "10",
+            input.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["r"], 102);
+    assert_eq!(json["details"]["g"], 51);
+    assert_eq!(json["details"]["b"], 128);
+    assert_eq!(json["details"]["a"], 255);
+    assert_eq!(json["details"]["hex"], "#663380");
+    assert_eq!(json["details"]["luma"], 75);
+}
+
+#[test]
+fn test_pixel_out_of_bounds() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    common::create_test_rgba_image(10, 10).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["pixel", "--x", "10", "--y", "0", input.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+    assert!(String::from_utf8_lossy(&result.stderr).contains("outside image bounds"));
+}
+
+#[test]
+fn test_region_average_blends_two_colors() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("split.png");
+
+    // Red left half (x < 10), blue right half
+    let img = image::RgbaImage::from_fn(20, 10, |x, _| {
+        if x < 10 {
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            image::Rgba([0, 0, 255, 255])
+        }
+    });
+    img.save(&input).unwrap();
+
+    // 8 columns: 2 red, 6 blue
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "region-average",
+            "--x",
+            "8",
+            "--y",
+            "2",
+            "--width",
+            "8",
+            "--height",
+            "4",
+            input.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["r"], 64);
+    assert_eq!(json["details"]["g"], 0);
+    assert_eq!(json["details"]["b"], 191);
+    assert_eq!(json["details"]["hex"], "#4000BF");
+}
+
+#[test]
+fn test_audit_directory_summary() {
+    let temp_dir = TempDir::new().unwrap();
+    let dir = temp_dir.path();
+
+    common::create_test_rgba_image(40, 20)
+        .save(dir.join("a.png"))
+        .unwrap();
+    common::create_test_rgba_image(60, 30)
+        .save(dir.join("b.png"))
+        .unwrap();
+    image::DynamicImage::ImageRgb8(image::RgbImage::new(80, 40))
+        .save(dir.join("c.jpg"))
+        .unwrap();
+    std::fs::write(dir.join("readme.txt"), "not an image").unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "audit", dir.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["images"], 3);
+    assert_eq!(json["details"]["skipped"], 1);
+    assert_eq!(json["details"]["formats"]["PNG"], 2);
+    assert_eq!(json["details"]["formats"]["JPEG"], 1);
+    assert_eq!(json["details"]["with_alpha"], 2);
+    assert_eq!(json["details"]["width"]["min"], 40);
+    assert_eq!(json["details"]["width"]["max"], 80);
+    assert_eq!(json["details"]["height"]["mean"], 30.0);
+}
+
+/// Check `value` against the subset of JSON Schema the `schema` command uses
+fn schema_violations(
+    value: &serde_json::Value,
+    schema: &serde_json::Value,
+    path: &str,
+) -> Vec<String> {
+    let mut violations = Vec::new();
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            violations.push(format!("{}: expected {}", path, expected));
+        }
+    }
+    let type_ok = match schema.get("type").and_then(|t| t.as_str()) {
+        Some("object") => value.is_object(),
+        Some("array") => value.is_array(),
+        Some("string") => value.is_string(),
+        Some("boolean") => value.is_boolean(),
+        Some("number") => value.is_number(),
+        _ => true,
+    };
+    if !type_ok {
+        violations.push(format!("{}: expected type {}", path, schema["type"]));
+        return violations;
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(|p| p.as_object());
+        for key in schema["required"].as_array().into_iter().flatten() {
+            if !object.contains_key(key.as_str().unwrap()) {
+                violations.push(format!("{}: missing {}", path, key));
+            }
+        }
+        for (key, child) in object {
+            match properties.and_then(|p| p.get(key)) {
+                Some(child_schema) => violations.extend(schema_violations(
+                    child,
+                    child_schema,
+                    &format!("{}.{}", path, key),
+                )),
+                None if schema["additionalProperties"] == false => {
+                    violations.push(format!("{}: unexpected {}", path, key))
+                }
+                None => {}
+            }
+        }
+    }
+    if let (Some(items), Some(schema)) = (value.as_array(), schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            violations.extend(schema_violations(item, schema, &format!("{}[{}]", path, i)));
+        }
+    }
+    violations
+}
+
+#[test]
+fn test_schema_matches_real_responses() {
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .arg("schema")
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let schemas: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let success_schema = &schemas["SuccessResponse"];
+    let error_schema = &schemas["ErrorResponse"];
+    assert_eq!(success_schema["title"], "SuccessResponse");
+
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let resized = temp_dir.path().join("resized.png");
+    common::create_test_rgba_image(40, 20).save(&input).unwrap();
+
+    // Upscaling also produces a warning
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "resize", "--width", "80"])
+        .arg(&input)
+        .arg(&resized)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let success: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(success.get("details").is_some());
+    assert_eq!(
+        schema_violations(&success, success_schema, "$"),
+        Vec::<String>::new()
+    );
+    // The two shapes are told apart by `success`
+    assert!(!schema_violations(&success, error_schema, "$").is_empty());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "pad", "--all", "4", "--color", "nope"])
+        .arg(&input)
+        .arg(temp_dir.path().join("padded.png"))
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert!(error.get("field").is_some());
+    assert_eq!(
+        schema_violations(&error, error_schema, "$"),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn test_info_output_format_csv() {
+    let temp_dir = TempDir::new().unwrap();
+    let img_path = temp_dir.path().join("test.png");
+    common::create_test_rgba_image(64, 48)
+        .save(&img_path)
+        .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["info", "--output-format", "csv", img_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 2, "{}", stdout);
+    let header: Vec<&str> = lines[0].split(',').collect();
+    let values: Vec<&str> = lines[1].split(',').collect();
+    assert_eq!(header.len(), values.len());
+    assert_eq!(header[0], "input");
+
+    let column = |name: &str| values[header.iter().position(|&h| h == name).unwrap()];
+    assert_eq!(column("width"), "64");
+    assert_eq!(column("height"), "48");
+    assert_eq!(column("format"), "PNG");
+
+    // TSV uses the same columns
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["info", "--output-format", "tsv", img_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().next().unwrap(), lines[0].replace(',', "\t"));
+}
+
+#[test]
+fn test_info_channels_solid_gray() {
+    let temp_dir = TempDir::new().unwrap();
+    let img_path = temp_dir.path().join("gray.png");
+    image::RgbaImage::from_pixel(16, 8, image::Rgba([90, 90, 90, 255]))
+        .save(&img_path)
+        .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["info", "--json", "--channels", img_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let channels = &json["details"]["channels"];
+    for channel in ["r", "g", "b"] {
+        assert_eq!(channels[channel]["min"], 90, "{}", channel);
+        assert_eq!(channels[channel]["max"], 90, "{}", channel);
+        assert_eq!(channels[channel]["mean"], 90.0, "{}", channel);
+    }
+    assert_eq!(channels["a"]["min"], 255);
+    assert_eq!(channels["a"]["max"], 255);
+    assert_eq!(channels["a"]["mean"], 255.0);
+
+    // Plain info does not compute them
+    let output = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["info", "--json", img_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["details"].get("channels").is_none());
+}
@@ -68,6 +68,72 @@ fn test_rotate_json_output() {
     assert_eq!(json["command"], "rotate");
 }
 
+#[test]
+fn test_rotate_auto_deskews_grid() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let grid = image::RgbaImage::from_fn(200, 200, |x, y| {
+        if x % 20 < 2 || y % 20 < 2 {
+            image::Rgba([0, 0, 0, 255])
+        } else {
+            image::Rgba([255, 255, 255, 255])
+        }
+    });
+    let skewed = mdimgedit::ops::rotate(
+        &image::DynamicImage::ImageRgba8(grid),
+        3.0,
+        false,
+        image::Rgba([255, 255, 255, 255]),
+    )
+    .unwrap();
+    skewed.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "rotate",
+            "--json",
+            "--auto",
+            "--max-angle",
+            "10",
+            "--background",
+            "white",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Should be valid JSON");
+    let skew = json["details"]["skew_degrees"].as_f64().unwrap();
+    assert!((skew - 3.0).abs() < 0.5, "skew was {}", skew);
+}
+
+#[test]
+fn test_rotate_requires_degrees_or_auto() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(10, 10);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["rotate", input.to_str().unwrap(), output.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!result.status.success());
+}
+
 #[test]
 fn test_flip_json_output() {
     let temp_dir = TempDir::new().unwrap();
@@ -244,6 +310,138 @@ fn test_crop_basic() {
     assert_eq!(out_img.height(), 50);
 }
 
+#[test]
+fn test_crop_pad_if_needed() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(100, 100, image::Rgba([255, 0, 0, 255]))
+        .save(&input)
+        .unwrap();
+
+    let crop = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args([
+                "crop", "--x", "60", "--y", "60", "--width", "60", "--height", "60",
+            ])
+            .args(extra)
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    // Without the flag the region is rejected
+    assert_eq!(crop(&[]).status.code(), Some(5));
+
+    let result = crop(&["--pad-if-needed", "--background", "blue"]);
+    assert!(result.status.success());
+    let out = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out.dimensions(), (60, 60));
+    assert_eq!(out.get_pixel(10, 10), &image::Rgba([255, 0, 0, 255]));
+    assert_eq!(out.get_pixel(50, 50), &image::Rgba([0, 0, 255, 255]));
+}
+
+#[test]
+fn test_crop_pad_if_needed_larger_than_image_centered() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    image::RgbaImage::from_pixel(100, 100, image::Rgba([255, 0, 0, 255]))
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "crop", "--width", "140", "--height", "120"])
+        .args([
+            "--anchor",
+            "center",
+            "--pad-if-needed",
+            "--background",
+            "blue",
+        ])
+        .args([input.to_str().unwrap(), output.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["parameters"]["x"], -20);
+    assert_eq!(json["parameters"]["y"], -10);
+
+    // The image sits in the middle with an even border of background
+    let out = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out.dimensions(), (140, 120));
+    let blue = image::Rgba([0, 0, 255, 255]);
+    let red = image::Rgba([255, 0, 0, 255]);
+    assert_eq!(out.get_pixel(19, 9), &blue);
+    assert_eq!(out.get_pixel(20, 10), &red);
+    assert_eq!(out.get_pixel(119, 109), &red);
+    assert_eq!(out.get_pixel(120, 110), &blue);
+}
+
+/// Write an 8x8 indexed PNG of four color quadrants, with a palette entry
+/// the image does not use
+fn save_indexed_png(path: &std::path::Path) -> Vec<u8> {
+    let palette = vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 255, 255, 0, 7, 7, 7];
+    let indices: Vec<u8> = (0..64u8)
+        .map(|i| (i % 8 >= 4) as u8 + 2 * (i / 8 >= 4) as u8)
+        .collect();
+    let mut encoder = png::Encoder::new(std::fs::File::create(path).unwrap(), 8, 8);
+    encoder.set_color(png::ColorType::Indexed);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_palette(palette.clone());
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&indices).unwrap();
+    writer.finish().unwrap();
+    palette
+}
+
+fn png_color_type_and_palette(path: &std::path::Path) -> (png::ColorType, Option<Vec<u8>>) {
+    let reader = png::Decoder::new(std::io::BufReader::new(std::fs::File::open(path).unwrap()))
+        .read_info()
+        .unwrap();
+    let info = reader.info();
+    (info.color_type, info.palette.as_ref().map(|p| p.to_vec()))
+}
+
+#[test]
+fn test_crop_keep_palette() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("indexed.png");
+    let output = temp_dir.path().join("output.png");
+    let palette = save_indexed_png(&input);
+
+    let crop = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["--json", "--overwrite", "crop", "--x", "2", "--y", "2"])
+            .args(["--width", "4", "--height", "4"])
+            .args(extra)
+            .arg(&input)
+            .arg(&output)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let result = crop(&["--keep-palette"]);
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["palette_kept"], true);
+    assert_eq!(
+        png_color_type_and_palette(&output),
+        (png::ColorType::Indexed, Some(palette))
+    );
+    let out = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out.get_pixel(0, 0), &image::Rgba([255, 0, 0, 255]));
+    assert_eq!(out.get_pixel(3, 3), &image::Rgba([255, 255, 0, 255]));
+
+    // Without the flag the image crate writes plain RGB
+    assert!(crop(&[]).status.success());
+    assert_eq!(png_color_type_and_palette(&output).0, png::ColorType::Rgb);
+}
+
 #[test]
 fn test_crop_with_anchor_center() {
     let temp_dir = TempDir::new().unwrap();
@@ -310,6 +508,42 @@ fn test_crop_json_output() {
     assert_eq!(json["details"]["result_height"], 40);
 }
 
+#[test]
+fn test_crop_json_reports_resolved_parameters() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    common::create_test_rgba_image(100, 80)
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "crop",
+            "--width",
+            "50",
+            "--height",
+            "40",
+            "--anchor",
+            "center",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    // The center anchor resolves to an absolute origin
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["parameters"]["anchor"], "center");
+    assert_eq!(json["parameters"]["x"], 25);
+    assert_eq!(json["parameters"]["y"], 20);
+    assert_eq!(json["parameters"]["width"], 50);
+    assert_eq!(json["parameters"]["height"], 40);
+}
+
 #[test]
 fn test_rotate_90() {
     let temp_dir = TempDir::new().unwrap();
@@ -366,6 +600,62 @@ fn test_rotate_180() {
     assert_eq!(out_img.height(), 50);
 }
 
+#[test]
+fn test_rotate_right_turns_clockwise() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(100, 50);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "rotate-right",
+            "--json",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Should be valid JSON");
+    assert_eq!(json["command"], "rotate-right");
+
+    let out_img = image::open(&output).unwrap().to_rgba8();
+    assert_eq!(out_img.dimensions(), (50, 100));
+
+    assert_eq!(out_img, image::imageops::rotate90(&img));
+}
+
+#[test]
+fn test_rotate_left_and_180_shorthands() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+
+    let img = common::create_test_rgba_image(100, 50);
+    img.save(&input).unwrap();
+
+    for (command, expected) in [
+        ("rotate-left", image::imageops::rotate270(&img)),
+        ("rotate-180", image::imageops::rotate180(&img)),
+    ] {
+        let output = temp_dir.path().join(format!("{}.png", command));
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args([command, input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success(), "{}", command);
+        assert_eq!(image::open(&output).unwrap().to_rgba8(), expected);
+    }
+}
+
 #[test]
 fn test_flip_horizontal() {
     let temp_dir = TempDir::new().unwrap();
@@ -510,6 +800,60 @@ fn test_resize_width_only_preserves_aspect() {
     assert_eq!(out_img.height(), 25); // Preserved 2:1 ratio
 }
 
+#[test]
+fn test_resize_keep_other_stretches() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    common::create_test_rgba_image(100, 50)
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "resize",
+            "--width",
+            "200",
+            "--keep-other",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let out_img = image::open(&output).unwrap();
+    assert_eq!((out_img.width(), out_img.height()), (200, 50));
+}
+
+#[test]
+fn test_resize_linear_light() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("stripes.png");
+    image::RgbImage::from_fn(64, 16, |x, _| {
+        let v = if x % 2 == 0 { 0 } else { 255 };
+        image::Rgb([v, v, v])
+    })
+    .save(&input)
+    .unwrap();
+
+    let center_after = |output: &str, extra: &[&str]| {
+        let output = temp_dir.path().join(output);
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["fit", "--max-width", "32"])
+            .args(extra)
+            .args([input.to_str().unwrap(), output.to_str().unwrap()])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success());
+        image::open(&output).unwrap().to_rgb8().get_pixel(16, 4)[0]
+    };
+
+    // Averaging encoded values gives mid-gray; averaging light gives ~188
+    assert!(center_after("naive.png", &[]).abs_diff(128) <= 1);
+    assert!(center_after("linear.png", &["--linear"]).abs_diff(188) <= 1);
+}
+
 #[test]
 fn test_resize_scale() {
     let temp_dir = TempDir::new().unwrap();
@@ -567,84 +911,122 @@ fn test_resize_with_filter() {
 }
 
 #[test]
-fn test_fit_within_bounds() {
+fn test_resize_pad_to_exact_dimensions() {
     let temp_dir = TempDir::new().unwrap();
     let input = temp_dir.path().join("input.png");
     let output = temp_dir.path().join("output.png");
 
-    // 200x100 image (2:1 ratio)
     let img = common::create_test_rgba_image(200, 100);
     img.save(&input).unwrap();
 
     let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
         .args([
-            "fit",
-            "--max-width",
+            "resize",
+            "--width",
             "100",
-            "--max-height",
+            "--height",
             "100",
+            "--pad",
+            "--background",
+            "black",
             input.to_str().unwrap(),
             output.to_str().unwrap(),
         ])
         .output()
         .expect("Failed to execute command");
 
-    assert!(result.status.success());
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
 
-    let out_img = image::open(&output).unwrap();
-    // Width is limiting factor, so 100x50
+    let out_img = image::open(&output).unwrap().to_rgba8();
     assert_eq!(out_img.width(), 100);
-    assert_eq!(out_img.height(), 50);
+    assert_eq!(out_img.height(), 100);
+
+    // Black bars top and bottom, content in the middle
+    assert_eq!(out_img.get_pixel(50, 5), &image::Rgba([0, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(50, 95), &image::Rgba([0, 0, 0, 255]));
+    assert_eq!(out_img.get_pixel(50, 50)[2], 128);
 }
 
 #[test]
-fn test_fit_no_upscale() {
+fn test_resize_large_upscale_warns() {
     let temp_dir = TempDir::new().unwrap();
     let input = temp_dir.path().join("input.png");
     let output = temp_dir.path().join("output.png");
 
-    // Small 50x50 image
-    let img = common::create_test_rgba_image(50, 50);
+    let img = common::create_test_rgba_image(10, 10);
     img.save(&input).unwrap();
 
     let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
         .args([
-            "fit",
-            "--max-width",
-            "100",
-            "--max-height",
-            "100",
+            "resize",
+            "--json",
+            "--scale",
+            "10",
             input.to_str().unwrap(),
             output.to_str().unwrap(),
         ])
         .output()
         .expect("Failed to execute command");
 
+    // Advisory only: the resize still succeeds
     assert!(result.status.success());
+    assert!(output.exists());
 
-    let out_img = image::open(&output).unwrap();
-    // Should not upscale
-    assert_eq!(out_img.width(), 50);
-    assert_eq!(out_img.height(), 50);
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Should be valid JSON");
+    assert_eq!(json["details"]["result_width"], 100);
+    assert_eq!(json["warnings"][0]["code"], "LARGE_UPSCALE");
 }
 
 #[test]
-fn test_fit_with_upscale() {
+fn test_resize_upscale_warning_after_max_dimension() {
     let temp_dir = TempDir::new().unwrap();
     let input = temp_dir.path().join("input.png");
     let output = temp_dir.path().join("output.png");
 
-    let img = common::create_test_rgba_image(50, 50);
+    common::create_test_rgba_image(100, 50)
+        .save(&input)
+        .unwrap();
+
+    // The cap brings the 10x resize back to the source size, so nothing is
+    // upscaled and the strict run succeeds
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "--fail-on-warning", "--max-dimension", "100"])
+        .args(["resize", "--width", "1000"])
+        .args([input.to_str().unwrap(), output.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["result_width"], 100);
+    assert_eq!(json["details"]["result_height"], 50);
+    assert!(json.get("warnings").is_none());
+}
+
+#[test]
+fn test_resize_moderate_upscale_no_warning() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(10, 10);
     img.save(&input).unwrap();
 
     let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
         .args([
-            "fit",
-            "--max-width",
-            "100",
-            "--max-height",
-            "100",
-            "--upscale",
+            "resize",
+            "--json",
+            "--scale",
+            "2",
             input.to_str().unwrap(),
             output.to_str().unwrap(),
         ])
@@ -653,46 +1035,311 @@ fn test_fit_with_upscale() {
 
     assert!(result.status.success());
 
-    let out_img = image::open(&output).unwrap();
-    // Should upscale to 100x100
-    assert_eq!(out_img.width(), 100);
-    assert_eq!(out_img.height(), 100);
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Should be valid JSON");
+    assert!(json.get("warnings").is_none());
 }
 
 #[test]
-fn test_overwrite_protection() {
+fn test_resize_lanczos_window() {
     let temp_dir = TempDir::new().unwrap();
     let input = temp_dir.path().join("input.png");
     let output = temp_dir.path().join("output.png");
 
     let img = common::create_test_rgba_image(100, 100);
     img.save(&input).unwrap();
-    img.save(&output).unwrap(); // Create existing output
 
-    // Without --overwrite, should fail
     let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
         .args([
-            "crop",
+            "resize",
             "--width",
-            "50",
-            "--height",
-            "50",
+            "40",
+            "--lanczos-a",
+            "2",
             input.to_str().unwrap(),
             output.to_str().unwrap(),
         ])
         .output()
         .expect("Failed to execute command");
 
-    assert!(!result.status.success());
+    assert!(result.status.success());
 
-    // With --overwrite, should succeed
+    let out_img = image::open(&output).unwrap();
+    assert_eq!(out_img.width(), 40);
+    assert_eq!(out_img.height(), 40);
+
+    // The window only applies to the Lanczos filter
     let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
         .args([
-            "crop",
+            "resize",
+            "-y",
             "--width",
+            "40",
+            "--lanczos-a",
+            "2",
+            "--filter",
+            "nearest",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(result.status.code(), Some(5));
+}
+
+#[test]
+fn test_seam_carve_reduces_width() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(100, 100);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "seam-carve",
+            "--width",
+            "80",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Should be valid JSON");
+    assert_eq!(json["command"], "seam-carve");
+    assert_eq!(json["details"]["result_width"], 80);
+    assert_eq!(json["details"]["result_height"], 100);
+
+    let out_img = image::open(&output).unwrap();
+    assert_eq!(out_img.width(), 80);
+    assert_eq!(out_img.height(), 100);
+}
+
+#[test]
+fn test_fit_within_bounds() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    // 200x100 image (2:1 ratio)
+    let img = common::create_test_rgba_image(200, 100);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "fit",
+            "--max-width",
+            "100",
+            "--max-height",
+            "100",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(result.status.success());
+
+    let out_img = image::open(&output).unwrap();
+    // Width is limiting factor, so 100x50
+    assert_eq!(out_img.width(), 100);
+    assert_eq!(out_img.height(), 50);
+}
+
+#[test]
+fn test_limit_longest_side() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    common::create_test_rgba_image(400, 200)
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "limit",
+            "--max",
+            "100",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let out_img = image::open(&output).unwrap();
+    assert_eq!((out_img.width(), out_img.height()), (100, 50));
+}
+
+#[test]
+fn test_fit_no_upscale() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    // Small 50x50 image
+    let img = common::create_test_rgba_image(50, 50);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "fit",
+            "--max-width",
+            "100",
+            "--max-height",
+            "100",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(result.status.success());
+
+    let out_img = image::open(&output).unwrap();
+    // Should not upscale
+    assert_eq!(out_img.width(), 50);
+    assert_eq!(out_img.height(), 50);
+}
+
+#[test]
+fn test_fit_with_upscale() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(50, 50);
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "fit",
+            "--max-width",
+            "100",
+            "--max-height",
+            "100",
+            "--upscale",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(result.status.success());
+
+    let out_img = image::open(&output).unwrap();
+    // Should upscale to 100x100
+    assert_eq!(out_img.width(), 100);
+    assert_eq!(out_img.height(), 100);
+}
+
+#[test]
+fn test_fit_mode_up_leaves_large_image() {
+    let temp_dir = TempDir::new().unwrap();
+    let small = temp_dir.path().join("small.png");
+    let large = temp_dir.path().join("large.png");
+    let small_out = temp_dir.path().join("small_out.png");
+    let large_out = temp_dir.path().join("large_out.png");
+
+    common::create_test_rgba_image(50, 50).save(&small).unwrap();
+    common::create_test_rgba_image(200, 200)
+        .save(&large)
+        .unwrap();
+
+    for (input, output) in [(&small, &small_out), (&large, &large_out)] {
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args([
+                "fit",
+                "--max-width",
+                "100",
+                "--max-height",
+                "100",
+                "--mode",
+                "up",
+                input.to_str().unwrap(),
+                output.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success());
+    }
+
+    // Small image is enlarged, large one untouched
+    assert_eq!(image::open(&small_out).unwrap().width(), 100);
+    assert_eq!(image::open(&large_out).unwrap().width(), 200);
+}
+
+#[test]
+fn test_fit_mode_conflicts_with_upscale() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    common::create_test_rgba_image(50, 50).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "fit",
+            "--max-width",
+            "100",
+            "--upscale",
+            "--mode",
+            "down",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!result.status.success());
+}
+
+#[test]
+fn test_overwrite_protection() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    let img = common::create_test_rgba_image(100, 100);
+    img.save(&input).unwrap();
+    img.save(&output).unwrap(); // Create existing output
+
+    // Without --overwrite, should fail
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "crop",
+            "--width",
+            "50",
+            "--height",
+            "50",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!result.status.success());
+
+    // With --overwrite, should succeed
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "crop",
+            "--width",
+            "50",
+            "--height",
             "50",
-            "--height",
-            "50",
             "--overwrite",
             input.to_str().unwrap(),
             output.to_str().unwrap(),
@@ -702,3 +1349,644 @@ fn test_overwrite_protection() {
 
     assert!(result.status.success());
 }
+
+#[test]
+fn test_interactive_without_terminal_refuses() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    let img = common::create_test_rgba_image(100, 100);
+    img.save(&input).unwrap();
+    img.save(&output).unwrap();
+
+    // Piped stdin is not a terminal, so even a "y" falls back to the error
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--interactive", "crop", "--width", "50", "--height", "50"])
+        .arg(&input)
+        .arg(&output)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    child.stdin.take().unwrap().write_all(b"y\n").unwrap();
+    let result = child.wait_with_output().unwrap();
+
+    assert_eq!(result.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    assert!(!stderr.contains("[y/N]"), "{}", stderr);
+    assert_eq!(image::open(&output).unwrap().width(), 100);
+}
+
+#[test]
+fn test_same_path_output_requires_overwrite() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("img.png");
+
+    common::create_test_rgba_image(100, 100)
+        .save(&path)
+        .unwrap();
+    let original = std::fs::read(&path).unwrap();
+
+    // Passing the input twice without -y is refused and leaves it untouched
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "crop",
+            "--width",
+            "50",
+            "--height",
+            "50",
+            path.to_str().unwrap(),
+            path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&result.stderr).contains("same file as INPUT"));
+    assert_eq!(std::fs::read(&path).unwrap(), original);
+
+    // With -y the edit goes through a temporary file and replaces the input
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "crop",
+            "--width",
+            "50",
+            "--height",
+            "50",
+            "-y",
+            path.to_str().unwrap(),
+            path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    let edited = image::open(&path).unwrap();
+    assert_eq!((edited.width(), edited.height()), (50, 50));
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+}
+
+#[test]
+fn test_preserve_timestamps_copies_input_mtime() {
+    use std::time::{Duration, SystemTime};
+
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    common::create_test_rgba_image(20, 20).save(&input).unwrap();
+
+    let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+    std::fs::File::options()
+        .write(true)
+        .open(&input)
+        .unwrap()
+        .set_times(
+            std::fs::FileTimes::new()
+                .set_modified(old)
+                .set_accessed(old),
+        )
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--preserve-timestamps",
+            "resize",
+            "--width",
+            "10",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let modified = std::fs::metadata(&output).unwrap().modified().unwrap();
+    let drift = modified
+        .duration_since(old)
+        .unwrap_or_else(|e| e.duration());
+    assert!(drift < Duration::from_secs(2), "drift {:?}", drift);
+}
+
+#[test]
+fn test_create_dirs_for_nested_output() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir
+        .path()
+        .join("out")
+        .join("nested")
+        .join("output.png");
+
+    common::create_test_rgba_image(100, 100)
+        .save(&input)
+        .unwrap();
+
+    let crop_args = [
+        "--json",
+        "crop",
+        "--width",
+        "50",
+        "--height",
+        "50",
+        input.to_str().unwrap(),
+        output.to_str().unwrap(),
+    ];
+
+    // Without --create-dirs the missing directory is named in the error
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(crop_args)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(3));
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["code"], "WRITE_ERROR");
+    assert!(json["error"].as_str().unwrap().contains("--create-dirs"));
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(crop_args)
+        .arg("--create-dirs")
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+    assert!(output.parent().unwrap().is_dir());
+    assert_eq!(image::open(&output).unwrap().width(), 50);
+}
+
+#[test]
+fn test_max_dimension_caps_upscaled_result() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    common::create_test_rgba_image(100, 50)
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "--max-dimension",
+            "300",
+            "resize",
+            "--scale",
+            "4",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    // 400x200 from the resize is brought back to 300x150
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["result_width"], 300);
+    assert_eq!(json["details"]["result_height"], 150);
+
+    let img = image::open(&output).unwrap();
+    assert_eq!((img.width(), img.height()), (300, 150));
+}
+
+#[test]
+fn test_resize_downscale_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    common::create_test_rgba_image(100, 50)
+        .save(&input)
+        .unwrap();
+
+    let run = |width: &str, name: &str| {
+        let output = temp_dir.path().join(name);
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args([
+                "--json",
+                "resize",
+                "--width",
+                width,
+                "--downscale-only",
+                input.to_str().unwrap(),
+                output.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success());
+        let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+        let img = image::open(&output).unwrap();
+        (json, (img.width(), img.height()))
+    };
+
+    // Enlarging is refused: the image is written unchanged
+    let (json, dim) = run("200", "up.png");
+    assert_eq!(json["details"]["skipped"], true);
+    assert_eq!(dim, (100, 50));
+
+    // Shrinking works as usual
+    let (json, dim) = run("50", "down.png");
+    assert_eq!(json["details"]["skipped"], false);
+    assert_eq!(dim, (50, 25));
+}
+
+#[test]
+fn test_smart_crop_centers_on_detail() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+
+    // Flat background with a striped patch in the bottom-left corner
+    let img = image::RgbaImage::from_fn(200, 100, |x, y| {
+        if x < 40 && y >= 60 && x % 4 < 2 {
+            image::Rgba([0, 0, 0, 255])
+        } else {
+            image::Rgba([200, 200, 200, 255])
+        }
+    });
+    img.save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "smart-crop",
+            "--width",
+            "50",
+            "--height",
+            "50",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{:?}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert!(json["details"]["x"].as_u64().unwrap() < 20);
+    assert_eq!(json["details"]["y"], 50);
+
+    let out_img = image::open(&output).unwrap();
+    assert_eq!((out_img.width(), out_img.height()), (50, 50));
+}
+
+#[test]
+fn test_resize_with_custom_filters() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    common::create_test_rgba_image(90, 60).save(&input).unwrap();
+
+    for filter in ["lanczos2", "mitchell", "area"] {
+        let output = temp_dir.path().join(format!("{}.png", filter));
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args([
+                "resize",
+                "--width",
+                "45",
+                "--filter",
+                filter,
+                input.to_str().unwrap(),
+                output.to_str().unwrap(),
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success(), "{}", filter);
+
+        let out_img = image::open(&output).unwrap();
+        assert_eq!((out_img.width(), out_img.height()), (45, 30));
+    }
+}
+
+#[test]
+fn test_resize_per_axis_filters() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    common::create_test_rgba_image(20, 10).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "resize",
+            "--width",
+            "40",
+            "--height",
+            "40",
+            "--filter",
+            "lanczos,nearest",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["parameters"]["filter"], "lanczos,nearest");
+    let out_img = image::open(&output).unwrap();
+    assert_eq!((out_img.width(), out_img.height()), (40, 40));
+
+    // More than two filters is rejected
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "resize",
+            "--width",
+            "40",
+            "--filter",
+            "lanczos,nearest,area",
+            "-y",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!result.status.success());
+}
+
+#[test]
+fn test_resize_auto_filter() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    let black = image::Rgba([0, 0, 0, 255]);
+    let white = image::Rgba([255, 255, 255, 255]);
+    image::RgbaImage::from_fn(6, 6, |x, _| if x < 3 { black } else { white })
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "resize",
+            "--scale",
+            "2",
+            "--filter",
+            "auto",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["parameters"]["filter"], "auto");
+    assert_eq!(json["details"]["resolved_filter"], "nearest");
+    let out = image::open(&output).unwrap().to_rgba8();
+    assert!(out.pixels().all(|p| *p == black || *p == white));
+}
+
+#[test]
+fn test_region_crops_then_resizes() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    common::create_test_rgba_image(100, 100)
+        .save(&input)
+        .unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "--json",
+            "region",
+            "--x",
+            "25",
+            "--y",
+            "25",
+            "--width",
+            "50",
+            "--height",
+            "50",
+            "--out-width",
+            "200",
+            "--out-height",
+            "200",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["command"], "region");
+    assert_eq!(json["parameters"]["out_width"], 200);
+
+    let out_img = image::open(&output).unwrap();
+    assert_eq!((out_img.width(), out_img.height()), (200, 200));
+
+    // A region outside the image is rejected
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "region",
+            "--x",
+            "80",
+            "--width",
+            "50",
+            "--height",
+            "50",
+            "--out-width",
+            "200",
+            "--out-height",
+            "200",
+            "-y",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!result.status.success());
+}
+
+#[test]
+fn test_rotate_bad_background_error_names_field() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    let output = temp_dir.path().join("output.png");
+    common::create_test_rgba_image(10, 10).save(&input).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args([
+            "rotate",
+            "--json",
+            "--degrees",
+            "30",
+            "--background",
+            "notacolor",
+            input.to_str().unwrap(),
+            output.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(5));
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stderr).unwrap();
+    assert_eq!(json["success"], false);
+    assert_eq!(json["code"], "INVALID_COLOR");
+    assert_eq!(json["field"], "background");
+}
+
+#[test]
+fn test_scale_modes_to_square() {
+    let temp_dir = TempDir::new().unwrap();
+    let input = temp_dir.path().join("input.png");
+    // Green down the left quarter, which only the cover crop drops
+    image::RgbaImage::from_fn(200, 100, |x, _| {
+        if x < 50 {
+            image::Rgba([0, 255, 0, 255])
+        } else {
+            image::Rgba([255, 0, 0, 255])
+        }
+    })
+    .save(&input)
+    .unwrap();
+
+    let scale = |mode: &str| {
+        let output = temp_dir.path().join(format!("{}.png", mode));
+        let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+            .args(["--json", "scale", "--width", "100", "--height", "100"])
+            .args([
+                "--mode",
+                mode,
+                "--filter",
+                "nearest",
+                "--background",
+                "blue",
+            ])
+            .arg(&input)
+            .arg(&output)
+            .output()
+            .expect("Failed to execute command");
+        assert!(result.status.success(), "{}", mode);
+        let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+        assert_eq!(json["parameters"]["mode"], mode);
+        image::open(&output).unwrap().to_rgba8()
+    };
+    let green = image::Rgba([0, 255, 0, 255]);
+    let red = image::Rgba([255, 0, 0, 255]);
+
+    let exact = scale("exact");
+    assert_eq!(exact.dimensions(), (100, 100));
+    assert_eq!(exact.get_pixel(5, 5), &green);
+
+    let fit = scale("fit");
+    assert_eq!(fit.dimensions(), (100, 50));
+    assert_eq!(fit.get_pixel(5, 25), &green);
+
+    let cover = scale("cover");
+    assert_eq!(cover.dimensions(), (100, 100));
+    assert_eq!(cover.get_pixel(5, 5), &red);
+
+    let pad = scale("pad");
+    assert_eq!(pad.dimensions(), (100, 100));
+    assert_eq!(pad.get_pixel(5, 5), &image::Rgba([0, 0, 255, 255]));
+    assert_eq!(pad.get_pixel(5, 50), &green);
+}
+
+#[test]
+fn test_batch_manifest_runs_each_job() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.png");
+    let second = temp_dir.path().join("second.png");
+    let resized = temp_dir.path().join("resized.png");
+    let gray = temp_dir.path().join("gray.png");
+    let flipped = temp_dir.path().join("flipped.png");
+    let manifest = temp_dir.path().join("jobs.json");
+    common::create_test_rgba_image(80, 40).save(&first).unwrap();
+    common::create_test_rgba_image(30, 30)
+        .save(&second)
+        .unwrap();
+
+    let jobs = serde_json::json!([
+        {"command": "resize", "args": ["--width", "40"], "input": first, "output": resized},
+        {"command": "grayscale", "input": second, "output": gray},
+    ]);
+    std::fs::write(&manifest, jobs.to_string()).unwrap();
+
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "batch", "--manifest"])
+        .arg(&manifest)
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        result.status.success(),
+        "{}",
+        String::from_utf8_lossy(&result.stderr)
+    );
+
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["details"]["succeeded"], 2);
+    assert_eq!(json["details"]["results"][0]["command"], "resize");
+    assert_eq!(json["details"]["results"][1]["success"], true);
+    // Each job's own details and warnings are kept in its result
+    assert_eq!(json["details"]["results"][0]["details"]["result_width"], 40);
+    assert!(json["details"]["results"][1].get("warnings").is_none());
+
+    let out = image::open(&resized).unwrap();
+    assert_eq!((out.width(), out.height()), (40, 20));
+    let out = image::open(&gray).unwrap().to_rgba8();
+    assert!(out.pixels().all(|p| p[0] == p[1] && p[1] == p[2]));
+
+    // The outputs now exist, so without --overwrite the first job fails; the
+    // second still runs and is reported
+    let jobs = serde_json::json!([
+        {"command": "resize", "args": ["--width", "40"], "input": first, "output": resized},
+        {"command": "flip", "args": ["--horizontal"], "input": second, "output": flipped},
+        {"command": "resize", "args": ["--bogus"], "input": first, "output": gray},
+    ]);
+    std::fs::write(&manifest, jobs.to_string()).unwrap();
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "batch", "--manifest"])
+        .arg(&manifest)
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(result.status.code(), Some(3));
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(json["success"], false);
+    assert_eq!(json["details"]["failed"], 2);
+    assert_eq!(json["details"]["results"][1]["success"], true);
+    assert_eq!(json["details"]["results"][2]["code"], "INVALID_PARAMETER");
+    assert!(flipped.exists());
+
+    // A translucent image saved as JPEG warns that its alpha is dropped
+    let translucent = temp_dir.path().join("translucent.png");
+    let jpeg = temp_dir.path().join("flipped.jpg");
+    image::RgbaImage::from_pixel(10, 10, image::Rgba([255, 0, 0, 128]))
+        .save(&translucent)
+        .unwrap();
+    let jobs = serde_json::json!([
+        {"command": "flip", "args": ["--vertical"], "input": translucent, "output": jpeg},
+    ]);
+    std::fs::write(&manifest, jobs.to_string()).unwrap();
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["--json", "batch", "--manifest"])
+        .arg(&manifest)
+        .output()
+        .expect("Failed to execute command");
+    assert!(result.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&result.stdout).unwrap();
+    assert_eq!(
+        json["details"]["results"][0]["warnings"][0]["code"],
+        "ALPHA_DISCARDED"
+    );
+
+    // Commands that only report are rejected up front, as their output
+    // would be lost
+    let jobs = serde_json::json!([{"command": "info", "input": second}]);
+    std::fs::write(&manifest, jobs.to_string()).unwrap();
+    let result = Command::new(env!("CARGO_BIN_EXE_mdimgedit"))
+        .args(["batch", "--manifest"])
+        .arg(&manifest)
+        .output()
+        .expect("Failed to execute command");
+    assert!(!result.status.success());
+    assert!(String::from_utf8_lossy(&result.stderr).contains("writes no file"));
+}